@@ -0,0 +1,77 @@
+//! Error types for the raytracer
+//!
+//! Operations on the scene that can fail return a `RaytracerError` instead of
+//! only printing a message, so that callers (the main loop, tests) can react
+//! to the failure. The main loop still prints these errors with the usual
+//! `Raytracer Err` and `Raytracer ~Err` prefixes.
+//!
+//! author:         Zhean Ganituen (zrygan)
+//! last updated:   April 18, 2025
+
+use std::fmt;
+
+/// The ways an operation on the scene can fail
+#[derive(Clone, Debug, PartialEq)]
+pub enum RaytracerError {
+    /// An index does not refer to an object in `OBJ_COLLECTION`
+    IndexOutOfBounds {
+        /// The index that was used
+        index: usize,
+        /// The number of objects in the collection
+        len: usize,
+    },
+    /// A lock on a global was poisoned by a thread that panicked while holding it
+    LockPoisoned(&'static str),
+    /// The scene already has `OBJC_MAX_OBJ_COUNT` objects
+    TooManyObjects(i32),
+    /// The scene would have more than `OBJC_MAX_TOTAL_RAYS` rays
+    TooManyRays(i32),
+    /// An emitter would have fewer than `OBJC_MIN_RAY_COUNT` rays
+    TooFewRays(i32),
+    /// The rays of a targeted emitter follow its targets, so their number
+    /// cannot be changed directly
+    FixedRays,
+    /// A parameter was set on an object that doesn't have it, see
+    /// `RaytracerObjects::set_param()`
+    ParamNotApplicable(&'static str),
+    /// A parameter was set to a value outside its bounds
+    ParamOutOfRange {
+        /// The name of the parameter
+        param: &'static str,
+        /// The values the parameter may take, e.g. "above 0"
+        bounds: &'static str,
+    },
+}
+
+impl fmt::Display for RaytracerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RaytracerError::IndexOutOfBounds { index, len } => write!(
+                f,
+                "index {} is out of bounds for {} object(s) in the scene",
+                index, len
+            ),
+            RaytracerError::LockPoisoned(name) => write!(f, "failed to get a lock on {}", name),
+            RaytracerError::TooManyObjects(max) => {
+                write!(f, "the scene is limited to {} objects", max)
+            }
+            RaytracerError::TooManyRays(max) => {
+                write!(f, "the scene is limited to {} rays in total", max)
+            }
+            RaytracerError::TooFewRays(min) => {
+                write!(f, "an emitter cannot have fewer than {} rays", min)
+            }
+            RaytracerError::FixedRays => {
+                write!(f, "a targeted emitter has one ray per target")
+            }
+            RaytracerError::ParamNotApplicable(param) => {
+                write!(f, "the object has no {}", param)
+            }
+            RaytracerError::ParamOutOfRange { param, bounds } => {
+                write!(f, "the {} must be {}", param, bounds)
+            }
+        }
+    }
+}
+
+impl std::error::Error for RaytracerError {}
@@ -0,0 +1,124 @@
+//! Access to the global object collection without handling its lock
+//!
+//! These functions take the read lock on `OBJ_COLLECTION` themselves, so
+//! callers that only look at the objects don't need to know how the
+//! collection is stored. Code that changes the collection still locks it
+//! directly, and calls `mark_absorbers_changed()` if absorbers may have been
+//! added, removed, moved, or resized.
+//!
+//! author:         Zhean Ganituen (zrygan)
+//! last updated:   April 18, 2025
+
+use std::collections::BTreeMap;
+
+use super::{ABSORBERS_GENERATION, OBJ_COLLECTION};
+use crate::helpers::action_utils::object_type_name;
+use crate::objects::behavior::RaytracerObjects;
+
+/// Calls a function with every object in the scene, in drawing order
+///
+/// # Arguments
+///
+/// * `f` - The function to call, it must not lock `OBJ_COLLECTION` for
+///   writing
+///
+/// # Thread Safety
+///
+/// This function holds a read lock on the `OBJ_COLLECTION` global while `f`
+/// runs.
+pub fn for_each_object(mut f: impl FnMut(&RaytracerObjects)) {
+    for obj in OBJ_COLLECTION.read().unwrap().iter() {
+        f(obj);
+    }
+}
+
+/// Collects a value from every object in the scene, in drawing order
+///
+/// The lock is released before returning, so the values can be used while
+/// the collection is changed.
+///
+/// # Arguments
+///
+/// * `f` - The function giving the value of an object, it must not lock
+///   `OBJ_COLLECTION` for writing
+///
+/// # Returns
+///
+/// The value of each object, in the same order as the collection
+///
+/// # Thread Safety
+///
+/// This function holds a read lock on the `OBJ_COLLECTION` global while `f`
+/// runs.
+pub fn map_objects<T>(f: impl FnMut(&RaytracerObjects) -> T) -> Vec<T> {
+    OBJ_COLLECTION.read().unwrap().iter().map(f).collect()
+}
+
+/// Records that the absorbers of the scene may have changed
+///
+/// Edits that only touch emitters or circles don't need to call this, which
+/// keeps the absorbers cached by `check_for_occlusion()` while an emitter is
+/// moved.
+///
+/// # Thread Safety
+///
+/// This function acquires a write lock on the `ABSORBERS_GENERATION` global.
+pub fn mark_absorbers_changed() {
+    *ABSORBERS_GENERATION.write().unwrap() += 1;
+}
+
+/// Counts the objects in the scene of each type
+///
+/// # Arguments
+///
+/// * `specify` - If true, the objects are counted by their concrete type
+///   (e.g. "Spotlight") instead of the general one (e.g. "Emitter"), see
+///   `object_type_name()`
+///
+/// # Returns
+///
+/// The number of objects of each type in the scene, types with no objects
+/// are left out
+///
+/// # Thread Safety
+///
+/// This function acquires a read lock on the `OBJ_COLLECTION` global.
+pub fn count_by_type(specify: bool) -> BTreeMap<&'static str, usize> {
+    count_types(&OBJ_COLLECTION.read().unwrap(), specify)
+}
+
+/// Counts the objects of each type in a list, see `count_by_type()`
+fn count_types(objects: &[RaytracerObjects], specify: bool) -> BTreeMap<&'static str, usize> {
+    let mut counts = BTreeMap::new();
+    for obj in objects {
+        *counts.entry(object_type_name(obj, specify)).or_insert(0) += 1;
+    }
+
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::objects::absorber::{AbsorberPerfect, Absorbers};
+    use crate::objects::circle::ObjectCircle;
+    use macroquad::color::WHITE;
+
+    #[test]
+    fn objects_are_counted_by_type() {
+        let circle = || ObjectCircle::new(0.0, 0.0, WHITE, 10.0);
+        let objects = [
+            RaytracerObjects::ObjectCircle(circle()),
+            RaytracerObjects::Absorbers(Absorbers::AbsorberPerfect(AbsorberPerfect::new(circle()))),
+            RaytracerObjects::ObjectCircle(circle()),
+        ];
+
+        let counts = count_types(&objects, true);
+        assert_eq!(counts.get("ObjectCircle"), Some(&2));
+        assert_eq!(counts.get("Perfect"), Some(&1));
+        assert_eq!(counts.len(), 2);
+
+        assert_eq!(count_types(&objects, false).get("Absorber"), Some(&1));
+        assert!(count_types(&[], false).is_empty());
+    }
+}
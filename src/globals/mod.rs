@@ -0,0 +1,482 @@
+//! Contains all global variables for the Raytracer project
+//!
+//! This module centralizes configuration constants and shared resources for the raytracer.
+//! It includes application metadata, window settings, visual defaults, keybindings,
+//! and object limitations used throughout the application.
+//!
+//! # Modules
+//!
+//! * `collection` - Functions for reading the object collection without handling its lock
+
+pub mod collection;
+
+use crate::objects::behavior::RaytracerObjects;
+use macroquad::input::KeyCode::{self};
+use macroquad::prelude::Color;
+use once_cell::sync::Lazy;
+use std::f32::consts::PI;
+use std::sync::RwLock;
+
+/// App Information (starts with the APP_ prefix)
+///
+/// These constants contain metadata about the application itself that may be
+/// displayed in window titles, about screens, or for identifying the application.
+pub const APP_NAME: &str = "Raytracer";
+pub const APP_VERSION: &str = "v0.rp";
+pub const APP_AUTHOR: &str = "Zhean Ganituen";
+pub const APP_GITHUB: &str = "https://github.com/zrygan/raytracer";
+pub const APP_EXPORT_RAYS_PATH: &str = "rays.json";
+pub const APP_IMPORT_CSV_PATH: &str = "scene.csv";
+/// The version of the CSV scene layout written by `save_csv()`, bump this
+/// whenever the meaning of the columns changes
+pub const APP_SCENE_VERSION: u32 = 3;
+pub const APP_PRESETS_DIR: &str = "presets";
+pub const APP_EXPORT_LIGHTMAP_PATH: &str = "lightmap.png";
+pub const APP_EXPORT_SVG_PATH: &str = "scene.svg";
+pub const APP_EXPORT_RENDER_PATH: &str = "render.png";
+pub const APP_EXPORT_RENDER_SUPERSAMPLE: u32 = 4; // times the window resolution, usually 2 or 4
+pub const APP_SCENE_LOG_MAX: usize = 1000; // operations kept in the scene log
+// pub const APP_ICON: Icon;
+
+/// Window Settings (starts with the WINDOW_ prefix)
+///
+/// These constants define the visual appearance and dimensions of the application window.
+pub const WINDOW_HEIGHT: i32 = 800;
+pub const WINDOW_WIDTH: i32 = 600;
+pub const WINDOW_USE_FRAME_RATE: bool = true;
+pub const WINDOW_FRAME_RATE: f32 = 1. / 45.;
+pub const WINDOW_AUTOTUNE_GAIN: f32 = 0.5; // ray count change per unit of frame time error
+pub const WINDOW_AUTOTUNE_DEADBAND: f32 = 0.1; // frame time errors smaller than this are ignored
+pub const WINDOW_CLAMP_ON_RESIZE: bool = true;
+pub const WINDOW_WRAP_ON_DRAG: bool = false; // starting value of WRAP_ON_DRAG
+pub const WINDOW_CLAMP_ON_DRAG: bool = true; // starting value of CLAMP_ON_DRAG
+pub const WINDOW_FIT_PADDING: f32 = 20.0; // space kept around the objects when framing them
+
+/// Standard Colors
+///
+/// Common colors used throughout the application for consistent visual styling.
+pub const CORNFLOWER_BLUE: Color = Color::new(0.39, 0.58, 0.92, 1.00);
+
+/// Macroquad Set Up (starts with the MACROQUAD_ prefix)
+///
+/// These constants configure the Macroquad rendering engine behavior including
+/// display quality options and window properties.
+pub const MACROQUAD_HIGH_DPI: bool = false;
+pub const MACROQUAD_FULLSCREEN: bool = false;
+pub const MACROQUAD_SAMPLE_COUNT: i32 = 10; // MSAA sample count
+pub const MACROQUAD_RESIZEABLE: bool = true;
+pub const MACROQUAD_SMOOTH_RAYS: bool = false; // soft-edged rays, slower than plain lines
+pub const MACROQUAD_BATCH_RAYS: bool = false; // draw each emitter's rays as one mesh
+
+/// Color Themes (starts with the THEME_ prefix)
+///
+/// A theme bundles the colors that depend on whether the background is dark or
+/// light. Objects take the theme's colors when they are created, so switching
+/// the theme does not recolor existing objects.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Theme {
+    /// Name of the theme, shown when switching
+    pub name: &'static str,
+    /// Color the window is cleared with every frame
+    pub background: Color,
+    /// Fill color of new circles and emitters, absorbers are filled with
+    /// `OBJD_ABSORBER_FILL` instead
+    pub circle_fill: Color,
+    /// Color of the rays of new emitters
+    pub ray_color: Color,
+    /// Color of the HUD text
+    pub hud_text: Color,
+}
+
+pub const THEME_DARK: Theme = Theme {
+    name: "dark",
+    background: Color::new(0.00, 0.00, 0.00, 1.00), // Black
+    circle_fill: CORNFLOWER_BLUE,
+    ray_color: Color::new(0.50, 0.50, 0.50, 1.00),
+    hud_text: Color::new(1.00, 1.00, 1.00, 1.00), // White
+};
+pub const THEME_LIGHT: Theme = Theme {
+    name: "light",
+    background: Color::new(0.95, 0.95, 0.93, 1.00), // Off-white
+    circle_fill: Color::new(0.15, 0.30, 0.65, 1.00), // Dark blue
+    ray_color: Color::new(0.35, 0.35, 0.35, 1.00),
+    hud_text: Color::new(0.00, 0.00, 0.00, 1.00), // Black
+};
+
+/// Raytracer Theme
+///
+/// The theme currently in use, switched at runtime between `THEME_DARK` and
+/// `THEME_LIGHT`.
+pub static THEME: RwLock<Theme> = RwLock::new(THEME_DARK);
+
+/// Raytracer Object Collection
+///
+/// Thread-safe global collection of all objects in the raytracer scene.
+/// Uses a `RwLock` so that many readers can look at the objects at once while
+/// changes get exclusive access. Code that only reads the objects should use
+/// the functions in `collection` instead of locking it directly.
+pub static OBJ_COLLECTION: Lazy<RwLock<Vec<RaytracerObjects>>> =
+    Lazy::new(|| RwLock::new(Vec::new()));
+
+/// Raytracer Absorber Generation
+///
+/// Counts the edits that may have changed the absorbers in `OBJ_COLLECTION`,
+/// see `collection::mark_absorbers_changed()`. `check_for_occlusion()` only
+/// copies the absorbers again when this has changed since its last pass.
+pub static ABSORBERS_GENERATION: RwLock<u64> = RwLock::new(0);
+
+/// Raytracer Ray Length Cap
+///
+/// The maximum length of every ray in the scene, adjustable at runtime.
+/// A value of 0 means rays extend to the edge of the screen.
+pub static RAY_MAX_LENGTH: RwLock<f32> = RwLock::new(OBJD_RAY_MAX_LENGTH);
+
+/// Raytracer Ray Draw Stride
+///
+/// Only every Nth ray of each emitter is drawn, adjustable at runtime to
+/// preview dense emitters quickly. Occlusion and every other calculation
+/// still use all the rays. A value of 1 draws every ray.
+pub static RAY_DRAW_STRIDE: RwLock<usize> = RwLock::new(OBJD_RAY_DRAW_STRIDE);
+
+/// Orientation Arrows Toggle
+///
+/// Debug view, when set, emitters with an orientation draw an arrow pointing
+/// along it. Toggled at runtime.
+pub static SHOW_ORIENTATION_ARROWS: RwLock<bool> = RwLock::new(false);
+
+/// Ray Endpoints Toggle
+///
+/// Debug view, when set, a dot is drawn where every ray ends, showing which
+/// rays were stopped by an absorber and which reached the window edge.
+/// Toggled at runtime.
+pub static SHOW_RAY_ENDPOINTS: RwLock<bool> = RwLock::new(false);
+
+/// Flowing Rays Toggle
+///
+/// When set, rays are drawn as dashes moving from their start to their end
+/// at `RAY_FLOW_SPEED`, showing which way the light travels. Toggled at
+/// runtime.
+pub static FLOWING_RAYS: RwLock<bool> = RwLock::new(false);
+
+/// Flowing Rays Speed
+///
+/// How fast the dashes of flowing rays move (in pixels per second),
+/// adjustable at runtime.
+pub static RAY_FLOW_SPEED: RwLock<f32> = RwLock::new(OBJD_RAY_FLOW_SPEED);
+
+/// Additive Rays Toggle
+///
+/// When set, emitters leave their rays out of `draw_object()` and the main
+/// loop draws every ray in one pass with additive blending, so overlapping
+/// rays add up in brightness. Toggled at runtime.
+pub static ADDITIVE_RAYS: RwLock<bool> = RwLock::new(false);
+
+/// Wrap On Drag Toggle
+///
+/// When set, an object dragged past a window edge reappears at the opposite
+/// edge. Starts as `WINDOW_WRAP_ON_DRAG` and is toggled at runtime.
+pub static WRAP_ON_DRAG: RwLock<bool> = RwLock::new(WINDOW_WRAP_ON_DRAG);
+
+/// Clamp On Drag Toggle
+///
+/// When set (and `WRAP_ON_DRAG` is not), a dragged object is kept fully inside
+/// the window. Starts as `WINDOW_CLAMP_ON_DRAG` and is toggled at runtime, so
+/// objects can still be placed off-screen on purpose.
+pub static CLAMP_ON_DRAG: RwLock<bool> = RwLock::new(WINDOW_CLAMP_ON_DRAG);
+
+/// Occlusion Toggle
+///
+/// When set, rays are truncated by absorbers. When not, they ignore absorbers
+/// and extend to their full length. Toggled at runtime.
+pub static OCCLUSION_ENABLED: RwLock<bool> = RwLock::new(true);
+
+/// Ray Intersections Toggle
+///
+/// When set, the points where rays cross each other are marked. Toggled at
+/// runtime, and turned off when there are too many rays to check.
+pub static SHOW_INTERSECTIONS: RwLock<bool> = RwLock::new(false);
+
+/// Shadows Toggle
+///
+/// When set, the shadow behind each absorber is filled in. Toggled at runtime.
+pub static SHOW_SHADOWS: RwLock<bool> = RwLock::new(false);
+
+/// Drag Collision Toggle
+///
+/// When set, dragged objects stop at contact with other objects. Toggled at
+/// runtime.
+pub static DRAG_COLLISION_ENABLED: RwLock<bool> = RwLock::new(false);
+
+/// Ray Count Tuning Toggle
+///
+/// When set, ray counts follow the frame time budget (`WINDOW_FRAME_RATE`).
+/// Toggled at runtime.
+pub static AUTOTUNE_ENABLED: RwLock<bool> = RwLock::new(false);
+
+/// Raytracer Object Constants (starts with the OBJC_ prefix)
+///
+/// These constants define limitations for object counts to prevent performance issues
+/// and memory overflow.
+pub const OBJC_MAX_OBJ_COUNT: i32 = 100;
+pub const OBJC_MAX_RAY_COUNT: i32 = 100;
+pub const OBJC_MIN_RAY_COUNT: i32 = 3;
+pub const OBJC_MAX_TOTAL_RAYS: i32 = 2000;
+pub const OBJC_MOUSE_EPSILON: f32 = 5.0;
+pub const OBJC_DRAG_THRESHOLD: f32 = 3.0; // in pixels
+pub const OBJC_CREATION_DRAG_MIN: f32 = 10.0; // in pixels, shorter drags create the default size
+pub const OBJC_CREATION_ANGLE_PER_PIXEL: f32 = PI / 180.0; // spotlight beam angle per pixel dragged
+pub const OBJC_SCALE_RAYS_WITH_SIZE: bool = false; // keep the ray density when resizing emitters
+pub const OBJC_FADE_IN_NEW_OBJECTS: bool = true; // new objects fade in over OBJD_FADE_IN_DURATION
+pub const OBJC_DRAG_COLLISION_ITERATIONS: usize = 8;
+pub const OBJC_DRAG_COLLISION_EPSILON: f32 = 1e-3; // overlaps smaller than this are contact
+pub const OBJC_MAX_INTERSECTION_RAYS: usize = 500;
+pub const OBJC_OCCLUSION_EARLY_EXIT_T: f32 = 1e-4;
+pub const OBJC_MAX_REFLECTIONS: usize = 8; // bounces per ray off mirrors
+pub const OBJC_OCCLUSION_EPSILON: f32 = 1e-4; // roots this close to the ray's start are at its start
+
+/// Raytracer Default Object Parameters (starts with OBJD_ prefix)
+///
+/// These constants define the default visual appearance and dimensions of
+/// raytracer objects when created.
+pub const OBJD_CIRCLE_RADIUS: f32 = 50.0;
+pub const OBJD_RAY_WIDTH: f32 = 1.0;
+pub const OBJD_RAY_FEATHER: f32 = 1.0; // soft edge on each side of smooth rays
+pub const OBJD_VISIBLE_WAVELENGTHS: (f32, f32) = (380.0, 780.0); // in nanometers
+pub const OBJD_RAY_COUNT: i32 = 32;
+pub const OBJD_RAY_MAX_LENGTH: f32 = 0.0; // 0 means extend to the screen edge
+pub const OBJD_RAY_DRAW_STRIDE: usize = 1; // draw every ray
+pub const OBJD_RAY_FLOW_SPEED: f32 = 60.0; // pixels per second
+pub const OBJD_RAY_FLOW_DASH: f32 = 12.0; // length of each dash of a flowing ray
+pub const OBJD_RAY_FLOW_GAP: f32 = 8.0; // space between the dashes of a flowing ray
+pub const OBJD_RAY_LENGTH_DELTA: f32 = 10.0;
+pub const OBJD_GOLDEN_ANGLE_RAYS: bool = false; // space isotropic rays by the golden angle
+pub const OBJD_DISABLED_ALPHA: f32 = 0.35; // fill opacity of switched off emitters
+pub const OBJD_COLLIMATED_BEAM_DIAMETER: f32 = 2.0 * OBJD_CIRCLE_RADIUS;
+pub const OBJD_COLLIMATED_ORIENTATION: f32 = 0.0; // in radians
+pub const OBJD_SPOTLIGHT_BEAM_ANGLE: f32 = PI / 3.0; // in radians
+pub const OBJD_SPOTLIGHT_ORIENTATION: f32 = 0.0; // in radians
+pub const OBJD_CONVERGENT_DISTANCE: f32 = 200.0; // from the emitter to its target
+pub const OBJD_CONVERGENT_ORIENTATION: f32 = 0.0; // in radians
+pub const OBJD_CONVERGENT_SPREAD: f32 = 0.05; // in radians
+pub const OBJD_TARGETED_OFFSETS: [(f32, f32); 3] = [(250.0, -100.0), (250.0, 0.0), (250.0, 100.0)]; // from the emitter
+pub const OBJD_ARC_START_ANGLE: f32 = PI / 2.0; // in radians
+pub const OBJD_ARC_END_ANGLE: f32 = 3.0 * PI / 2.0; // in radians
+pub const OBJD_ARC_THICKNESS: f32 = 4.0;
+pub const OBJD_MIRROR_RADIUS: f32 = 150.0; // the focal point is half of this from the mirror
+pub const OBJD_MIRROR_START_ANGLE: f32 = -PI / 4.0; // in radians, a quarter of the circle
+pub const OBJD_MIRROR_END_ANGLE: f32 = PI / 4.0; // facing left, towards new emitters
+pub const OBJD_MIRROR_COLOR: Color = Color::new(0.80, 0.85, 0.90, 1.00); // Silver
+pub const OBJD_ABSORBER_FILL: Color = Color::new(0.20, 0.20, 0.20, 1.00); // Charcoal, still visible on the dark theme
+pub const OBJD_SIZE_DELTA_FACTOR: f32 = 5.;
+pub const OBJD_ORIENTATION_DELTA_FACTOR: f32 = 0.01;
+pub const OBJD_PREVIEW_ALPHA: f32 = 0.35;
+pub const OBJD_FADE_IN_DURATION: f64 = 0.3; // in seconds
+pub const OBJD_SNAPSHOT_ALPHA: f32 = 0.25;
+pub const OBJD_IMAGE_SOURCE_ALPHA: f32 = 0.25;
+pub const OBJD_IMAGE_SOURCE_COLOR: Color = Color::new(0.80, 0.80, 1.00, 0.50);
+pub const OBJD_SELECTION_COLOR: Color = Color::new(1.00, 1.00, 1.00, 0.80);
+pub const OBJD_SELECTION_MARGIN: f32 = 4.0;
+pub const OBJD_SELECTION_THICKNESS: f32 = 2.0;
+pub const OBJD_HOVER_COLOR: Color = Color::new(1.00, 0.85, 0.20, 0.90); // Amber
+pub const OBJD_KEYBOARD_SELECTION_COLOR: Color = Color::new(0.40, 0.80, 1.00, 0.90); // Light blue
+pub const OBJD_BURIED_COLOR: Color = Color::new(1.00, 0.30, 0.30, 0.90); // Red, emitters inside an absorber
+pub const OBJD_ARROW_LENGTH: f32 = 30.0; // past the emitter's edge
+pub const OBJD_ARROW_THICKNESS: f32 = 2.0;
+pub const OBJD_ARROW_COLOR: Color = Color::new(1.00, 0.20, 0.80, 1.00); // Magenta
+pub const OBJD_SUN_RADIUS: f32 = 30.0;
+pub const OBJD_SUN_RAY_COUNT: i32 = OBJC_MAX_RAY_COUNT;
+pub const OBJD_SUN_COLOR: Color = Color::new(1.00, 0.80, 0.20, 1.00); // Warm yellow
+pub const OBJD_SUN_RAY_COLOR: Color = Color::new(1.00, 0.80, 0.20, 0.60);
+pub const OBJD_ECLIPSE_DISTANCE: f32 = 150.0; // from the sun to the absorber
+pub const OBJD_ECLIPSE_RADIUS: f32 = 25.0;
+pub const OBJD_LOCK_SIZE: f32 = 10.0;
+pub const OBJD_LOCK_THICKNESS: f32 = 2.0;
+pub const OBJD_LOCK_COLOR: Color = Color::new(1.00, 1.00, 1.00, 0.90);
+pub const OBJD_SHADOW_COLOR: Color = Color::new(0.30, 0.30, 0.60, 0.25); // Dim blue
+pub const OBJD_INTERSECTION_RADIUS: f32 = 3.0;
+pub const OBJD_INTERSECTION_COLOR: Color = Color::new(1.00, 0.85, 0.20, 1.00); // Amber
+pub const OBJD_RAY_ENDPOINT_RADIUS: f32 = 2.0;
+pub const OBJD_RAY_ENDPOINT_COLOR: Color = Color::new(1.00, 0.20, 0.60, 1.00); // Magenta
+
+/// Raytracer Keybinds (starts with KEYB_ prefix)
+///
+/// These constants map keyboard keys to specific actions in the raytracer,
+/// making it easy to modify keybindings from a central location.
+pub const KEYB_DELETE: KeyCode = KeyCode::Backspace;
+pub const KEYB_SIMPLE_CIRCLE: KeyCode = KeyCode::O;
+pub const KEYB_EMITTER_ISOTROPIC: KeyCode = KeyCode::I;
+pub const KEYB_EMITTER_COLLIMATED: KeyCode = KeyCode::C;
+pub const KEYB_EMITTER_SPOTLIGHT: KeyCode = KeyCode::S;
+pub const KEYB_EMITTER_CONVERGENT: KeyCode = KeyCode::V;
+pub const KEYB_EMITTER_TARGETED: KeyCode = KeyCode::Insert;
+pub const KEYB_ABSORBER_PERFECT: KeyCode = KeyCode::P;
+pub const KEYB_ABSORBER_ARC: KeyCode = KeyCode::A;
+pub const KEYB_MIRROR_ARC: KeyCode = KeyCode::F8;
+/// Every key that adds objects to the scene, including the presets
+pub const KEYB_CREATION_KEYS: [KeyCode; 11] = [
+    KEYB_SIMPLE_CIRCLE,
+    KEYB_EMITTER_ISOTROPIC,
+    KEYB_EMITTER_COLLIMATED,
+    KEYB_EMITTER_SPOTLIGHT,
+    KEYB_EMITTER_CONVERGENT,
+    KEYB_EMITTER_TARGETED,
+    KEYB_ABSORBER_PERFECT,
+    KEYB_ABSORBER_ARC,
+    KEYB_MIRROR_ARC,
+    KEYB_PRESET_SUN,
+    KEYB_PRESET_ECLIPSE,
+];
+pub const KEYB_TOGGLE_THEME: KeyCode = KeyCode::F9;
+pub const KEYB_DEBUG_SHOW_ALL_OBJ: KeyCode = KeyCode::Backslash;
+pub const KEYB_PRINT_CHANGES: KeyCode = KeyCode::Backslash; // with KEYB_PRINT_CHANGES_MODIFIER
+pub const KEYB_PRINT_CHANGES_MODIFIER: KeyCode = KeyCode::LeftControl;
+pub const KEYB_NUMERIC_ENTRY: KeyCode = KeyCode::N;
+pub const KEYB_SELECT_TOGGLE: KeyCode = KeyCode::Q;
+pub const KEYB_SELECT_CLEAR: KeyCode = KeyCode::Escape;
+pub const KEYB_SELECT_NEXT: KeyCode = KeyCode::Tab;
+pub const KEYB_TOGGLE_INTERSECTIONS: KeyCode = KeyCode::X;
+pub const KEYB_TOGGLE_RAY_ENDPOINTS: KeyCode = KeyCode::X; // with KEYB_RAY_ENDPOINTS_MODIFIER
+pub const KEYB_RAY_ENDPOINTS_MODIFIER: KeyCode = KeyCode::LeftControl;
+pub const KEYB_TOGGLE_OCCLUSION: KeyCode = KeyCode::U;
+pub const KEYB_TOGGLE_ORIENTATION_ARROWS: KeyCode = KeyCode::K;
+pub const KEYB_TOGGLE_RAY_FLOW: KeyCode = KeyCode::K; // with KEYB_RAY_FLOW_MODIFIER
+pub const KEYB_RAY_FLOW_MODIFIER: KeyCode = KeyCode::LeftControl;
+pub const KEYB_REPORT_COVERAGE: KeyCode = KeyCode::L;
+pub const KEYB_EXPORT_RAYS: KeyCode = KeyCode::J;
+pub const KEYB_IMPORT_CSV: KeyCode = KeyCode::Y;
+pub const KEYB_RESET_ALL: KeyCode = KeyCode::Home;
+pub const KEYB_FIT_TO_WINDOW: KeyCode = KeyCode::Z;
+pub const KEYB_RESTORE_DELETED: KeyCode = KeyCode::Z; // with KEYB_RESTORE_MODIFIER
+pub const KEYB_RESTORE_MODIFIER: KeyCode = KeyCode::LeftControl;
+pub const KEYB_BRING_TO_FRONT: KeyCode = KeyCode::PageUp;
+pub const KEYB_SEND_TO_BACK: KeyCode = KeyCode::PageDown;
+pub const KEYB_TOGGLE_DRAG_COLLISION: KeyCode = KeyCode::W;
+pub const KEYB_TOGGLE_DRAG_WRAP: KeyCode = KeyCode::W; // with KEYB_DRAG_WRAP_MODIFIER
+pub const KEYB_DRAG_WRAP_MODIFIER: KeyCode = KeyCode::LeftControl;
+pub const KEYB_TOGGLE_DRAG_CLAMP: KeyCode = KeyCode::B; // with KEYB_DRAG_CLAMP_MODIFIER
+pub const KEYB_DRAG_CLAMP_MODIFIER: KeyCode = KeyCode::LeftControl;
+pub const KEYB_TOGGLE_LOCK: KeyCode = KeyCode::H;
+pub const KEYB_TOGGLE_SHADOWS: KeyCode = KeyCode::D;
+pub const KEYB_GROUP: KeyCode = KeyCode::F2;
+pub const KEYB_UNGROUP: KeyCode = KeyCode::F3;
+pub const KEYB_TOGGLE_ADDITIVE_RAYS: KeyCode = KeyCode::F4;
+pub const KEYB_TOGGLE_LIGHTMAP: KeyCode = KeyCode::F6;
+pub const KEYB_CLEAR_LIGHTMAP: KeyCode = KeyCode::F7;
+pub const KEYB_EXPORT_LIGHTMAP: KeyCode = KeyCode::F10;
+pub const KEYB_TOGGLE_AUTOTUNE: KeyCode = KeyCode::F11;
+pub const KEYB_EXPORT_SVG: KeyCode = KeyCode::F12;
+pub const KEYB_EXPORT_RENDER: KeyCode = KeyCode::End;
+pub const KEYB_CONSOLE: KeyCode = KeyCode::GraveAccent;
+pub const KEYB_SAVE_PRESET_MODIFIER: KeyCode = KeyCode::LeftControl; // with a slot key
+pub const KEYB_PRESET_SLOTS: [KeyCode; 9] = [
+    KeyCode::Key1,
+    KeyCode::Key2,
+    KeyCode::Key3,
+    KeyCode::Key4,
+    KeyCode::Key5,
+    KeyCode::Key6,
+    KeyCode::Key7,
+    KeyCode::Key8,
+    KeyCode::Key9,
+];
+pub const KEYB_PRESET_SUN: KeyCode = KeyCode::B;
+pub const KEYB_PRESET_ECLIPSE: KeyCode = KeyCode::E;
+pub const KEYB_HELP: KeyCode = KeyCode::F1;
+pub const KEYB_HELP_ALT: KeyCode = KeyCode::Slash; // with Shift, i.e. `?`
+
+/// Raytracer Keybinds for Objects (starts with KEYB_RTC_ prefix)
+///
+/// These constants map keyboard keys to specific actions in raytracer when the
+/// user is hovering on a Raytracer object
+pub const KEYB_RTC_MULTIPLIER: i32 = 3;
+pub const KEYB_REPEAT_INTERVAL: f32 = 0.05; // seconds between repeats of a held adjustment key
+pub const KEYB_RTC_ENLARGE: KeyCode = KeyCode::Equal;
+pub const KEYB_RTC_SHRINK: KeyCode = KeyCode::Minus;
+pub const KEYB_RTC_INC_ORIENTATION: KeyCode = KeyCode::Left;
+pub const KEYB_RTC_DEC_ORIENTATION: KeyCode = KeyCode::Right;
+pub const KEYB_RTC_RESET_ORIENTATION: KeyCode = KeyCode::R;
+pub const KEYB_RTC_AIM_AT_CURSOR: KeyCode = KeyCode::R; // with KEYB_RTC_AIM_MODIFIER
+pub const KEYB_RTC_AIM_MODIFIER: KeyCode = KeyCode::LeftControl;
+pub const KEYB_RTC_ROTATE_DRAG: KeyCode = KeyCode::LeftControl; // held while dragging
+
+/// Raytracer Keybinds for Emitters (starts with KEYB_EMM_ prefix)
+///
+/// These constants map keyboard keys to specific actions in raytracer when the
+/// user is hovering on a Emitters type object
+pub const KEYB_EMM_INC_RAYS: KeyCode = KeyCode::RightBracket;
+pub const KEYB_EMM_DEC_RAYS: KeyCode = KeyCode::LeftBracket;
+pub const KEYB_EMM_CONVERT: KeyCode = KeyCode::T;
+pub const KEYB_EMM_TOGGLE_ENABLED: KeyCode = KeyCode::F5;
+pub const KEYB_EMM_SNAPSHOT_RAYS: KeyCode = KeyCode::F;
+pub const KEYB_CLEAR_SNAPSHOTS: KeyCode = KeyCode::G;
+pub const KEYB_IMAGE_SOURCE: KeyCode = KeyCode::M;
+pub const KEYB_MEASURE_ANGLE: KeyCode = KeyCode::M; // with KEYB_MEASURE_ANGLE_MODIFIER
+pub const KEYB_MEASURE_ANGLE_MODIFIER: KeyCode = KeyCode::LeftControl;
+pub const KEYB_RAY_LENGTH_INC: KeyCode = KeyCode::Period;
+pub const KEYB_RAY_LENGTH_DEC: KeyCode = KeyCode::Comma;
+
+/// Raytracer Secondary Keybinds and Delta for Emitters
+/// These constants map keyboard keys to specific actions in raytracer when the
+/// user is hovering on an (specifically) EmitterCollimated and EmitterSpotlight
+/// type object
+pub const KEYB_EMM_SEC_INC: KeyCode = KeyCode::Semicolon;
+pub const KEYB_EMM_SEC_DEC: KeyCode = KeyCode::Apostrophe;
+pub const KEYB_EMM_SEC_COLL_WIDTH_DELTA: i32 = 1;
+pub const KEYB_EMM_SEC_SPOT_ANGLE_DELTA: f32 = 0.01;
+
+/// Numeric Entry Overlay Settings (starts with NUMENTRY_ prefix)
+///
+/// These constants define the visual appearance of the overlay used to type
+/// exact values for object parameters.
+pub const NUMENTRY_FONT_SIZE: f32 = 20.0;
+pub const NUMENTRY_BG_COLOR: Color = Color::new(0.15, 0.15, 0.15, 0.90);
+pub const NUMENTRY_TEXT_COLOR: Color = Color::new(1.00, 1.00, 1.00, 1.00); // White
+
+/// Coverage Settings (starts with COVERAGE_ prefix)
+///
+/// These constants configure the grid used to measure how much of the window
+/// is illuminated by rays.
+pub const COVERAGE_CELL_SIZE: f32 = 10.0;
+
+/// Lightmap Settings (starts with LIGHTMAP_ prefix)
+///
+/// These constants configure the long exposure mode, where rays accumulate
+/// over time instead of being cleared every frame.
+pub const LIGHTMAP_EXPOSURE: f32 = 0.05; // fraction of a ray's alpha added per frame
+pub const LIGHTMAP_EXPORT_LEGEND: bool = true; // add an intensity scale below exported images
+pub const LIGHTMAP_LEGEND_HEIGHT: u16 = 16; // in pixels
+
+/// Command Console Settings (starts with CONSOLE_ prefix)
+///
+/// Settings for the command console opened with `KEYB_CONSOLE`.
+pub const CONSOLE_FONT_SIZE: f32 = 20.0;
+pub const CONSOLE_BG_COLOR: Color = Color::new(0.10, 0.10, 0.10, 0.90);
+pub const CONSOLE_TEXT_COLOR: Color = Color::new(1.00, 1.00, 1.00, 1.00); // White
+
+/// HUD Settings (starts with HUD_ prefix)
+///
+/// These constants define the visual appearance of the heads-up display drawn
+/// over the scene.
+pub const HUD_FONT_SIZE: f32 = 20.0;
+pub const HUD_POS_X: f32 = 10.0;
+pub const HUD_POS_Y: f32 = 45.0;
+pub const HUD_TOAST_FONT_SIZE: f32 = 18.0;
+pub const HUD_TOAST_DURATION: f64 = 2.0; // seconds for a toast to fade out
+pub const HUD_TOAST_OFFSET: (f32, f32) = (12.0, -12.0); // from where the toast was shown
+pub const HUD_TOAST_MARGIN: f32 = 10.0; // from the corner the toasts are stacked in
+pub const HUD_TOAST_MAX: usize = 5; // stacked toasts shown at once
+pub const HUD_MEASURE_THICKNESS: f32 = 1.5;
+pub const HUD_MEASURE_ARC_RADIUS: f32 = 30.0;
+pub const HUD_MEASURE_ARC_SEGMENTS: usize = 24;
+
+/// Help Overlay Settings (starts with HELP_ prefix)
+///
+/// These constants define the visual appearance of the keybind help overlay.
+pub const HELP_FONT_SIZE: f32 = 18.0;
+pub const HELP_BG_COLOR: Color = Color::new(0.00, 0.00, 0.00, 0.80);
+pub const HELP_TEXT_COLOR: Color = Color::new(1.00, 1.00, 1.00, 1.00); // White
+pub const HELP_MARGIN: f32 = 20.0;
+pub const HELP_KEY_COLUMN: f32 = 280.0;
+pub const HELP_COLUMN_WIDTH: f32 = 480.0; // the action and key columns of one entry
+pub const HELP_NEXT_PAGE: KeyCode = KeyCode::Right;
+pub const HELP_PREV_PAGE: KeyCode = KeyCode::Left;
@@ -12,12 +12,18 @@
 //! last updated:   April 18, 2025
 
 use crate::{
-    globals::{OBJ_COLLECTION, OBJC_MOUSE_EPSILON, OBJD_CIRCLE_RADIUS},
-    objects::{
-        absorber::Absorbers,
-        behavior::{RaytracerObjects, VariableSize},
-        emitters::*,
+    errors::RaytracerError,
+    globals::{
+        ADDITIVE_RAYS, AUTOTUNE_ENABLED, CLAMP_ON_DRAG, DRAG_COLLISION_ENABLED, FLOWING_RAYS,
+        OBJ_COLLECTION, OBJC_CREATION_DRAG_MIN, OBJC_DRAG_COLLISION_EPSILON,
+        OBJC_DRAG_COLLISION_ITERATIONS, OBJC_MOUSE_EPSILON, OBJD_RAY_DRAW_STRIDE,
+        OBJD_RAY_FLOW_SPEED, OBJD_RAY_MAX_LENGTH, OCCLUSION_ENABLED, RAY_DRAW_STRIDE,
+        RAY_FLOW_SPEED, RAY_MAX_LENGTH, SHOW_INTERSECTIONS, SHOW_ORIENTATION_ARROWS,
+        SHOW_RAY_ENDPOINTS, SHOW_SHADOWS, THEME, THEME_DARK, WINDOW_CLAMP_ON_DRAG,
+        WINDOW_WRAP_ON_DRAG, WRAP_ON_DRAG,
+        collection::{count_by_type, map_objects, mark_absorbers_changed},
     },
+    objects::{absorber::Absorbers, behavior::RaytracerObjects},
 };
 
 /// Removes an object from the scene at the specified index
@@ -34,26 +40,71 @@ use crate::{
 /// This function acquires a write lock on the `OBJ_COLLECTION` global,
 /// so it's safe to call from multiple threads.
 ///
+/// # Returns
+///
+/// * `Ok(RaytracerObjects)` - The removed object, so it can be put back (e.g.
+///   to undo the removal)
+/// * `Err(RaytracerError::IndexOutOfBounds)` - If there is no object at `index`
+/// * `Err(RaytracerError::LockPoisoned)` - If the write lock could not be acquired
+///
 /// # Examples
 ///
 /// ```
 /// // Remove the first object in the collection
-/// remove_object_at_index(0);
+/// remove_object_at_index(0)?;
 ///
 /// // Remove an object found at the cursor position
 /// if let Some(index) = object_at_cursor_index(mouse_x, mouse_y) {
-///     remove_object_at_index(index);
+///     remove_object_at_index(index)?;
 /// }
 /// ```
-pub fn remove_object_at_index(index: usize) {
-    let mut temp = OBJ_COLLECTION.write().unwrap();
-    if (index) < temp.len() {
-        temp.remove(index);
+pub fn remove_object_at_index(index: usize) -> Result<RaytracerObjects, RaytracerError> {
+    let mut collection = OBJ_COLLECTION
+        .write()
+        .map_err(|_| RaytracerError::LockPoisoned("OBJ_COLLECTION"))?;
+
+    if index < collection.len() {
+        mark_absorbers_changed();
+        Ok(collection.remove(index))
     } else {
-        eprintln!("Raytracer Err: Removing object at index is out of bounds.")
+        Err(RaytracerError::IndexOutOfBounds {
+            index,
+            len: collection.len(),
+        })
+    }
+}
+
+/// Checks whether the cursor is on an object
+///
+/// The cursor is on an object if its Euclidean distance to the object's center
+/// is at most the object's radius plus `OBJC_MOUSE_EPSILON`, the cursor's
+/// proximity tolerance.
+///
+/// # Arguments
+///
+/// * `object` - The object to test
+/// * `mouse_x` - The x-coordinate of the cursor
+/// * `mouse_y` - The y-coordinate of the cursor
+pub fn is_cursor_on_object(object: &RaytracerObjects, mouse_x: f32, mouse_y: f32) -> bool {
+    let ((x, y), rad) = get_object_scope(object);
+
+    match rad {
+        Some(r) => ((mouse_x - x).powi(2) + (mouse_y - y).powi(2)).sqrt() <= r + OBJC_MOUSE_EPSILON,
+        None => false,
     }
 }
 
+/// Finds the index of the first object in `objects` that the cursor is on
+///
+/// This is the hit test shared by `object_at_cursor_index` and
+/// `object_at_cursor_type`, so that both always agree about which object (if
+/// any) is under the cursor.
+pub fn object_index_at(objects: &[RaytracerObjects], mouse_x: f32, mouse_y: f32) -> Option<usize> {
+    objects
+        .iter()
+        .position(|object| is_cursor_on_object(object, mouse_x, mouse_y))
+}
+
 /// Finds the first object located at or near the specified cursor position
 ///
 /// This function checks all objects in the scene to find one that contains the
@@ -72,79 +123,448 @@ pub fn remove_object_at_index(index: usize) {
 /// # Selection Logic
 ///
 /// Objects are considered "at the cursor" if the distance between the cursor
-/// and object's center is less than `OBJC_MOUSE_EPSILON + OBJD_CIRCLE_RADIUS`,
+/// and object's center is at most `OBJC_MOUSE_EPSILON` plus the object's radius,
 /// which accounts for both the cursor's proximity tolerance and the object's size.
+/// See `is_cursor_on_object`.
 pub fn object_at_cursor_index(mouse_x: f32, mouse_y: f32) -> Option<usize> {
-    let temp = OBJ_COLLECTION.read().unwrap();
+    object_index_at(&OBJ_COLLECTION.read().unwrap(), mouse_x, mouse_y)
+}
+
+pub fn get_object_scope(object: &RaytracerObjects) -> ((f32, f32), Option<f32>) {
+    let params = object.params();
+    (params.position, Some(params.radius))
+}
+
+/// Moves a target position out of every object it would overlap
+///
+/// Used when dragging with collisions enabled. The object at `index` is
+/// placed at `target`, and for every other object whose circle it overlaps it
+/// is pushed straight away from that object until the two circles touch.
+/// Pushing out of one object can push into another, so this is repeated up to
+/// `OBJC_DRAG_COLLISION_ITERATIONS` times. If an overlap remains after that
+/// (e.g. the object is squeezed between two others), it stays where it is.
+///
+/// # Arguments
+///
+/// * `objects` - The objects in the scene
+/// * `index` - The index of the dragged object in `objects`
+/// * `target` - The position the object is being dragged to
+///
+/// # Returns
+///
+/// A tuple `(f32, f32)` of the position closest to `target` that does not
+/// overlap any other object
+pub fn stop_at_objects(
+    objects: &[RaytracerObjects],
+    index: usize,
+    target: (f32, f32),
+) -> (f32, f32) {
+    let Some(dragged) = objects.get(index) else {
+        return target;
+    };
+    let (current, radius) = get_object_scope(dragged);
+    let radius = radius.unwrap_or(0.0);
+
+    // the circles of every other object, (center, contact distance)
+    let others: Vec<((f32, f32), f32)> = objects
+        .iter()
+        .enumerate()
+        .filter(|(other_index, _)| *other_index != index)
+        .map(|(_, other)| {
+            let (center, other_radius) = get_object_scope(other);
+            (center, radius + other_radius.unwrap_or(0.0))
+        })
+        .collect();
+
+    let overlaps = |(x, y): (f32, f32), (cx, cy): (f32, f32), contact: f32| {
+        (x - cx).powi(2) + (y - cy).powi(2) < (contact - OBJC_DRAG_COLLISION_EPSILON).powi(2)
+    };
+
+    let mut position = target;
+    for _ in 0..OBJC_DRAG_COLLISION_ITERATIONS {
+        let mut pushed = false;
+
+        for &((cx, cy), contact) in &others {
+            if !overlaps(position, (cx, cy), contact) {
+                continue;
+            }
+
+            // push away from the other object's center, or from where the
+            // dragged object came from if both centers are on top of each other
+            let (mut dx, mut dy) = (position.0 - cx, position.1 - cy);
+            if dx == 0.0 && dy == 0.0 {
+                (dx, dy) = (current.0 - cx, current.1 - cy);
+            }
+            let distance = (dx.powi(2) + dy.powi(2)).sqrt();
+            let (nx, ny) = if distance > 0.0 {
+                (dx / distance, dy / distance)
+            } else {
+                (1.0, 0.0)
+            };
 
-    for (index, object) in temp.iter().enumerate() {
-        let (x, y) = object.get_pos();
+            position = (cx + nx * contact, cy + ny * contact);
+            pushed = true;
+        }
 
-        if (mouse_x - x).abs() < OBJC_MOUSE_EPSILON + OBJD_CIRCLE_RADIUS
-            && (mouse_y - y).abs() < OBJC_MOUSE_EPSILON + OBJD_CIRCLE_RADIUS
-        {
-            return Some(index);
+        if !pushed {
+            return position;
         }
     }
 
-    None
+    if others
+        .iter()
+        .any(|&(center, contact)| overlaps(position, center, contact))
+    {
+        current
+    } else {
+        position
+    }
 }
 
-pub fn get_object_scope(object: &RaytracerObjects) -> ((f32, f32), Option<f32>) {
-    let pos = object.get_pos();
-    let rad = match object {
-        RaytracerObjects::Absorbers(o) => Some(o.get_radius()),
-        RaytracerObjects::ObjectCircle(o) => Some(o.get_radius()),
-        RaytracerObjects::Emitters(o) => Some(o.get_radius()),
-    };
-
-    (pos, rad)
+/// Gets the name of an object's type
+///
+/// # Arguments
+///
+/// * `object` - The object to name
+/// * `specify` - If true, the concrete type is given (e.g. "Spotlight")
+///   instead of the general one (e.g. "Emitter")
+pub fn object_type_name(object: &RaytracerObjects, specify: bool) -> &'static str {
+    match object {
+        RaytracerObjects::ObjectCircle(_) => "ObjectCircle",
+        RaytracerObjects::Absorbers(absorber) => {
+            if specify {
+                match absorber {
+                    Absorbers::AbsorberPerfect(_) => "Perfect",
+                    Absorbers::AbsorberArc(_) => "Arc",
+                    Absorbers::MirrorArc(_) => "Mirror",
+                }
+            } else {
+                "Absorber"
+            }
+        }
+        RaytracerObjects::Emitters(emitter) => {
+            if specify {
+                emitter.type_name()
+            } else {
+                "Emitter"
+            }
+        }
+    }
 }
 
+/// Gets the type of the first object located at the specified cursor position
+///
+/// Uses the same hit test as `object_at_cursor_index`.
+///
+/// # Returns
+///
+/// The name of the object's type (see `object_type_name`), or "None" if there
+/// is no object at the cursor position
 pub fn object_at_cursor_type(mouse_x: f32, mouse_y: f32, specify: bool) -> &'static str {
     let temp = OBJ_COLLECTION.read().unwrap();
 
-    for object in temp.iter() {
-        let (pos, rad) = get_object_scope(object);
-        if let Some(r) = rad {
-            if (mouse_x - pos.0).abs() < OBJC_MOUSE_EPSILON + r
-                && (mouse_y - pos.1).abs() < OBJC_MOUSE_EPSILON + r
-            {
-                return match object {
-                    RaytracerObjects::ObjectCircle(_) => "ObjectCircle",
-                    RaytracerObjects::Absorbers(absorber) => {
-                        if specify {
-                            match absorber {
-                                Absorbers::AbsorberPerfect(_) => "Perfect",
-                            }
-                        } else {
-                            "Absorber"
-                        }
-                    }
-                    RaytracerObjects::Emitters(emitter) => {
-                        if specify {
-                            match emitter {
-                                Emitters::EmitterIsotropic(_) => "Isotropic",
-                                Emitters::EmitterCollimated(_) => "Collimated",
-                                Emitters::EmitterSpotlight(_) => "Spotlight",
-                            }
-                        } else {
-                            "Emitter"
-                        }
-                    }
-                };
-            }
-        }
+    match object_index_at(&temp, mouse_x, mouse_y) {
+        Some(index) => object_type_name(&temp[index], specify),
+        None => "None",
+    }
+}
+
+/// Gets the indices of the objects that an edit should apply to
+///
+/// If any objects are selected, the edit applies to all of them. Otherwise it
+/// applies to the keyboard selection wherever the cursor is, or to the object
+/// at the cursor position if there is no keyboard selection.
+///
+/// # Arguments
+///
+/// * `selected` - The indices of the selected objects
+/// * `selected_index` - The object picked with `KEYB_SELECT_NEXT`, if any
+/// * `mouse_x` - The x-coordinate of the cursor
+/// * `mouse_y` - The y-coordinate of the cursor
+pub fn edit_targets(
+    selected: &[usize],
+    selected_index: Option<usize>,
+    mouse_x: f32,
+    mouse_y: f32,
+) -> Vec<usize> {
+    if selected.is_empty() {
+        selected_index
+            .or_else(|| object_at_cursor_index(mouse_x, mouse_y))
+            .into_iter()
+            .collect()
+    } else {
+        selected.to_vec()
+    }
+}
+
+/// Gets the index that follows `current` when cycling through `len` objects
+///
+/// Cycling starts at the first object and wraps around after the last one.
+///
+/// # Returns
+///
+/// The next index, or `None` if there are no objects
+pub fn next_index(current: Option<usize>, len: usize) -> Option<usize> {
+    if len == 0 {
+        return None;
+    }
+
+    match current {
+        Some(index) => Some((index + 1) % len),
+        None => Some(0),
     }
+}
+
+/// Gets the index that precedes `current` when cycling through `len` objects
+///
+/// Cycling backward starts at the last object and wraps around after the
+/// first one.
+///
+/// # Returns
+///
+/// The previous index, or `None` if there are no objects
+pub fn previous_index(current: Option<usize>, len: usize) -> Option<usize> {
+    if len == 0 {
+        return None;
+    }
+
+    match current {
+        Some(index) => Some((index + len - 1) % len),
+        None => Some(len - 1),
+    }
+}
+
+/// Splits edit targets into the unlocked ones and the number of locked ones
+///
+/// Locked objects are skipped by every handler that edits objects, so this is
+/// applied to the result of `edit_targets()`. Indices of objects that no
+/// longer exist are dropped without being counted as locked.
+///
+/// # Arguments
+///
+/// * `objects` - The objects in the scene
+/// * `targets` - The indices of the objects to edit
+///
+/// # Returns
+///
+/// A tuple of the unlocked targets and how many targets were locked
+pub fn unlocked_targets(objects: &[RaytracerObjects], targets: Vec<usize>) -> (Vec<usize>, usize) {
+    let (locked, unlocked): (Vec<usize>, Vec<usize>) = targets
+        .into_iter()
+        .filter(|index| *index < objects.len())
+        .partition(|index| objects[*index].is_locked());
+
+    (unlocked, locked.len())
+}
 
-    "None"
+/// Gets an unused group id
+///
+/// # Arguments
+///
+/// * `objects` - The objects in the scene
+///
+/// # Returns
+///
+/// One more than the largest group id in use, or 1 if there are no groups
+pub fn next_group_id(objects: &[RaytracerObjects]) -> u32 {
+    objects
+        .iter()
+        .filter_map(|o| o.get_base_object().group_id)
+        .max()
+        .map_or(1, |id| id + 1)
+}
+
+/// Gets the other objects in the same group as an object
+///
+/// # Arguments
+///
+/// * `objects` - The objects in the scene
+/// * `index` - The index of the object in `objects`
+///
+/// # Returns
+///
+/// The indices of every other object with the same group id, empty if the
+/// object is not in a group
+pub fn group_members(objects: &[RaytracerObjects], index: usize) -> Vec<usize> {
+    let Some(group_id) = objects
+        .get(index)
+        .and_then(|o| o.get_base_object().group_id)
+    else {
+        return Vec::new();
+    };
+
+    objects
+        .iter()
+        .enumerate()
+        .filter(|(other, o)| *other != index && o.get_base_object().group_id == Some(group_id))
+        .map(|(other, _)| other)
+        .collect()
+}
+
+/// Gets the objects deleted along with an object
+///
+/// Deleting a member of a group deletes the whole group, except for its
+/// locked members, which stay where they are (as when the group is dragged).
+///
+/// # Arguments
+///
+/// * `objects` - The objects in the scene
+/// * `index` - The index of the object being deleted
+///
+/// # Returns
+///
+/// The indices of the object and its unlocked group members, highest first,
+/// so removing them in that order keeps the other indices valid
+pub fn group_deletion_order(objects: &[RaytracerObjects], index: usize) -> Vec<usize> {
+    let mut indices: Vec<usize> = group_members(objects, index)
+        .into_iter()
+        .filter(|member| !objects[*member].is_locked())
+        .collect();
+    indices.push(index);
+    indices.sort_unstable_by(|a, b| b.cmp(a));
+
+    indices
+}
+
+/// Takes the last deleted objects off the list of deleted objects
+///
+/// A group deleted at once is restored at once: along with the last deleted
+/// object, the objects deleted right before it from the same group are
+/// taken too (see `group_deletion_order()`).
+///
+/// # Arguments
+///
+/// * `deleted` - The deleted objects and the index each was at, oldest first
+///
+/// # Returns
+///
+/// The objects to restore and their indices, in the order to insert them,
+/// empty if nothing was deleted
+pub fn take_last_deleted(
+    deleted: &mut Vec<(usize, RaytracerObjects)>,
+) -> Vec<(usize, RaytracerObjects)> {
+    let Some(last) = deleted.pop() else {
+        return Vec::new();
+    };
+
+    let group_id = last.1.get_base_object().group_id;
+    let mut taken = vec![last];
+    while group_id.is_some()
+        && let Some(next) =
+            deleted.pop_if(|(_, object)| object.get_base_object().group_id == group_id)
+    {
+        taken.push(next);
+    }
+
+    taken
+}
+
+/// Updates a selection after the object at `removed_index` was removed
+///
+/// The removed object is deselected, and the indices after it are shifted
+/// down by one to match the collection.
+pub fn remove_from_selection(selected: &mut Vec<usize>, removed_index: usize) {
+    *selected = selected
+        .iter()
+        .filter_map(|index| index_after_removal(*index, removed_index))
+        .collect();
+}
+
+/// Gets the new index of an object after the object at `removed_index` was
+/// removed from the collection
+///
+/// # Returns
+///
+/// * `Some(usize)` - The object's new index
+/// * `None` - If the object itself was removed
+pub fn index_after_removal(index: usize, removed_index: usize) -> Option<usize> {
+    match index.cmp(&removed_index) {
+        std::cmp::Ordering::Less => Some(index),
+        std::cmp::Ordering::Equal => None,
+        std::cmp::Ordering::Greater => Some(index - 1),
+    }
+}
+
+/// Measures the drag that sizes an object while it is being created
+///
+/// # Arguments
+///
+/// * `origin` - Where the cursor was when the creation key was pressed
+/// * `cursor` - Where the cursor is now
+///
+/// # Returns
+///
+/// The distance dragged, or `None` if it is shorter than
+/// `OBJC_CREATION_DRAG_MIN` and the object keeps its default size
+pub fn creation_drag_size(origin: (f32, f32), cursor: (f32, f32)) -> Option<f32> {
+    let distance = ((cursor.0 - origin.0).powi(2) + (cursor.1 - origin.1).powi(2)).sqrt();
+    (distance >= OBJC_CREATION_DRAG_MIN).then_some(distance)
+}
+
+/// Gets the new index of an object after an object was inserted into the
+/// collection at `inserted_index`
+pub fn index_after_insertion(index: usize, inserted_index: usize) -> usize {
+    if index >= inserted_index {
+        index + 1
+    } else {
+        index
+    }
+}
+
+/// Gets the new index of an object after the object at `from` was moved to
+/// `to` (see `move_object_to_index()`)
+pub fn index_after_move(index: usize, from: usize, to: usize) -> usize {
+    if index == from {
+        to
+    } else if from < index && index <= to {
+        index - 1
+    } else if to <= index && index < from {
+        index + 1
+    } else {
+        index
+    }
+}
+
+/// Removes every object from the scene and restores the runtime settings
+///
+/// The runtime-adjustable globals (the ray length cap, the ray draw stride,
+/// the orientation arrows and ray endpoints toggles, the additive rays
+/// toggle, and the theme) are set back to their defaults from the `globals`
+/// module. State that lives in the main loop, such as toggles and the
+/// selection, must be reset by the caller.
+///
+/// # Thread Safety
+///
+/// This function acquires a write lock on `OBJ_COLLECTION`, `RAY_MAX_LENGTH`,
+/// `RAY_DRAW_STRIDE`, `SHOW_ORIENTATION_ARROWS`, `SHOW_RAY_ENDPOINTS`,
+/// `FLOWING_RAYS`, `RAY_FLOW_SPEED`, `ADDITIVE_RAYS`, `WRAP_ON_DRAG`,
+/// `CLAMP_ON_DRAG`, `THEME`, and `ABSORBERS_GENERATION`.
+pub fn reset_scene_to_defaults() {
+    OBJ_COLLECTION.write().unwrap().clear();
+    mark_absorbers_changed();
+    *RAY_MAX_LENGTH.write().unwrap() = OBJD_RAY_MAX_LENGTH;
+    *RAY_DRAW_STRIDE.write().unwrap() = OBJD_RAY_DRAW_STRIDE;
+    *SHOW_ORIENTATION_ARROWS.write().unwrap() = false;
+    *SHOW_RAY_ENDPOINTS.write().unwrap() = false;
+    *FLOWING_RAYS.write().unwrap() = false;
+    *RAY_FLOW_SPEED.write().unwrap() = OBJD_RAY_FLOW_SPEED;
+    *ADDITIVE_RAYS.write().unwrap() = false;
+    *WRAP_ON_DRAG.write().unwrap() = WINDOW_WRAP_ON_DRAG;
+    *CLAMP_ON_DRAG.write().unwrap() = WINDOW_CLAMP_ON_DRAG;
+    *OCCLUSION_ENABLED.write().unwrap() = true;
+    *SHOW_INTERSECTIONS.write().unwrap() = false;
+    *SHOW_SHADOWS.write().unwrap() = false;
+    *DRAG_COLLISION_ENABLED.write().unwrap() = false;
+    *AUTOTUNE_ENABLED.write().unwrap() = false;
+    *THEME.write().unwrap() = THEME_DARK;
 }
 
 /// Prints details of all objects in the scene to the console
 ///
 /// This function is primarily a debugging tool that outputs a formatted
 /// representation of every object in the global collection, including their
-/// indices and full Debug representations.
+/// indices and full Debug representations, followed by the number of objects
+/// of each type.
 ///
 /// # Usage
 ///
@@ -168,13 +588,174 @@ pub fn object_at_cursor_type(mouse_x: f32, mouse_y: f32, specify: bool) -> &'sta
 ///         EmitterIsotropic {
 ///             base_object: ObjectCircle { ... },
 ///             rays: [...],
+///             ray_width: 1.0,
+///             ray_color: Color { ... },
 ///         },
 ///     ),
 /// )
 /// ```
 pub fn print_all_objects() {
-    for (index, obj) in OBJ_COLLECTION.read().unwrap().iter().enumerate() {
+    // formatted first, so the collection isn't locked while printing
+    for (index, description) in map_objects(|obj| format!("{:#?}", obj)).iter().enumerate() {
         println!("RaytracerObject: {}", index);
-        println!("{:#?}", obj);
+        println!("{}", description);
+    }
+
+    let counts: Vec<String> = count_by_type(true)
+        .iter()
+        .map(|(type_name, count)| format!("{} {}", count, type_name))
+        .collect();
+    println!("Raytracer Debug: Object counts: {}", counts.join(", "));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::objects::circle::ObjectCircle;
+    use macroquad::color::WHITE;
+
+    fn circle(pos_x: f32, pos_y: f32, radius: f32) -> RaytracerObjects {
+        RaytracerObjects::ObjectCircle(ObjectCircle::new(pos_x, pos_y, WHITE, radius))
+    }
+
+    #[test]
+    fn short_creation_drags_keep_the_default_size() {
+        assert_eq!(creation_drag_size((10.0, 10.0), (13.0, 14.0)), None);
+        assert_eq!(creation_drag_size((10.0, 10.0), (40.0, 50.0)), Some(50.0));
+    }
+
+    #[test]
+    fn indices_follow_insertions_and_moves() {
+        assert_eq!(index_after_insertion(1, 2), 1);
+        assert_eq!(index_after_insertion(2, 2), 3);
+
+        // moving 1 to 3 in [a, b, c, d] gives [a, c, d, b]
+        let moved: Vec<usize> = (0..4).map(|i| index_after_move(i, 1, 3)).collect();
+        assert_eq!(moved, vec![0, 3, 1, 2]);
+        // moving 3 to 0 in [a, b, c, d] gives [d, a, b, c]
+        let moved: Vec<usize> = (0..4).map(|i| index_after_move(i, 3, 0)).collect();
+        assert_eq!(moved, vec![1, 2, 3, 0]);
+    }
+
+    #[test]
+    fn locked_objects_are_left_out_of_edits() {
+        let mut objects = vec![circle(0.0, 0.0, 10.0), circle(50.0, 0.0, 10.0)];
+        objects[1].get_base_object_mut().locked = true;
+
+        assert_eq!(unlocked_targets(&objects, vec![0, 1]), (vec![0], 1));
+        assert_eq!(unlocked_targets(&objects, vec![1]), (vec![], 1));
+        // indices past the end are dropped without counting as locked
+        assert_eq!(unlocked_targets(&objects, vec![0, 5]), (vec![0], 0));
+    }
+
+    #[test]
+    fn dragging_stops_at_contact_distance() {
+        let objects = vec![circle(100.0, 100.0, 20.0), circle(200.0, 100.0, 30.0)];
+
+        // dragged into the second circle, it stops touching it
+        let (x, y) = stop_at_objects(&objects, 0, (190.0, 100.0));
+        assert!((x - 150.0).abs() < 1e-3 && (y - 100.0).abs() < 1e-3);
+
+        // far enough away, the target is kept
+        assert_eq!(stop_at_objects(&objects, 0, (100.0, 300.0)), (100.0, 300.0));
+
+        // dropped right on the other center, it is pushed back the way it came
+        let (x, _) = stop_at_objects(&objects, 0, (200.0, 100.0));
+        assert!((x - 150.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn group_members_share_an_id() {
+        let mut objects = vec![
+            circle(0.0, 0.0, 10.0),
+            circle(50.0, 0.0, 10.0),
+            circle(100.0, 0.0, 10.0),
+        ];
+        assert_eq!(next_group_id(&objects), 1);
+        assert!(group_members(&objects, 0).is_empty());
+
+        objects[0].get_base_object_mut().group_id = Some(1);
+        objects[2].get_base_object_mut().group_id = Some(1);
+        assert_eq!(next_group_id(&objects), 2);
+        assert_eq!(group_members(&objects, 0), vec![2]);
+        assert!(group_members(&objects, 1).is_empty());
+    }
+
+    #[test]
+    fn groups_are_deleted_and_restored_together() {
+        let mut objects = vec![
+            circle(0.0, 0.0, 10.0),
+            circle(1.0, 0.0, 10.0),
+            circle(2.0, 0.0, 10.0),
+            circle(3.0, 0.0, 10.0),
+        ];
+        for index in [0, 2, 3] {
+            objects[index].get_base_object_mut().group_id = Some(1);
+        }
+        objects[3].get_base_object_mut().locked = true;
+
+        // the locked member stays
+        assert_eq!(group_deletion_order(&objects, 0), vec![2, 0]);
+        assert_eq!(group_deletion_order(&objects, 1), vec![1]);
+
+        // delete the ungrouped object, then the group
+        let mut deleted = vec![(1, objects.remove(1))];
+        for index in group_deletion_order(&objects, 0) {
+            deleted.push((index, objects.remove(index)));
+        }
+        assert_eq!(objects.len(), 1);
+
+        let restored: Vec<f32> = take_last_deleted(&mut deleted)
+            .into_iter()
+            .map(|(index, object)| {
+                objects.insert(index, object);
+                objects[index].get_pos().0
+            })
+            .collect();
+        assert_eq!(restored, vec![0.0, 2.0]);
+        assert_eq!(take_last_deleted(&mut deleted).len(), 1);
+        assert!(take_last_deleted(&mut deleted).is_empty());
+    }
+
+    #[test]
+    fn nearby_objects_are_selected_consistently() {
+        let objects = vec![circle(100.0, 100.0, 50.0), circle(170.0, 100.0, 50.0)];
+
+        // between both centers, the first object wins
+        assert_eq!(object_index_at(&objects, 140.0, 100.0), Some(0));
+
+        // inside the first object's bounding box but outside its radius
+        assert!(!is_cursor_on_object(&objects[0], 145.0, 145.0));
+        assert_eq!(object_index_at(&objects, 145.0, 145.0), Some(1));
+
+        // outside of both
+        assert_eq!(object_index_at(&objects, 100.0, 300.0), None);
+    }
+
+    #[test]
+    fn cycling_wraps_around() {
+        assert_eq!(next_index(None, 3), Some(0));
+        assert_eq!(next_index(Some(1), 3), Some(2));
+        assert_eq!(next_index(Some(2), 3), Some(0));
+        assert_eq!(next_index(Some(0), 0), None);
+
+        assert_eq!(previous_index(None, 3), Some(2));
+        assert_eq!(previous_index(Some(0), 3), Some(2));
+        assert_eq!(previous_index(Some(2), 3), Some(1));
+        assert_eq!(previous_index(None, 0), None);
+    }
+
+    #[test]
+    fn selection_uses_each_objects_radius() {
+        let objects = vec![circle(100.0, 100.0, 10.0)];
+
+        assert_eq!(
+            object_index_at(&objects, 100.0 + 10.0 + OBJC_MOUSE_EPSILON, 100.0),
+            Some(0)
+        );
+        assert_eq!(
+            object_index_at(&objects, 100.0 + 11.0 + OBJC_MOUSE_EPSILON, 100.0),
+            None
+        );
     }
 }
@@ -0,0 +1,125 @@
+//! Utility functions for measuring how much of the scene is lit
+//!
+//! This module rasterizes the rays of every emitter into a coarse coverage
+//! grid laid over the window, which gives a rough metric of how much of the
+//! window is illuminated. It is meant to be used after occlusion so that the
+//! area behind absorbers is not counted. How much of each emitter's light is
+//! blocked by absorbers is counted by occlusion too.
+//!
+//! author:         Zhean Ganituen (zrygan)
+//! last updated:   April 18, 2025
+
+use crate::globals::{COVERAGE_CELL_SIZE, OBJ_COLLECTION, collection::for_each_object};
+use crate::objects::behavior::RaytracerObjects;
+use crate::objects::ray::ObjectRay;
+use macroquad::window::{screen_height, screen_width};
+
+/// A grid of cells over the window that records which cells a ray touched
+pub struct CoverageGrid {
+    /// Number of columns in the grid
+    pub columns: usize,
+    /// Number of rows in the grid
+    pub rows: usize,
+    /// Size of each (square) cell in pixels
+    pub cell_size: f32,
+    /// Whether each cell was touched, stored row by row
+    pub cells: Vec<bool>,
+}
+
+impl CoverageGrid {
+    /// Creates an empty grid covering a `width` by `height` area
+    pub fn new(width: f32, height: f32, cell_size: f32) -> CoverageGrid {
+        let columns = (width / cell_size).ceil().max(1.0) as usize;
+        let rows = (height / cell_size).ceil().max(1.0) as usize;
+
+        CoverageGrid {
+            columns,
+            rows,
+            cell_size,
+            cells: vec![false; columns * rows],
+        }
+    }
+
+    /// Marks every cell that the ray passes through
+    ///
+    /// The ray is sampled every half cell so that no cell along its path is
+    /// skipped. Samples outside of the grid are ignored.
+    pub fn rasterize_ray(&mut self, ray: &ObjectRay) {
+        let (dx, dy) = (ray.end_x - ray.start_x, ray.end_y - ray.start_y);
+        let length = (dx.powi(2) + dy.powi(2)).sqrt();
+        let steps = (length / (self.cell_size / 2.0)).ceil() as usize;
+
+        for step in 0..=steps {
+            let t = if steps == 0 {
+                0.0
+            } else {
+                step as f32 / steps as f32
+            };
+            let (x, y) = (ray.start_x + t * dx, ray.start_y + t * dy);
+
+            if x < 0.0 || y < 0.0 {
+                continue;
+            }
+
+            let (column, row) = ((x / self.cell_size) as usize, (y / self.cell_size) as usize);
+            if column < self.columns && row < self.rows {
+                self.cells[row * self.columns + column] = true;
+            }
+        }
+    }
+
+    /// Gets the fraction (from 0 to 1) of cells that were touched
+    pub fn touched_fraction(&self) -> f32 {
+        let touched = self.cells.iter().filter(|c| **c).count();
+        touched as f32 / self.cells.len() as f32
+    }
+}
+
+/// Computes the fraction of the window that is lit by at least one ray
+///
+/// Every ray of every emitter in the scene is rasterized into a
+/// `CoverageGrid` with cells of `COVERAGE_CELL_SIZE` pixels.
+///
+/// # Thread Safety
+///
+/// This function acquires a read lock on the `OBJ_COLLECTION` global
+/// (through `for_each_object()`).
+///
+/// # Returns
+///
+/// The fraction (from 0 to 1) of grid cells touched by at least one ray
+pub fn illuminated_fraction() -> f32 {
+    let mut grid = CoverageGrid::new(screen_width(), screen_height(), COVERAGE_CELL_SIZE);
+
+    for_each_object(|obj| {
+        if let RaytracerObjects::Emitters(emitter) = obj
+            && emitter.is_enabled()
+        {
+            for ray in emitter.get_rays() {
+                grid.rasterize_ray(ray);
+            }
+        }
+    });
+
+    grid.touched_fraction()
+}
+
+/// Gets the fraction of an emitter's rays that were blocked by an absorber
+///
+/// See `Emitters::blocked_fraction()`. This should be called after
+/// `check_for_occlusion()`, which counts the blocked rays.
+///
+/// # Arguments
+///
+/// * `index` - The index of the emitter in `OBJ_COLLECTION`
+///
+/// # Returns
+///
+/// The fraction (from 0 to 1) of the emitter's rays whose light ended on an
+/// absorber, 0 if the object at `index` is not an emitter
+pub fn emitter_blocked_fraction(index: usize) -> f32 {
+    match OBJ_COLLECTION.read().unwrap().get(index) {
+        Some(RaytracerObjects::Emitters(emitter)) => emitter.blocked_fraction(),
+        _ => 0.0,
+    }
+}
@@ -0,0 +1,494 @@
+//! Utility functions for exporting scene data
+//!
+//! This module writes the geometry of the scene to files so that it can be
+//! analyzed with external tools. The output is written by hand to avoid adding
+//! a serialization dependency. The light accumulated by a long exposure can
+//! also be saved as an image, and so can the scene itself, rendered offscreen
+//! at a higher resolution than the window. The keybinds and runtime settings
+//! can be written out too, to share or document a configuration.
+//!
+//! author:         Zhean Ganituen (zrygan)
+//! last updated:   April 18, 2025
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+use macroquad::camera::{Camera2D, set_camera, set_default_camera};
+use macroquad::math::Rect;
+use macroquad::texture::{Image, render_target_msaa};
+use macroquad::window::{clear_background, screen_height, screen_width};
+
+use crate::globals::{
+    ADDITIVE_RAYS, AUTOTUNE_ENABLED, CLAMP_ON_DRAG, DRAG_COLLISION_ENABLED, FLOWING_RAYS,
+    KEYB_EMM_SEC_COLL_WIDTH_DELTA, KEYB_EMM_SEC_SPOT_ANGLE_DELTA, LIGHTMAP_EXPORT_LEGEND,
+    LIGHTMAP_LEGEND_HEIGHT, OBJ_COLLECTION, OBJD_ORIENTATION_DELTA_FACTOR, OBJD_RAY_LENGTH_DELTA,
+    OBJD_SIZE_DELTA_FACTOR, OCCLUSION_ENABLED, RAY_DRAW_STRIDE, RAY_FLOW_SPEED, RAY_MAX_LENGTH,
+    SHOW_INTERSECTIONS, SHOW_ORIENTATION_ARROWS, SHOW_RAY_ENDPOINTS, SHOW_SHADOWS, THEME,
+    WRAP_ON_DRAG, collection::for_each_object,
+};
+use crate::helpers::lightmap_utils::Lightmap;
+use crate::objects::behavior::{Drawable, RaytracerObjects};
+use crate::user_input::help_overlay::keybind_help;
+
+/// Writes the rays of every emitter in the scene to a JSON file
+///
+/// The file contains an array with one entry per emitter. Each entry has the
+/// emitter's index in `OBJ_COLLECTION`, its type, and its rays with their start
+/// and end coordinates, thickness, and color (as `[r, g, b, a]`).
+///
+/// This should be called after `check_for_occlusion()` so that the end points
+/// of the rays are the truncated ones.
+///
+/// # Arguments
+///
+/// * `path` - The path of the JSON file to write
+///
+/// # Returns
+///
+/// * `Ok(())` - If the file was written
+/// * `Err(std::io::Error)` - If the file could not be created or written
+///
+/// # Example Output
+///
+/// ```text
+/// [
+///   {"index": 0, "type": "Isotropic", "rays": [
+///     {"start": [100, 100], "end": [400, 100], "thickness": 1, "color": [0.5, 0.5, 0.5, 1]}
+///   ]}
+/// ]
+/// ```
+pub fn export_rays_json(path: &str) -> std::io::Result<()> {
+    let mut file = BufWriter::new(File::create(path)?);
+    let collection = OBJ_COLLECTION.read().unwrap();
+
+    let emitters = collection
+        .iter()
+        .enumerate()
+        .filter_map(|(index, obj)| match obj {
+            RaytracerObjects::Emitters(e) => Some((index, e)),
+            _ => None,
+        });
+
+    writeln!(file, "[")?;
+    for (count, (index, emitter)) in emitters.enumerate() {
+        if count > 0 {
+            writeln!(file, ",")?;
+        }
+
+        writeln!(
+            file,
+            "  {{\"index\": {}, \"type\": \"{}\", \"rays\": [",
+            index,
+            emitter.type_name()
+        )?;
+
+        let rays = emitter.get_rays();
+        for (ray_index, ray) in rays.iter().enumerate() {
+            write!(
+                file,
+                "    {{\"start\": [{}, {}], \"end\": [{}, {}], \"thickness\": {}, \"color\": [{}, {}, {}, {}]}}",
+                ray.start_x,
+                ray.start_y,
+                ray.end_x,
+                ray.end_y,
+                ray.thickness,
+                ray.color.r,
+                ray.color.g,
+                ray.color.b,
+                ray.color.a
+            )?;
+            writeln!(
+                file,
+                "{}",
+                if ray_index + 1 < rays.len() { "," } else { "" }
+            )?;
+        }
+
+        write!(file, "  ]}}")?;
+    }
+    writeln!(file, "\n]")?;
+
+    file.flush()
+}
+
+/// Writes the keybinds and the current runtime settings to a JSON file
+///
+/// The keybinds are the ones shown in the help overlay (see
+/// `keybind_help()`). The settings are the values of the runtime toggles
+/// (such as `FLOWING_RAYS` and `OCCLUSION_ENABLED`) as they are now, along with the fixed step sizes
+/// of the keys that adjust objects.
+///
+/// # Arguments
+///
+/// * `path` - The path of the JSON file to write
+///
+/// # Returns
+///
+/// * `Ok(())` - If the file was written
+/// * `Err(std::io::Error)` - If the file could not be created or written
+///
+/// # Example Output
+///
+/// ```text
+/// {
+///   "keybinds": [
+///     {"action": "Create a simple circle", "keys": "O"}
+///   ],
+///   "settings": {
+///     "theme": "Dark",
+///     "flowing_rays": false
+///   }
+/// }
+/// ```
+pub fn export_settings_json(path: &str) -> std::io::Result<()> {
+    let settings = [
+        ("theme", json_string(THEME.read().unwrap().name)),
+        ("ray_max_length", RAY_MAX_LENGTH.read().unwrap().to_string()),
+        (
+            "ray_draw_stride",
+            RAY_DRAW_STRIDE.read().unwrap().to_string(),
+        ),
+        (
+            "show_orientation_arrows",
+            SHOW_ORIENTATION_ARROWS.read().unwrap().to_string(),
+        ),
+        (
+            "show_ray_endpoints",
+            SHOW_RAY_ENDPOINTS.read().unwrap().to_string(),
+        ),
+        ("flowing_rays", FLOWING_RAYS.read().unwrap().to_string()),
+        ("ray_flow_speed", RAY_FLOW_SPEED.read().unwrap().to_string()),
+        ("additive_rays", ADDITIVE_RAYS.read().unwrap().to_string()),
+        ("wrap_on_drag", WRAP_ON_DRAG.read().unwrap().to_string()),
+        ("clamp_on_drag", CLAMP_ON_DRAG.read().unwrap().to_string()),
+        ("occlusion", OCCLUSION_ENABLED.read().unwrap().to_string()),
+        (
+            "show_intersections",
+            SHOW_INTERSECTIONS.read().unwrap().to_string(),
+        ),
+        ("show_shadows", SHOW_SHADOWS.read().unwrap().to_string()),
+        (
+            "drag_collision",
+            DRAG_COLLISION_ENABLED.read().unwrap().to_string(),
+        ),
+        ("autotune", AUTOTUNE_ENABLED.read().unwrap().to_string()),
+        ("ray_length_delta", OBJD_RAY_LENGTH_DELTA.to_string()),
+        ("size_delta", OBJD_SIZE_DELTA_FACTOR.to_string()),
+        (
+            "orientation_delta",
+            OBJD_ORIENTATION_DELTA_FACTOR.to_string(),
+        ),
+        (
+            "collimated_width_delta",
+            KEYB_EMM_SEC_COLL_WIDTH_DELTA.to_string(),
+        ),
+        (
+            "spotlight_angle_delta",
+            KEYB_EMM_SEC_SPOT_ANGLE_DELTA.to_string(),
+        ),
+    ];
+
+    let mut file = BufWriter::new(File::create(path)?);
+    file.write_all(settings_json(&keybind_help(), &settings).as_bytes())?;
+    file.flush()
+}
+
+/// Formats keybinds and settings as the JSON written by
+/// `export_settings_json()`
+///
+/// # Arguments
+///
+/// * `keybinds` - `(action, keys)` pairs
+/// * `settings` - `(name, value)` pairs, the values already formatted as JSON
+fn settings_json(keybinds: &[(&str, String)], settings: &[(&str, String)]) -> String {
+    let keybinds: Vec<String> = keybinds
+        .iter()
+        .map(|(action, keys)| {
+            format!(
+                "    {{\"action\": {}, \"keys\": {}}}",
+                json_string(action),
+                json_string(keys)
+            )
+        })
+        .collect();
+    let settings: Vec<String> = settings
+        .iter()
+        .map(|(name, value)| format!("    {}: {}", json_string(name), value))
+        .collect();
+
+    format!(
+        "{{\n  \"keybinds\": [\n{}\n  ],\n  \"settings\": {{\n{}\n  }}\n}}\n",
+        keybinds.join(",\n"),
+        settings.join(",\n")
+    )
+}
+
+/// Quotes text as a JSON string, escaping quotes and backslashes
+fn json_string(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Saves the light accumulated by a long exposure as a PNG image
+///
+/// Only the light is saved, on a transparent background, so the image can be
+/// composited over something else. Each pixel has the color of the rays that
+/// lit it, and its alpha is how much light it gathered, from 0 (never lit) to
+/// 1 (fully exposed). If `LIGHTMAP_EXPORT_LEGEND` is set, a strip showing
+/// that scale is added below the image (see `with_intensity_legend()`).
+///
+/// # Arguments
+///
+/// * `lightmap` - The long exposure to save
+/// * `path` - The path of the PNG file to write
+///
+/// # Returns
+///
+/// * `Ok(())` - If the image was written
+/// * `Err(std::io::Error)` - If the file could not be created
+pub fn export_lightmap_png(lightmap: &Lightmap, path: &str) -> std::io::Result<()> {
+    // `export_png()` panics instead of returning errors, so make sure the file
+    // can be written first
+    File::create(path)?;
+
+    let image = lightmap.image();
+    let image = if LIGHTMAP_EXPORT_LEGEND {
+        with_intensity_legend(image, LIGHTMAP_LEGEND_HEIGHT)
+    } else {
+        image
+    };
+    image.export_png(path);
+
+    Ok(())
+}
+
+/// Saves a high quality render of the scene as a PNG image
+///
+/// The background, rays, and objects are drawn into an offscreen render
+/// target (with MSAA) that is `supersample` times the window size in each
+/// direction, then averaged back down to the window size. Thin rays and the
+/// edges of objects come out much smoother than in the live view, which is
+/// left untouched. The selection outlines, previews, and HUD are not part of
+/// the scene, so they are not drawn. With additive blending on, the rays are
+/// drawn normally.
+///
+/// This should be called after `check_for_occlusion()` so that the rays are
+/// truncated.
+///
+/// # Arguments
+///
+/// * `path` - The path of the PNG file to write
+/// * `supersample` - How many times larger than the window to render, usually
+///   2 or 4 (0 is treated as 1)
+///
+/// # Returns
+///
+/// * `Ok(())` - If the image was written
+/// * `Err(std::io::Error)` - If the file could not be created
+///
+/// # Thread Safety
+///
+/// This function acquires read locks on the `OBJ_COLLECTION` and `THEME`
+/// globals.
+pub fn export_render_png(path: &str, supersample: u32) -> std::io::Result<()> {
+    // `export_png()` panics instead of returning errors, so make sure the file
+    // can be written first
+    File::create(path)?;
+
+    let supersample = supersample.max(1);
+    let (width, height) = (screen_width(), screen_height());
+    let target = render_target_msaa(width as u32 * supersample, height as u32 * supersample);
+
+    // the same coordinates as the window, stretched over the larger target
+    let mut camera = Camera2D::from_display_rect(Rect::new(0.0, 0.0, width, height));
+    camera.render_target = Some(target.clone());
+    set_camera(&camera);
+
+    clear_background(THEME.read().unwrap().background);
+    let additive_rays = *ADDITIVE_RAYS.read().unwrap();
+    for_each_object(|obj| match obj {
+        RaytracerObjects::ObjectCircle(object) => object.draw_object(),
+        RaytracerObjects::Emitters(object) => {
+            // emitters leave their rays out while additive blending is on
+            if additive_rays {
+                object.draw_rays();
+            }
+            object.draw_object();
+        }
+        RaytracerObjects::Absorbers(object) => object.draw_object(),
+    });
+
+    // switching cameras flushes the draw calls, so the target is complete
+    set_default_camera();
+
+    downsample(&target.texture.get_texture_data(), supersample).export_png(path);
+
+    Ok(())
+}
+
+/// Shrinks an image by averaging blocks of pixels
+///
+/// Every `factor` by `factor` block of pixels becomes one pixel with the
+/// average of their channels. Pixels left over at the right and top edges,
+/// when the size is not a multiple of `factor`, are dropped.
+///
+/// # Arguments
+///
+/// * `image` - The image to shrink
+/// * `factor` - How many times smaller the result is in each direction
+///
+/// # Returns
+///
+/// The shrunk image, or a copy of `image` if `factor` is 0 or 1
+pub fn downsample(image: &Image, factor: u32) -> Image {
+    if factor <= 1 {
+        return image.clone();
+    }
+
+    let factor = factor as usize;
+    let source_width = image.width as usize;
+    let (width, height) = (source_width / factor, image.height as usize / factor);
+    let mut bytes = Vec::with_capacity(width * height * 4);
+
+    for y in 0..height {
+        for x in 0..width {
+            let mut sum = [0u32; 4];
+            for block_y in 0..factor {
+                let row = (y * factor + block_y) * source_width;
+                for block_x in 0..factor {
+                    let start = (row + x * factor + block_x) * 4;
+                    for (channel, total) in sum.iter_mut().enumerate() {
+                        *total += image.bytes[start + channel] as u32;
+                    }
+                }
+            }
+
+            let count = (factor * factor) as u32;
+            bytes.extend(sum.map(|total| ((total + count / 2) / count) as u8));
+        }
+    }
+
+    Image {
+        bytes,
+        width: width as u16,
+        height: height as u16,
+    }
+}
+
+/// Adds an intensity scale below an image
+///
+/// The scale is a white strip whose alpha goes from 0 at the left edge to 1 at
+/// the right edge, matching how the alpha of a lightmap pixel maps to the light
+/// it gathered.
+///
+/// # Arguments
+///
+/// * `image` - The image, with its rows stored bottom to top
+/// * `height` - The height of the strip in pixels
+///
+/// # Returns
+///
+/// The image with the strip as its bottom rows
+pub fn with_intensity_legend(image: Image, height: u16) -> Image {
+    let width = image.width as usize;
+    let mut row = Vec::with_capacity(width * 4);
+    for x in 0..width {
+        let intensity = x as f32 / (width.max(2) - 1) as f32;
+        row.extend_from_slice(&[255, 255, 255, (intensity * 255.0).round() as u8]);
+    }
+
+    // the rows are stored bottom to top, so the strip comes first
+    let mut bytes = row.repeat(height as usize);
+    bytes.extend_from_slice(&image.bytes);
+
+    Image {
+        bytes,
+        width: image.width,
+        height: image.height + height,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn settings_are_written_as_json() {
+        let keybinds = [
+            ("Delete object", "Backspace".to_string()),
+            ("Print all objects", "\\".to_string()),
+        ];
+        let settings = [
+            ("theme", json_string("Dark")),
+            ("ray_draw_stride", 1.to_string()),
+        ];
+
+        assert_eq!(
+            settings_json(&keybinds, &settings),
+            concat!(
+                "{\n",
+                "  \"keybinds\": [\n",
+                "    {\"action\": \"Delete object\", \"keys\": \"Backspace\"},\n",
+                "    {\"action\": \"Print all objects\", \"keys\": \"\\\\\"}\n",
+                "  ],\n",
+                "  \"settings\": {\n",
+                "    \"theme\": \"Dark\",\n",
+                "    \"ray_draw_stride\": 1\n",
+                "  }\n",
+                "}\n"
+            )
+        );
+        assert_eq!(json_string("say \"hi\""), "\"say \\\"hi\\\"\"");
+    }
+
+    #[test]
+    fn legend_is_added_below_the_image() {
+        let image = Image {
+            bytes: vec![10; 3 * 2 * 4],
+            width: 3,
+            height: 2,
+        };
+        let legend = with_intensity_legend(image, 1);
+
+        assert_eq!((legend.width, legend.height), (3, 3));
+        // the first stored row is the bottom one, the legend
+        assert_eq!(
+            &legend.bytes[..12],
+            &[255, 255, 255, 0, 255, 255, 255, 128, 255, 255, 255, 255]
+        );
+        assert!(legend.bytes[12..].iter().all(|b| *b == 10));
+    }
+
+    #[test]
+    fn downsample_averages_blocks() {
+        // a 4x2 image, the left 2x2 block alternates black and white, the
+        // right one is a solid gray
+        let mut bytes = Vec::new();
+        for _ in 0..2 {
+            bytes.extend_from_slice(&[0, 0, 0, 255, 255, 255, 255, 255]);
+            bytes.extend_from_slice(&[100, 100, 100, 255, 100, 100, 100, 255]);
+        }
+        let image = Image {
+            bytes,
+            width: 4,
+            height: 2,
+        };
+        let small = downsample(&image, 2);
+
+        assert_eq!((small.width, small.height), (2, 1));
+        assert_eq!(small.bytes, vec![128, 128, 128, 255, 100, 100, 100, 255]);
+    }
+
+    #[test]
+    fn downsample_drops_partial_blocks() {
+        let image = Image {
+            bytes: vec![50; 5 * 3 * 4],
+            width: 5,
+            height: 3,
+        };
+        let small = downsample(&image, 2);
+
+        assert_eq!((small.width, small.height), (2, 1));
+        assert!(small.bytes.iter().all(|b| *b == 50));
+        assert_eq!(downsample(&image, 1).bytes, image.bytes);
+    }
+}
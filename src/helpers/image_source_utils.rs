@@ -0,0 +1,150 @@
+//! Utility functions for visualizing the image source of an emitter
+//!
+//! In the image source method, a reflection off a flat mirror is drawn as if
+//! it came from a phantom copy of the emitter mirrored across the wall. This
+//! module builds that phantom copy for teaching purposes, it does not reflect
+//! any rays in the simulation.
+//!
+//! Absorbers are circles, so the mirror wall is taken to be the line tangent to
+//! the absorber at the point nearest to the emitter.
+//!
+//! author:         Zhean Ganituen (zrygan)
+//! last updated:   April 18, 2025
+
+use crate::globals::{OBJ_COLLECTION, OBJD_IMAGE_SOURCE_ALPHA};
+use crate::objects::behavior::RaytracerObjects;
+use crate::objects::ray::ObjectRay;
+
+/// A flat mirror, given as a point on it and its unit normal
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MirrorLine {
+    /// A point on the mirror
+    pub point: (f32, f32),
+    /// The unit normal of the mirror
+    pub normal: (f32, f32),
+}
+
+impl MirrorLine {
+    /// Gets the mirror tangent to a circle, facing the given point
+    ///
+    /// # Arguments
+    ///
+    /// * `center` - The center of the circle
+    /// * `radius` - The radius of the circle
+    /// * `facing` - The point the mirror faces (e.g. the emitter's center)
+    ///
+    /// # Returns
+    ///
+    /// * `Some(MirrorLine)` - The tangent line nearest to `facing`
+    /// * `None` - If `facing` is at the center, where there is no nearest point
+    pub fn tangent_to_circle(
+        center: (f32, f32),
+        radius: f32,
+        facing: (f32, f32),
+    ) -> Option<MirrorLine> {
+        let (dx, dy) = (facing.0 - center.0, facing.1 - center.1);
+        let distance = (dx.powi(2) + dy.powi(2)).sqrt();
+        if distance == 0.0 {
+            return None;
+        }
+
+        let normal = (dx / distance, dy / distance);
+        Some(MirrorLine {
+            point: (center.0 + radius * normal.0, center.1 + radius * normal.1),
+            normal,
+        })
+    }
+
+    /// Reflects a point across the mirror
+    pub fn reflect(&self, (x, y): (f32, f32)) -> (f32, f32) {
+        let distance = (x - self.point.0) * self.normal.0 + (y - self.point.1) * self.normal.1;
+        (
+            x - 2.0 * distance * self.normal.0,
+            y - 2.0 * distance * self.normal.1,
+        )
+    }
+}
+
+/// Builds the image source of an emitter mirrored across an absorber
+///
+/// Every ray of the emitter is reflected across the mirror, so the phantom rays
+/// come from the image source and pass through the points where the real rays
+/// meet the mirror. The phantom rays are drawn with `OBJD_IMAGE_SOURCE_ALPHA`.
+///
+/// # Arguments
+///
+/// * `emitter_index` - The index of the emitter in `OBJ_COLLECTION`
+/// * `absorber_index` - The index of the absorber used as the mirror
+///
+/// # Thread Safety
+///
+/// This function acquires a read lock on the `OBJ_COLLECTION` global.
+///
+/// # Returns
+///
+/// * `Some((position, rays))` - The image source's position and phantom rays
+/// * `None` - If the indices are not an emitter and an absorber, or the
+///   emitter is at the absorber's center
+pub fn image_source(
+    emitter_index: usize,
+    absorber_index: usize,
+) -> Option<((f32, f32), Vec<ObjectRay>)> {
+    let collection = OBJ_COLLECTION.read().unwrap();
+    let (Some(RaytracerObjects::Emitters(emitter)), Some(RaytracerObjects::Absorbers(absorber))) = (
+        collection.get(emitter_index),
+        collection.get(absorber_index),
+    ) else {
+        return None;
+    };
+
+    let base_object = absorber.get_base_object();
+    let emitter_pos = (
+        emitter.get_base_object().pos_x,
+        emitter.get_base_object().pos_y,
+    );
+    let mirror = MirrorLine::tangent_to_circle(
+        (base_object.pos_x, base_object.pos_y),
+        base_object.radius,
+        emitter_pos,
+    )?;
+
+    let rays = emitter
+        .get_rays()
+        .iter()
+        .map(|ray| {
+            let (start_x, start_y) = mirror.reflect((ray.start_x, ray.start_y));
+            let (end_x, end_y) = mirror.reflect((ray.end_x, ray.end_y));
+            let mut color = ray.color;
+            color.a = OBJD_IMAGE_SOURCE_ALPHA;
+
+            ObjectRay::new(start_x, start_y, end_x, end_y, ray.thickness, color)
+        })
+        .collect();
+
+    Some((mirror.reflect(emitter_pos), rays))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn image_is_mirrored_across_tangent() {
+        // circle at the origin with radius 10, emitter 30 to its right
+        let mirror = MirrorLine::tangent_to_circle((0.0, 0.0), 10.0, (30.0, 0.0)).unwrap();
+        assert_eq!(mirror.point, (10.0, 0.0));
+
+        // the emitter is 20 in front of the mirror, so its image is 20 behind
+        assert_eq!(mirror.reflect((30.0, 0.0)), (-10.0, 0.0));
+        // points on the mirror stay in place
+        assert_eq!(mirror.reflect((10.0, 5.0)), (10.0, 5.0));
+    }
+
+    #[test]
+    fn emitter_at_center_has_no_mirror() {
+        assert_eq!(
+            MirrorLine::tangent_to_circle((5.0, 5.0), 10.0, (5.0, 5.0)),
+            None
+        );
+    }
+}
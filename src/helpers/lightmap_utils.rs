@@ -0,0 +1,120 @@
+//! Utility functions for accumulating rays into a long exposure
+//!
+//! Normally the screen is cleared every frame, so only the current rays are
+//! visible. A `Lightmap` keeps an off-screen texture that is never cleared,
+//! and every frame the rays are drawn into it faintly. Rays that stay in place
+//! build up to full brightness, while dragging an emitter around leaves light
+//! trails behind it (a "light painting" effect).
+//!
+//! author:         Zhean Ganituen (zrygan)
+//! last updated:   April 18, 2025
+
+use crate::globals::{LIGHTMAP_EXPOSURE, collection::for_each_object};
+use crate::objects::behavior::RaytracerObjects;
+use macroquad::camera::{Camera2D, set_camera, set_default_camera};
+use macroquad::color::{BLANK, WHITE};
+use macroquad::math::{Rect, vec2};
+use macroquad::shapes::draw_line;
+use macroquad::texture::{DrawTextureParams, Image, RenderTarget, draw_texture_ex, render_target};
+use macroquad::window::{clear_background, screen_height, screen_width};
+
+/// An off-screen texture the size of the window that rays accumulate in
+pub struct Lightmap {
+    /// The texture the rays are drawn into, never cleared between frames
+    target: RenderTarget,
+    /// The window size the texture was created for
+    size: (f32, f32),
+}
+
+impl Lightmap {
+    /// Creates an empty lightmap the size of the window
+    ///
+    /// Must be called after the window is created.
+    pub fn new() -> Lightmap {
+        let size = (screen_width(), screen_height());
+        let lightmap = Lightmap {
+            target: render_target(size.0 as u32, size.1 as u32),
+            size,
+        };
+        lightmap.clear();
+
+        lightmap
+    }
+
+    /// Erases everything accumulated so far
+    pub fn clear(&self) {
+        self.use_camera();
+        clear_background(BLANK);
+        set_default_camera();
+    }
+
+    /// Draws the current rays of every enabled emitter into the lightmap
+    ///
+    /// Each ray is drawn with its alpha scaled by `LIGHTMAP_EXPOSURE`, so it
+    /// takes several frames for a ray to reach its full brightness. If the
+    /// window was resized, the lightmap starts over at the new size.
+    ///
+    /// # Thread Safety
+    ///
+    /// This function acquires a read lock on the `OBJ_COLLECTION` global
+    /// (through `for_each_object()`).
+    pub fn accumulate(&mut self) {
+        if self.size != (screen_width(), screen_height()) {
+            *self = Lightmap::new();
+        }
+
+        self.use_camera();
+        for_each_object(|obj| {
+            if let RaytracerObjects::Emitters(emitter) = obj
+                && emitter.is_enabled()
+            {
+                for ray in emitter.get_rays() {
+                    let mut color = ray.color;
+                    color.a *= LIGHTMAP_EXPOSURE;
+                    draw_line(
+                        ray.start_x,
+                        ray.start_y,
+                        ray.end_x,
+                        ray.end_y,
+                        ray.thickness,
+                        color,
+                    );
+                }
+            }
+        });
+        set_default_camera();
+    }
+
+    /// Draws the accumulated light over the whole window
+    pub fn draw(&self) {
+        // render targets are stored upside down
+        draw_texture_ex(
+            &self.target.texture,
+            0.0,
+            0.0,
+            WHITE,
+            DrawTextureParams {
+                dest_size: Some(vec2(self.size.0, self.size.1)),
+                flip_y: true,
+                ..Default::default()
+            },
+        );
+    }
+
+    /// Reads the accumulated light back from the GPU
+    ///
+    /// Like every render target, the rows are stored bottom to top, which is
+    /// what `Image::export_png()` expects. Only the rays are in the image, the
+    /// objects and the background are never drawn into the lightmap.
+    pub fn image(&self) -> Image {
+        self.target.texture.get_texture_data()
+    }
+
+    /// Sends the following draw calls to the lightmap, with the same
+    /// coordinates as the window
+    fn use_camera(&self) {
+        let mut camera = Camera2D::from_display_rect(Rect::new(0.0, 0.0, self.size.0, self.size.1));
+        camera.render_target = Some(self.target.clone());
+        set_camera(&camera);
+    }
+}
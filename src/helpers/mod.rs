@@ -7,7 +7,16 @@
 //! # Modules
 //!
 //! * `action_utils` - Functions for user interactions with objects (selecting, removing, etc.)
+//! * `coverage_utils` - Functions for measuring how much of the window is lit by rays
+//! * `export_utils` - Functions for exporting scene data to files
+//! * `image_source_utils` - Functions for showing an emitter mirrored across an absorber
+//! * `lightmap_utils` - Functions for accumulating rays over time into a long exposure
 //! * `object_utils` - Utility functions for object creation, manipulation and mathematical operations
+//! * `scene_io` - Functions for loading scene layouts from files
+//! * `scene_log` - A log of the changes made to the scene, printed on demand
+//! * `shadow_utils` - Functions for filling the shadows cast by absorbers
+//! * `simulation_utils` - Fixed time steps for time-based updates such as key repeat
+//! * `toast` - Functions for showing short notifications on screen
 //!
 //! # Usage
 //!
@@ -34,5 +43,32 @@
 /// Utilities for interacting with objects in the scene
 pub mod action_utils;
 
+/// Illuminated area (coverage) utilities
+pub mod coverage_utils;
+
+/// Scene data export utilities
+pub mod export_utils;
+
+/// Image source (mirrored emitter) visualization utilities
+pub mod image_source_utils;
+
+/// Long exposure (accumulated rays) utilities
+pub mod lightmap_utils;
+
 /// Mathematical and object creation/manipulation utilities
 pub mod object_utils;
+
+/// Scene layout loading
+pub mod scene_io;
+
+/// Scene change log
+pub mod scene_log;
+
+/// Absorber shadow visualization
+pub mod shadow_utils;
+
+/// Fixed time step simulation utilities
+pub mod simulation_utils;
+
+/// On-screen notifications
+pub mod toast;
@@ -8,9 +8,25 @@
 //! last updated:   April 18, 2025
 
 use crate::RaytracerObjects;
-use crate::globals::{OBJ_COLLECTION, OBJD_RAY_COUNT};
-use crate::objects::emitters::Emitters;
-use crate::objects::ray::{init_collimated_rays, init_isotropic_rays, init_spotlight_rays};
+use crate::errors::RaytracerError;
+use crate::globals::{
+    OBJ_COLLECTION, OBJC_MAX_INTERSECTION_RAYS, OBJC_MAX_OBJ_COUNT, RAY_MAX_LENGTH, THEME,
+    WINDOW_FIT_PADDING, collection::mark_absorbers_changed,
+};
+use crate::objects::absorber::Absorbers;
+use crate::objects::behavior::Movable;
+use crate::objects::circle::ObjectCircle;
+use crate::objects::emitters::{Emitters, VariableRays};
+use crate::objects::ray::{
+    fill_collimated_rays, fill_convergent_rays, fill_isotropic_rays, fill_spotlight_rays,
+    fill_targeted_rays,
+};
+use macroquad::color::Color;
+use macroquad::time::get_time;
+use macroquad::window::{screen_height, screen_width};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::mem::discriminant;
 
 /// Gets a set of points form x1 to x2 that are linearly spaces. That is, for
 /// every point xi from the set of points, the distance from xi to x(i+1) for
@@ -44,6 +60,27 @@ pub fn linspace(x1: f32, x2: f32, sample_size: i32) -> Option<Vec<f32>> {
     Some(points)
 }
 
+/// Spreads points from x1 to x2 like `linspace()`, but accepts fewer than two
+/// samples instead of failing.
+///
+/// A single sample is placed at the midpoint and zero (or negative) samples give
+/// an empty vector, both with a warning, so emitters with too few rays do not panic.
+pub fn spread_points(x1: f32, x2: f32, sample_size: i32) -> Vec<f32> {
+    if let Some(points) = linspace(x1, x2, sample_size) {
+        return points;
+    }
+
+    println!(
+        "Raytracer ~Err: Got {} ray(s), at least 2 are needed to spread them evenly.",
+        sample_size
+    );
+    if sample_size == 1 {
+        vec![(x1 + x2) / 2.0]
+    } else {
+        Vec::new()
+    }
+}
+
 /// Initializes or reinitializes all rays for all emitter objects in the scene
 ///
 /// This function iterates through the global object collection and updates
@@ -62,12 +99,15 @@ pub fn linspace(x1: f32, x2: f32, sample_size: i32) -> Option<Vec<f32>> {
 /// - Isotropic emitters create rays radiating in all directions
 /// - Collimated emitters create parallel rays
 /// - Spotlight emitters create a cone of rays
+/// - Convergent emitters create rays focused on their target
+/// - Targeted emitters create one ray through each of their targets
 ///
 /// # Performance Considerations
 ///
 /// This operation can be computationally expensive when many emitters exist
 /// in the scene, so it should only be called when necessary (after object
-/// creation or movement).
+/// creation or movement). The rays are rebuilt in place, so an emitter whose
+/// ray count did not change reuses its allocation.
 pub fn init_all_rays() {
     let mut collection = OBJ_COLLECTION.write().unwrap();
 
@@ -75,24 +115,17 @@ pub fn init_all_rays() {
     for obj in collection.iter_mut() {
         if let RaytracerObjects::Emitters(emitter_enum) = obj {
             match emitter_enum {
-                Emitters::EmitterIsotropic(e) => {
-                    let ray_count = if e.rays.is_empty() {
-                        OBJD_RAY_COUNT
-                    } else {
-                        e.rays.len() as i32
-                    };
-
-                    e.rays =
-                        init_isotropic_rays(e.base_object.pos_x, e.base_object.pos_y, ray_count)
-                }
+                Emitters::EmitterIsotropic(e) => fill_isotropic_rays(
+                    &mut e.rays,
+                    e.base_object.pos_x,
+                    e.base_object.pos_y,
+                    e.ray_count,
+                ),
                 Emitters::EmitterCollimated(e) => {
-                    let ray_count = if e.base_emitter.rays.is_empty() {
-                        OBJD_RAY_COUNT
-                    } else {
-                        e.base_emitter.rays.len() as i32
-                    };
+                    let ray_count = e.base_emitter.ray_count;
 
-                    e.base_emitter.rays = init_collimated_rays(
+                    fill_collimated_rays(
+                        &mut e.base_emitter.rays,
                         e.base_emitter.base_object.pos_x,
                         e.base_emitter.base_object.pos_y,
                         e.orientation,
@@ -101,13 +134,10 @@ pub fn init_all_rays() {
                     )
                 }
                 Emitters::EmitterSpotlight(e) => {
-                    let ray_count = if e.base_emitter.rays.is_empty() {
-                        OBJD_RAY_COUNT
-                    } else {
-                        e.base_emitter.rays.len() as i32
-                    };
+                    let ray_count = e.base_emitter.ray_count;
 
-                    e.base_emitter.rays = init_spotlight_rays(
+                    fill_spotlight_rays(
+                        &mut e.base_emitter.rays,
                         e.base_emitter.base_object.pos_x,
                         e.base_emitter.base_object.pos_y,
                         e.orientation,
@@ -115,7 +145,37 @@ pub fn init_all_rays() {
                         ray_count,
                     )
                 }
+                Emitters::EmitterConvergent(e) => {
+                    let ray_count = e.base_emitter.ray_count;
+
+                    fill_convergent_rays(
+                        &mut e.base_emitter.rays,
+                        e.base_emitter.base_object.pos_x,
+                        e.base_emitter.base_object.pos_y,
+                        e.base_emitter.base_object.radius,
+                        e.target,
+                        ray_count,
+                    )
+                }
+                Emitters::EmitterTargeted(e) => fill_targeted_rays(
+                    &mut e.base_emitter.rays,
+                    e.base_emitter.base_object.pos_x,
+                    e.base_emitter.base_object.pos_y,
+                    &e.targets,
+                ),
             }
+
+            // the reflections belong to the old rays, occlusion adds them back
+            let base_emitter = emitter_enum.get_base_emitter_mut();
+            base_emitter.reflections.clear();
+            base_emitter.blocked_rays = 0;
+
+            // the rays are rebuilt with the default width and color and no
+            // wavelength or range
+            emitter_enum.set_ray_width(emitter_enum.get_ray_width());
+            emitter_enum.set_ray_color(emitter_enum.get_ray_color());
+            emitter_enum.set_wavelength(emitter_enum.get_wavelength());
+            emitter_enum.set_max_length(emitter_enum.get_max_length());
         }
     }
 }
@@ -134,10 +194,12 @@ pub fn init_all_rays() {
 /// This function acquires a write lock on the `OBJ_COLLECTION` global,
 /// ensuring thread-safe addition of new objects.
 ///
-/// # Error Handling
+/// # Returns
 ///
-/// If acquiring the write lock fails, an error message is printed to stderr
-/// and the object is not added to the collection.
+/// * `Ok(())` - If the object was added
+/// * `Err(RaytracerError::TooManyObjects)` - If the scene already has
+///   `OBJC_MAX_OBJ_COUNT` objects
+/// * `Err(RaytracerError::LockPoisoned)` - If the write lock could not be acquired
 ///
 /// # Example
 ///
@@ -150,19 +212,550 @@ pub fn init_all_rays() {
 /// let circle = ObjectCircle::new(100.0, 100.0, 50.0);
 ///
 /// // Add it to the global collection
-/// add_object_to_collection(RaytracerObjects::ObjectCircle(circle));
+/// add_object_to_collection(RaytracerObjects::ObjectCircle(circle))?;
 /// ```
-pub fn add_object_to_collection(new_object: RaytracerObjects) {
-    match OBJ_COLLECTION.write() {
-        Ok(mut collection) => {
-            collection.push(new_object);
-            println!("Raytracer Upd: Added new object to OBJ_COLLECTION.");
+pub fn add_object_to_collection(mut new_object: RaytracerObjects) -> Result<(), RaytracerError> {
+    let mut collection = OBJ_COLLECTION
+        .write()
+        .map_err(|_| RaytracerError::LockPoisoned("OBJ_COLLECTION"))?;
+
+    if collection.len() >= OBJC_MAX_OBJ_COUNT as usize {
+        return Err(RaytracerError::TooManyObjects(OBJC_MAX_OBJ_COUNT));
+    }
+
+    new_object.get_base_object_mut().created_at = get_time();
+    collection.push(new_object);
+    mark_absorbers_changed();
+    println!("Raytracer Upd: Added new object to OBJ_COLLECTION.");
+    Ok(())
+}
+
+/// Inserts an object into the scene at an index, shifting the objects after it
+///
+/// Unlike `add_object_to_collection()`, the object keeps a chosen position in
+/// the drawing order, e.g. to put a deleted object back where it was so that
+/// the indices of the other objects stay meaningful.
+///
+/// # Arguments
+///
+/// * `index` - The index the object will have, at most the number of objects
+/// * `new_object` - The object to insert
+///
+/// # Thread Safety
+///
+/// This function acquires a write lock on the `OBJ_COLLECTION` global.
+///
+/// # Returns
+///
+/// * `Ok(())` - If the object was inserted
+/// * `Err(RaytracerError::IndexOutOfBounds)` - If `index` is past the end of
+///   the collection
+/// * `Err(RaytracerError::TooManyObjects)` - If the scene already has
+///   `OBJC_MAX_OBJ_COUNT` objects
+/// * `Err(RaytracerError::LockPoisoned)` - If the write lock could not be acquired
+pub fn insert_object_at(index: usize, new_object: RaytracerObjects) -> Result<(), RaytracerError> {
+    let mut collection = OBJ_COLLECTION
+        .write()
+        .map_err(|_| RaytracerError::LockPoisoned("OBJ_COLLECTION"))?;
+
+    insert_into(&mut collection, index, new_object)?;
+    mark_absorbers_changed();
+    Ok(())
+}
+
+/// Moves an object to another index in the scene, shifting the objects
+/// between the two indices
+///
+/// Objects are drawn in collection order, so this changes which objects are
+/// drawn over which (z-ordering).
+///
+/// # Arguments
+///
+/// * `from` - The current index of the object
+/// * `to` - The index the object will have
+///
+/// # Thread Safety
+///
+/// This function acquires a write lock on the `OBJ_COLLECTION` global.
+///
+/// # Returns
+///
+/// * `Ok(())` - If the object was moved
+/// * `Err(RaytracerError::IndexOutOfBounds)` - If `from` or `to` is not the
+///   index of an object
+/// * `Err(RaytracerError::LockPoisoned)` - If the write lock could not be acquired
+pub fn move_object_to_index(from: usize, to: usize) -> Result<(), RaytracerError> {
+    let mut collection = OBJ_COLLECTION
+        .write()
+        .map_err(|_| RaytracerError::LockPoisoned("OBJ_COLLECTION"))?;
+
+    move_within(&mut collection, from, to)?;
+    // the absorbers are tested in collection order
+    mark_absorbers_changed();
+    Ok(())
+}
+
+/// Inserts an object into `objects`, see `insert_object_at()`
+fn insert_into(
+    objects: &mut Vec<RaytracerObjects>,
+    index: usize,
+    new_object: RaytracerObjects,
+) -> Result<(), RaytracerError> {
+    if index > objects.len() {
+        return Err(RaytracerError::IndexOutOfBounds {
+            index,
+            len: objects.len(),
+        });
+    }
+    if objects.len() >= OBJC_MAX_OBJ_COUNT as usize {
+        return Err(RaytracerError::TooManyObjects(OBJC_MAX_OBJ_COUNT));
+    }
+
+    objects.insert(index, new_object);
+    Ok(())
+}
+
+/// Moves an object within `objects`, see `move_object_to_index()`
+fn move_within(
+    objects: &mut [RaytracerObjects],
+    from: usize,
+    to: usize,
+) -> Result<(), RaytracerError> {
+    if let Some(index) = [from, to].into_iter().find(|i| *i >= objects.len()) {
+        return Err(RaytracerError::IndexOutOfBounds {
+            index,
+            len: objects.len(),
+        });
+    }
+
+    if from < to {
+        objects[from..=to].rotate_left(1);
+    } else {
+        objects[to..=from].rotate_right(1);
+    }
+    Ok(())
+}
+
+/// Clamps the position of every object in the scene back into the window
+///
+/// This function is used after the window is resized, since objects that were
+/// placed near the old right or bottom edge may now be outside of the visible
+/// area. Objects that are already inside the window are left untouched.
+///
+/// # Thread Safety
+///
+/// This function acquires a write lock on the `OBJ_COLLECTION` global.
+///
+/// # Returns
+///
+/// The number of objects that were moved back into the window
+pub fn clamp_all_objects_to_screen() -> usize {
+    let mut collection = OBJ_COLLECTION.write().unwrap();
+    let mut moved = 0;
+
+    for obj in collection.iter_mut() {
+        let (x, y) = obj.get_pos();
+        let (new_x, new_y) = clamp_to_window(x, y, 0.0);
+
+        if (new_x, new_y) != (x, y) {
+            match obj {
+                RaytracerObjects::ObjectCircle(o) => o.move_object(new_x, new_y),
+                RaytracerObjects::Emitters(o) => o.move_object(new_x, new_y),
+                RaytracerObjects::Absorbers(o) => o.move_object(new_x, new_y),
+            }
+            moved += 1;
         }
-        Err(e) => {
-            eprintln!(
-                "Raytracer Err: Failed to add a new object to OBJ_COLLECTION.\nFailed to get write lock: {:?}.",
-                e
-            );
+    }
+
+    if moved > 0 {
+        mark_absorbers_changed();
+    }
+    moved
+}
+
+/// Gets the bounding box that encloses every object in a list
+///
+/// # Arguments
+///
+/// * `objects` - The objects to enclose
+///
+/// # Returns
+///
+/// * `Some((min_x, min_y, max_x, max_y))` - The corners of the bounding box
+/// * `None` - If there are no objects
+pub fn scene_bounds(objects: &[RaytracerObjects]) -> Option<(f32, f32, f32, f32)> {
+    objects
+        .iter()
+        .map(RaytracerObjects::get_bounds)
+        .reduce(|a, b| (a.0.min(b.0), a.1.min(b.1), a.2.max(b.2), a.3.max(b.3)))
+}
+
+/// Moves every object so that their bounding box is centered in the window
+///
+/// There is no camera to zoom with, so the objects are only translated, their
+/// sizes and the distances between them are kept.
+///
+/// # Thread Safety
+///
+/// This function acquires a write lock on the `OBJ_COLLECTION` global.
+///
+/// # Returns
+///
+/// * `Some(bool)` - Whether the bounding box, with `WINDOW_FIT_PADDING` on
+///   every side, fits inside the window
+/// * `None` - If the scene is empty, nothing is moved
+pub fn center_all_objects_in_window() -> Option<bool> {
+    let mut collection = OBJ_COLLECTION.write().unwrap();
+    let (min_x, min_y, max_x, max_y) = scene_bounds(&collection)?;
+
+    let shift_x = screen_width() / 2.0 - (min_x + max_x) / 2.0;
+    let shift_y = screen_height() / 2.0 - (min_y + max_y) / 2.0;
+
+    for obj in collection.iter_mut() {
+        let (x, y) = obj.get_pos();
+        let (new_x, new_y) = (x + shift_x, y + shift_y);
+        match obj {
+            RaytracerObjects::ObjectCircle(o) => o.move_object(new_x, new_y),
+            RaytracerObjects::Emitters(o) => o.move_object(new_x, new_y),
+            RaytracerObjects::Absorbers(o) => o.move_object(new_x, new_y),
         }
     }
+    mark_absorbers_changed();
+
+    Some(
+        max_x - min_x + 2.0 * WINDOW_FIT_PADDING <= screen_width()
+            && max_y - min_y + 2.0 * WINDOW_FIT_PADDING <= screen_height(),
+    )
+}
+
+/// Wraps a position around the window edges (toroidal wrap-around)
+///
+/// A position that is past the right edge reappears on the left edge, and a
+/// position that is past the bottom edge reappears on the top edge (and vice
+/// versa).
+///
+/// # Arguments
+///
+/// * `pos_x` - The x-coordinate to wrap
+/// * `pos_y` - The y-coordinate to wrap
+///
+/// # Returns
+///
+/// A tuple `(f32, f32)` of the wrapped position, always inside the window
+pub fn wrap_to_window(pos_x: f32, pos_y: f32) -> (f32, f32) {
+    (
+        pos_x.rem_euclid(screen_width()),
+        pos_y.rem_euclid(screen_height()),
+    )
+}
+
+/// Clamps a position so that it stays inside the window
+///
+/// The `radius` is used as a margin from each edge so that an object of that
+/// radius remains fully visible. Passing a radius of `0.0` only keeps the
+/// center of the object inside the window. If the object is larger than the
+/// window, it is centered on that axis instead.
+///
+/// # Arguments
+///
+/// * `pos_x` - The x-coordinate to clamp
+/// * `pos_y` - The y-coordinate to clamp
+/// * `radius` - The margin to keep from each edge of the window
+///
+/// # Returns
+///
+/// A tuple `(f32, f32)` of the clamped position
+pub fn clamp_to_window(pos_x: f32, pos_y: f32, radius: f32) -> (f32, f32) {
+    let (width, height) = (screen_width(), screen_height());
+    let margin_x = radius.clamp(0.0, width / 2.0);
+    let margin_y = radius.clamp(0.0, height / 2.0);
+
+    (
+        pos_x.clamp(margin_x, width - margin_x),
+        pos_y.clamp(margin_y, height - margin_y),
+    )
+}
+
+/// Finds the intersection point of two line segments
+///
+/// The segments are written parametrically as `p + t * (p2 - p)` and
+/// `q + u * (q2 - q)`. Only crossings with both `t` and `u` in `(0, 1]` are
+/// reported, so segments that merely share their starting point (such as the
+/// rays of an isotropic emitter) do not count as crossing.
+///
+/// # Arguments
+///
+/// * `p` - The start of the first segment
+/// * `p2` - The end of the first segment
+/// * `q` - The start of the second segment
+/// * `q2` - The end of the second segment
+///
+/// # Returns
+///
+/// * `Some((x, y))` - The point where the segments cross
+/// * `None` - If the segments are parallel or do not cross
+pub fn segment_intersection(
+    p: (f32, f32),
+    p2: (f32, f32),
+    q: (f32, f32),
+    q2: (f32, f32),
+) -> Option<(f32, f32)> {
+    let r = (p2.0 - p.0, p2.1 - p.1);
+    let s = (q2.0 - q.0, q2.1 - q.1);
+
+    // 2D cross product of the directions, zero when the segments are parallel
+    let denom = r.0 * s.1 - r.1 * s.0;
+    if denom.abs() < f32::EPSILON {
+        return None;
+    }
+
+    let qp = (q.0 - p.0, q.1 - p.1);
+    let t = (qp.0 * s.1 - qp.1 * s.0) / denom;
+    let u = (qp.0 * r.1 - qp.1 * r.0) / denom;
+
+    if (0.0 < t && t <= 1.0) && (0.0 < u && u <= 1.0) {
+        Some((p.0 + t * r.0, p.1 + t * r.1))
+    } else {
+        None
+    }
+}
+
+/// Finds every point where two rays in the scene cross each other
+///
+/// This compares every pair of rays from every emitter, so it is O(n²) in the
+/// total number of rays. The rays should already be occluded so that crossings
+/// behind an absorber are not reported.
+///
+/// # Returns
+///
+/// * `Some(points)` - The crossing points of all pairs of rays
+/// * `None` - If the scene has more than `OBJC_MAX_INTERSECTION_RAYS` rays
+pub fn find_ray_intersections() -> Option<Vec<(f32, f32)>> {
+    let collection = OBJ_COLLECTION.read().unwrap();
+    let rays: Vec<_> = collection
+        .iter()
+        .filter_map(|obj| match obj {
+            RaytracerObjects::Emitters(e) if e.is_enabled() => Some(e.get_rays()),
+            _ => None,
+        })
+        .flatten()
+        .collect();
+
+    if rays.len() > OBJC_MAX_INTERSECTION_RAYS {
+        return None;
+    }
+
+    let mut points = Vec::new();
+    for (i, a) in rays.iter().enumerate() {
+        for b in &rays[i + 1..] {
+            if let Some(point) = segment_intersection(
+                (a.start_x, a.start_y),
+                (a.end_x, a.end_y),
+                (b.start_x, b.start_y),
+                (b.end_x, b.end_y),
+            ) {
+                points.push(point);
+            }
+        }
+    }
+
+    Some(points)
+}
+
+/// Counts the rays of every emitter in `objects`
+///
+/// # Returns
+///
+/// The total number of rays in the given objects
+pub fn total_ray_count(objects: &[RaytracerObjects]) -> i32 {
+    objects
+        .iter()
+        .map(|obj| match obj {
+            RaytracerObjects::Emitters(e) => e.get_rays_count(),
+            _ => 0,
+        })
+        .sum()
+}
+
+/// Computes a signature of everything the rays of the scene are built from
+///
+/// `init_all_rays()` and `check_for_occlusion()` give the same rays for the
+/// same signature, so they can be skipped when it has not changed since
+/// their last pass (e.g. after a drag held against the window edge). This
+/// relies on nothing else building the rays of an object in the scene:
+/// moving an emitter or changing its ray count leaves its rays as they were
+/// until the next `init_all_rays()`. Besides
+/// the objects, this covers the ray length limit, the theme's ray color, the
+/// window size, and whether occlusion is on.
+///
+/// # Arguments
+///
+/// * `objects` - The objects in the scene
+/// * `occlusion_enabled` - If the rays are cut by absorbers
+///
+/// # Returns
+///
+/// A hash of the scene, equal for scenes whose rays are the same
+pub fn ray_signature(objects: &[RaytracerObjects], occlusion_enabled: bool) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    hash_objects(objects, &mut hasher);
+    occlusion_enabled.hash(&mut hasher);
+    hash_floats(
+        &[
+            *RAY_MAX_LENGTH.read().unwrap(),
+            screen_width(),
+            screen_height(),
+        ],
+        &mut hasher,
+    );
+    hash_color(THEME.read().unwrap().ray_color, &mut hasher);
+
+    hasher.finish()
+}
+
+/// Hashes the fields of each object that its rays depend on, see
+/// `ray_signature()`
+fn hash_objects(objects: &[RaytracerObjects], state: &mut impl Hasher) {
+    let hash_circle = |circle: &ObjectCircle, state: &mut _| {
+        hash_floats(&[circle.pos_x, circle.pos_y, circle.radius], state)
+    };
+
+    for obj in objects {
+        discriminant(obj).hash(state);
+        match obj {
+            RaytracerObjects::ObjectCircle(o) => hash_circle(o, state),
+            RaytracerObjects::Absorbers(o) => {
+                discriminant(o).hash(state);
+                hash_circle(o.get_base_object(), state);
+                if let Absorbers::AbsorberArc(arc) | Absorbers::MirrorArc(arc) = o {
+                    hash_floats(&[arc.start_angle, arc.end_angle], state);
+                }
+            }
+            RaytracerObjects::Emitters(o) => {
+                discriminant(o).hash(state);
+                let base_emitter = o.get_base_emitter();
+                hash_circle(&base_emitter.base_object, state);
+                base_emitter.ray_count.hash(state);
+                base_emitter.enabled.hash(state);
+                base_emitter.ray_width.to_bits().hash(state);
+                hash_color(base_emitter.ray_color, state);
+                base_emitter.wavelength_nm.map(f32::to_bits).hash(state);
+                base_emitter.max_length.map(f32::to_bits).hash(state);
+
+                match o {
+                    Emitters::EmitterIsotropic(_) => {}
+                    Emitters::EmitterCollimated(e) => {
+                        hash_floats(&[e.orientation, e.collimated_beam_diameter], state)
+                    }
+                    Emitters::EmitterSpotlight(e) => {
+                        hash_floats(&[e.orientation, e.spotlight_beam_angle], state)
+                    }
+                    Emitters::EmitterConvergent(e) => hash_floats(&[e.target.0, e.target.1], state),
+                    Emitters::EmitterTargeted(e) => {
+                        for target in &e.targets {
+                            hash_floats(&[target.0, target.1], state);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Hashes floats by their bits, `f32` itself is not `Hash`
+fn hash_floats(values: &[f32], state: &mut impl Hasher) {
+    for value in values {
+        value.to_bits().hash(state);
+    }
+}
+
+/// Hashes the channels of a color, see `hash_floats()`
+fn hash_color(color: Color, state: &mut impl Hasher) {
+    hash_floats(&[color.r, color.g, color.b, color.a], state);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::objects::circle::ObjectCircle;
+    use macroquad::color::{RED, WHITE};
+
+    /// Circles at x = 0, 1, 2, ... so the order can be read back
+    fn circles(count: usize) -> Vec<RaytracerObjects> {
+        (0..count)
+            .map(|x| RaytracerObjects::ObjectCircle(ObjectCircle::new(x as f32, 0.0, WHITE, 10.0)))
+            .collect()
+    }
+
+    fn order(objects: &[RaytracerObjects]) -> Vec<f32> {
+        objects.iter().map(|o| o.get_pos().0).collect()
+    }
+
+    fn objects_hash(objects: &[RaytracerObjects]) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        hash_objects(objects, &mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn scene_hash_changes_only_with_the_objects() {
+        let objects = circles(3);
+        assert_eq!(objects_hash(&objects), objects_hash(&circles(3)));
+
+        let mut moved = circles(3);
+        moved[1] = RaytracerObjects::ObjectCircle(ObjectCircle::new(1.0, 5.0, WHITE, 10.0));
+        assert_ne!(objects_hash(&objects), objects_hash(&moved));
+
+        // the fill color does not change any ray
+        let mut recolored = circles(3);
+        if let RaytracerObjects::ObjectCircle(o) = &mut recolored[0] {
+            o.color_fill = RED;
+        }
+        assert_eq!(objects_hash(&objects), objects_hash(&recolored));
+
+        assert_ne!(objects_hash(&objects), objects_hash(&circles(2)));
+    }
+
+    #[test]
+    fn objects_are_inserted_at_an_index() {
+        let mut objects = circles(3);
+        let new_object = RaytracerObjects::ObjectCircle(ObjectCircle::new(9.0, 0.0, WHITE, 10.0));
+
+        assert_eq!(insert_into(&mut objects, 1, new_object.clone()), Ok(()));
+        assert_eq!(order(&objects), vec![0.0, 9.0, 1.0, 2.0]);
+
+        // inserting at the length appends
+        assert_eq!(insert_into(&mut objects, 4, new_object.clone()), Ok(()));
+        assert_eq!(order(&objects), vec![0.0, 9.0, 1.0, 2.0, 9.0]);
+
+        assert_eq!(
+            insert_into(&mut objects, 6, new_object),
+            Err(RaytracerError::IndexOutOfBounds { index: 6, len: 5 })
+        );
+    }
+
+    #[test]
+    fn objects_are_moved_to_an_index() {
+        let mut objects = circles(4);
+
+        assert_eq!(move_within(&mut objects, 0, 2), Ok(()));
+        assert_eq!(order(&objects), vec![1.0, 2.0, 0.0, 3.0]);
+
+        assert_eq!(move_within(&mut objects, 3, 0), Ok(()));
+        assert_eq!(order(&objects), vec![3.0, 1.0, 2.0, 0.0]);
+
+        assert_eq!(
+            move_within(&mut objects, 1, 4),
+            Err(RaytracerError::IndexOutOfBounds { index: 4, len: 4 })
+        );
+        assert_eq!(order(&objects), vec![3.0, 1.0, 2.0, 0.0]);
+    }
+
+    #[test]
+    fn spread_points_handles_fewer_than_two_samples() {
+        assert_eq!(spread_points(-1.0, 1.0, 3), vec![-1.0, 0.0, 1.0]);
+        assert_eq!(spread_points(0.5, 1.5, 1), vec![1.0]);
+        assert!(spread_points(0.5, 1.5, 0).is_empty());
+        assert!(spread_points(0.5, 1.5, -2).is_empty());
+    }
 }
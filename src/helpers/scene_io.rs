@@ -0,0 +1,1094 @@
+//! Reading scene layouts from files
+//!
+//! This module builds objects from simple text layouts so that scenes can be
+//! generated in a spreadsheet or by a script. The objects are created with
+//! `new_object_at()`, the same function used when creating objects by hand.
+//! Layouts can also be saved in the same format, either to a path or to one of
+//! the numbered preset slots, the scene can be drawn to an SVG image, and the
+//! optional background image given with `--background` is loaded here.
+//!
+//! author:         Zhean Ganituen (zrygan)
+//! last updated:   April 18, 2025
+
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+
+use macroquad::color::{Color, WHITE};
+use macroquad::math::vec2;
+use macroquad::texture::{DrawTextureParams, Texture2D, draw_texture_ex, load_texture};
+use macroquad::window::{screen_height, screen_width};
+
+use crate::errors::RaytracerError;
+use crate::globals::{
+    APP_PRESETS_DIR, APP_SCENE_VERSION, OBJ_COLLECTION, OBJD_ARC_THICKNESS, OBJD_DISABLED_ALPHA,
+    THEME, collection::mark_absorbers_changed,
+};
+use crate::globals::{
+    OBJC_MAX_TOTAL_RAYS, OBJC_MIN_RAY_COUNT, OBJD_CONVERGENT_ORIENTATION, OBJD_VISIBLE_WAVELENGTHS,
+};
+use crate::helpers::action_utils::next_group_id;
+use crate::objects::absorber::{AbsorberArc, Absorbers};
+use crate::objects::behavior::{ObjectParam, RaytracerObjects, VariableSize};
+use crate::objects::emitters::Emitters;
+use crate::user_input::add_to_scene_actions::{add_new_object, new_object_at};
+
+/// One row of a CSV scene layout
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CsvRow {
+    /// The object type, as accepted by `new_object_at()`
+    pub object_type: String,
+    /// The x-coordinate of the object
+    pub pos_x: f32,
+    /// The y-coordinate of the object
+    pub pos_y: f32,
+    /// The radius of the object
+    pub radius: f32,
+    /// The type-specific parameter, `None` to keep the default
+    pub param: Option<f32>,
+    /// The group the object belongs to, ids are only meaningful within one
+    /// layout
+    pub group_id: Option<u32>,
+    /// Whether the object is locked against edits
+    pub locked: bool,
+    /// The number of rays of an emitter
+    pub ray_count: Option<i32>,
+    /// The orientation (in degrees) of an emitter, or where an arc starts
+    pub orientation: Option<f32>,
+    /// Whether an emitter is switched on
+    pub enabled: Option<bool>,
+    /// The wavelength (in nanometers) of an emitter's light
+    pub wavelength: Option<f32>,
+    /// How far the rays of an emitter reach (in pixels)
+    pub range: Option<f32>,
+    /// The thickness of an emitter's rays
+    pub ray_width: Option<f32>,
+    /// The target points of a targeted emitter
+    pub targets: Option<Vec<(f32, f32)>>,
+}
+
+/// The header row of a CSV scene layout, naming the columns of `CsvRow`
+pub const CSV_HEADER: &str = "type,x,y,radius,param,group,locked,rays,orientation,enabled,wavelength,range,ray_width,targets";
+
+/// Parses one row of a CSV scene layout
+///
+/// A row has the columns of `CSV_HEADER`, every field after `radius` may be
+/// left empty or omitted to keep the default. `locked` and `enabled` are `1`
+/// or `0`, and `targets` is a list of `x y` points separated by `;`.
+/// Whitespace around the fields is ignored.
+///
+/// # Returns
+///
+/// * `Ok(CsvRow)` - The parsed row
+/// * `Err(String)` - A message describing why the row is invalid
+pub fn parse_csv_row(line: &str) -> Result<CsvRow, String> {
+    let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+    let columns = CSV_HEADER.split(',').count();
+    if !(4..=columns).contains(&fields.len()) {
+        return Err(format!(
+            "expected 4 to {} fields, found {}",
+            columns,
+            fields.len()
+        ));
+    }
+
+    let number = |index: usize, name: &str| {
+        fields[index]
+            .parse::<f32>()
+            .map_err(|_| format!("{} '{}' is not a number", name, fields[index]))
+    };
+    let optional = |index: usize| fields.get(index).filter(|field| !field.is_empty());
+    let optional_number = |index: usize, name: &str| match optional(index) {
+        None => Ok(None),
+        Some(_) => number(index, name).map(Some),
+    };
+    let flag = |index: usize, name: &str| match optional(index) {
+        None => Ok(None),
+        Some(&"0") => Ok(Some(false)),
+        Some(&"1") => Ok(Some(true)),
+        Some(value) => Err(format!("{} '{}' is not 0 or 1", name, value)),
+    };
+
+    let radius = number(3, "radius")?;
+    if radius <= 0.0 {
+        return Err("radius must be greater than 0".to_string());
+    }
+
+    let group_id = match optional(5) {
+        None => None,
+        Some(group) => Some(
+            group
+                .parse::<u32>()
+                .map_err(|_| format!("group '{}' is not a whole number", group))?,
+        ),
+    };
+
+    let ray_count = match optional(7) {
+        None => None,
+        Some(rays) => Some(
+            rays.parse::<i32>()
+                .map_err(|_| format!("rays '{}' is not a whole number", rays))?,
+        ),
+    };
+
+    let targets = match optional(13) {
+        None => None,
+        Some(targets) => Some(
+            targets
+                .split(';')
+                .map(|point| {
+                    let coordinates: Vec<f32> = point
+                        .split_whitespace()
+                        .map(str::parse)
+                        .collect::<Result<_, _>>()
+                        .map_err(|_| format!("target '{}' is not a point", point))?;
+                    match coordinates[..] {
+                        [x, y] => Ok((x, y)),
+                        _ => Err(format!("target '{}' is not a point", point)),
+                    }
+                })
+                .collect::<Result<_, String>>()?,
+        ),
+    };
+
+    Ok(CsvRow {
+        object_type: fields[0].to_string(),
+        pos_x: number(1, "x")?,
+        pos_y: number(2, "y")?,
+        radius,
+        param: optional_number(4, "param")?,
+        group_id,
+        locked: flag(6, "locked")?.unwrap_or(false),
+        ray_count,
+        orientation: optional_number(8, "orientation")?,
+        enabled: flag(9, "enabled")?,
+        wavelength: optional_number(10, "wavelength")?,
+        range: optional_number(11, "range")?,
+        ray_width: optional_number(12, "ray width")?,
+        targets,
+    })
+}
+
+/// Builds the object described by a CSV row
+///
+/// The meaning of `param` depends on the type:
+/// - "emitter_collimated": the beam diameter (in pixels)
+/// - "emitter_spotlight": the beam angle (in degrees)
+/// - "emitter_convergent": the distance to the target (in pixels)
+/// - "absorber_arc" and "mirror_arc": how far the arc spans (in degrees)
+/// - every other type ignores it
+///
+/// The orientation is applied with `set_param()`, after `param`, so a
+/// convergent emitter's target is turned around it. For arcs it is the angle
+/// where the arc starts. The emitter columns are ignored by other objects,
+/// and the ray count of a targeted emitter follows its targets. Rays are not
+/// rebuilt, the caller is responsible for reinitializing them.
+///
+/// The group id is copied as it is, `read_csv()` maps it to an unused one.
+///
+/// # Returns
+///
+/// * `Ok(RaytracerObjects)` - The new object
+/// * `Err(String)` - If the object type is not recognized or a value is
+///   invalid
+fn object_from_row(row: &CsvRow) -> Result<RaytracerObjects, String> {
+    let mut object = new_object_at(&row.object_type, row.pos_x, row.pos_y)
+        .ok_or(format!("unknown object type '{}'", row.object_type))?;
+
+    match &mut object {
+        RaytracerObjects::ObjectCircle(o) => o.set_radius(row.radius),
+        RaytracerObjects::Absorbers(o) => o.set_radius(row.radius),
+        RaytracerObjects::Emitters(o) => o.set_radius(row.radius),
+    }
+
+    let base_object = object.get_base_object_mut();
+    base_object.group_id = row.group_id;
+    base_object.locked = row.locked;
+
+    if let Some(param) = row.param {
+        match &mut object {
+            RaytracerObjects::Emitters(Emitters::EmitterCollimated(o)) => {
+                o.collimated_beam_diameter = param
+            }
+            RaytracerObjects::Emitters(Emitters::EmitterSpotlight(o)) => {
+                o.spotlight_beam_angle = param.to_radians()
+            }
+            RaytracerObjects::Emitters(Emitters::EmitterConvergent(o)) => {
+                o.target = (row.pos_x + param, row.pos_y);
+                o.set_orientation(OBJD_CONVERGENT_ORIENTATION);
+            }
+            RaytracerObjects::Absorbers(Absorbers::AbsorberArc(arc))
+            | RaytracerObjects::Absorbers(Absorbers::MirrorArc(arc)) => {
+                arc.end_angle = arc.start_angle + param.to_radians()
+            }
+            _ => {}
+        }
+    }
+
+    if let Some(orientation) = row.orientation {
+        let oriented = object.params().orientation.is_some();
+        match &mut object {
+            RaytracerObjects::Absorbers(Absorbers::AbsorberArc(arc))
+            | RaytracerObjects::Absorbers(Absorbers::MirrorArc(arc)) => {
+                let span = arc.span();
+                arc.start_angle = orientation.to_radians();
+                arc.end_angle = arc.start_angle + span;
+            }
+            _ if oriented => {
+                object
+                    .set_param(ObjectParam::Orientation, orientation.to_radians())
+                    .map_err(|e| e.to_string())?;
+            }
+            _ => {}
+        }
+    }
+
+    if let RaytracerObjects::Emitters(emitter) = &mut object {
+        apply_emitter_columns(emitter, row)?;
+    }
+
+    Ok(object)
+}
+
+/// Applies the emitter columns of a CSV row to an emitter, see
+/// `object_from_row()`
+///
+/// The ray count may be anything up to `OBJC_MAX_TOTAL_RAYS`, since the keys
+/// can give an emitter more than `OBJC_MAX_RAY_COUNT` rays. Whether the scene
+/// has room for them is checked when the object is added.
+fn apply_emitter_columns(emitter: &mut Emitters, row: &CsvRow) -> Result<(), String> {
+    match (&mut *emitter, row.ray_count, &row.targets) {
+        (Emitters::EmitterTargeted(o), _, Some(targets)) => {
+            o.set_targets(targets.clone());
+        }
+        (Emitters::EmitterTargeted(_), _, None) | (_, None, _) => {}
+        (emitter, Some(ray_count), _) => {
+            if !(OBJC_MIN_RAY_COUNT..=OBJC_MAX_TOTAL_RAYS).contains(&ray_count) {
+                return Err(format!(
+                    "ray count must be between {} and {}",
+                    OBJC_MIN_RAY_COUNT, OBJC_MAX_TOTAL_RAYS
+                ));
+            }
+            emitter.get_base_emitter_mut().ray_count = ray_count;
+        }
+    }
+
+    if let Some(enabled) = row.enabled {
+        emitter.set_enabled(enabled);
+    }
+
+    if let Some(wavelength) = row.wavelength {
+        let (min, max) = OBJD_VISIBLE_WAVELENGTHS;
+        if !(min..=max).contains(&wavelength) {
+            return Err(format!("wavelength must be between {} and {} nm", min, max));
+        }
+        emitter.set_wavelength(Some(wavelength));
+    }
+
+    if let Some(range) = row.range {
+        if range <= 0.0 {
+            return Err("range must be greater than 0".to_string());
+        }
+        emitter.set_max_length(Some(range));
+    }
+
+    if let Some(ray_width) = row.ray_width {
+        if ray_width <= 0.0 {
+            return Err("ray width must be greater than 0".to_string());
+        }
+        emitter.set_ray_width(ray_width);
+    }
+
+    Ok(())
+}
+
+/// Parses the `version,<n>` row at the top of a CSV scene layout
+///
+/// # Returns
+///
+/// * `None` - If the line is not a version row
+/// * `Some(Ok(u32))` - The version of the layout
+/// * `Some(Err(String))` - A message describing why the version is invalid
+pub fn parse_version_row(line: &str) -> Option<Result<u32, String>> {
+    let version = line.strip_prefix("version,")?.trim();
+    Some(
+        version
+            .parse::<u32>()
+            .map_err(|_| format!("invalid version '{}'", version)),
+    )
+}
+
+/// Reads the objects of a CSV scene layout without adding them to the scene
+///
+/// The layout may start with a `version,<n>` row (see `parse_version_row()`).
+/// Layouts without one were written before the format was versioned and are
+/// read as version 1. Version 1 layouts have no group and locked columns, so
+/// their objects are ungrouped and unlocked, and layouts before version 3 have
+/// none of the emitter columns, so their emitters keep the defaults. Layouts
+/// from a newer version than `APP_SCENE_VERSION` are rejected, since their
+/// rows may mean something else.
+///
+/// Each row has the columns of `CSV_HEADER` (see `parse_csv_row()` and
+/// `object_from_row()`). Blank lines, lines starting with `#`, and a header
+/// row starting with `type` are skipped. Invalid rows and unknown types are
+/// skipped with a warning. The groups of the layout get
+/// new ids counting up from `first_group_id`.
+///
+/// # Arguments
+///
+/// * `path` - The path of the CSV file to read
+/// * `first_group_id` - The id given to the first group of the layout
+///
+/// # Returns
+///
+/// * `Ok(Vec<(usize, RaytracerObjects)>)` - The objects, each with the line
+///   number it was read from
+/// * `Err(std::io::Error)` - If the file could not be read, or its version is
+///   invalid or not supported
+fn read_csv(path: &str, first_group_id: u32) -> std::io::Result<Vec<(usize, RaytracerObjects)>> {
+    let contents = fs::read_to_string(path)?;
+    let mut objects = Vec::new();
+
+    let first_row = contents
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty() && !line.starts_with('#'));
+    let version = match first_row.and_then(parse_version_row) {
+        Some(Ok(version)) => version,
+        Some(Err(e)) => return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, e)),
+        None => 1,
+    };
+    // older versions only lack columns at the end, which are then left empty
+    if !(1..=APP_SCENE_VERSION).contains(&version) {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!(
+                "unsupported version {} (this build reads version {})",
+                version, APP_SCENE_VERSION
+            ),
+        ));
+    }
+
+    let mut next_group = first_group_id;
+    let mut group_ids: HashMap<u32, u32> = HashMap::new();
+
+    for (line_number, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty()
+            || line.starts_with('#')
+            || line.starts_with("type")
+            || line.starts_with("version")
+        {
+            continue;
+        }
+
+        let mut row = match parse_csv_row(line) {
+            Ok(row) => row,
+            Err(e) => {
+                println!(
+                    "Raytracer ~Err: Skipped line {} of {}, {}",
+                    line_number + 1,
+                    path,
+                    e
+                );
+                continue;
+            }
+        };
+
+        row.group_id = row.group_id.map(|id| {
+            *group_ids.entry(id).or_insert_with(|| {
+                next_group += 1;
+                next_group - 1
+            })
+        });
+
+        match object_from_row(&row) {
+            Ok(object) => objects.push((line_number + 1, object)),
+            Err(e) => println!(
+                "Raytracer ~Err: Skipped line {} of {}, {}",
+                line_number + 1,
+                path,
+                e
+            ),
+        }
+    }
+
+    Ok(objects)
+}
+
+/// Adds the objects read by `read_csv()` to the scene
+///
+/// Adding stops once the scene has `OBJC_MAX_OBJ_COUNT` objects, and emitters
+/// that would exceed the scene's ray budget are skipped with a warning.
+///
+/// # Returns
+///
+/// The number of objects added to the scene
+fn add_csv_objects(objects: Vec<(usize, RaytracerObjects)>, path: &str) -> usize {
+    let mut added = 0;
+
+    for (line_number, object) in objects {
+        match add_new_object(object) {
+            Ok(()) => added += 1,
+            Err(e @ RaytracerError::TooManyObjects(_)) => {
+                println!("Raytracer ~Err: Stopped loading {}, {}", path, e);
+                break;
+            }
+            Err(e) => println!(
+                "Raytracer ~Err: Skipped line {} of {}, {}",
+                line_number, path, e
+            ),
+        }
+    }
+
+    added
+}
+
+/// Loads the objects of a CSV scene layout into the scene
+///
+/// The layout is read with `read_csv()` and its objects are added after the
+/// ones already in the scene. Its groups get ids that are not used in the
+/// scene yet, so they don't merge with the groups already there. Nothing is
+/// added if the file can't be read or has an unsupported version.
+///
+/// The caller is responsible for reinitializing the rays (and occlusion).
+///
+/// # Arguments
+///
+/// * `path` - The path of the CSV file to read
+///
+/// # Returns
+///
+/// * `Ok(usize)` - The number of objects added to the scene
+/// * `Err(std::io::Error)` - If the file could not be read, or its version is
+///   invalid or not supported
+pub fn load_csv(path: &str) -> std::io::Result<usize> {
+    let objects = read_csv(path, next_group_id(&OBJ_COLLECTION.read().unwrap()))?;
+    Ok(add_csv_objects(objects, path))
+}
+
+/// Formats an object as a row of a CSV scene layout
+///
+/// This is the inverse of `parse_csv_row()` and `object_from_row()`. The
+/// columns an object doesn't have are left empty, so loading the row gives
+/// back the same object, including its group and lock, but not its colors.
+pub fn object_to_csv_row(object: &RaytracerObjects) -> String {
+    let object_type = match object {
+        RaytracerObjects::ObjectCircle(_) => "circle_none",
+        RaytracerObjects::Emitters(Emitters::EmitterIsotropic(_)) => "emitter_isotropic",
+        RaytracerObjects::Emitters(Emitters::EmitterCollimated(_)) => "emitter_collimated",
+        RaytracerObjects::Emitters(Emitters::EmitterSpotlight(_)) => "emitter_spotlight",
+        RaytracerObjects::Emitters(Emitters::EmitterConvergent(_)) => "emitter_convergent",
+        RaytracerObjects::Emitters(Emitters::EmitterTargeted(_)) => "emitter_targeted",
+        RaytracerObjects::Absorbers(Absorbers::AbsorberPerfect(_)) => "absorber_perfect",
+        RaytracerObjects::Absorbers(Absorbers::AbsorberArc(_)) => "absorber_arc",
+        RaytracerObjects::Absorbers(Absorbers::MirrorArc(_)) => "mirror_arc",
+    };
+
+    let params = object.params();
+    let (param, orientation) = match object {
+        RaytracerObjects::Emitters(Emitters::EmitterCollimated(o)) => {
+            (Some(o.collimated_beam_diameter), params.orientation)
+        }
+        RaytracerObjects::Emitters(Emitters::EmitterSpotlight(o)) => (
+            Some(o.spotlight_beam_angle.to_degrees()),
+            params.orientation,
+        ),
+        RaytracerObjects::Emitters(Emitters::EmitterConvergent(o)) => {
+            (Some(o.focus_distance()), params.orientation)
+        }
+        RaytracerObjects::Absorbers(Absorbers::AbsorberArc(arc))
+        | RaytracerObjects::Absorbers(Absorbers::MirrorArc(arc)) => {
+            (Some(arc.span().to_degrees()), Some(arc.start_angle))
+        }
+        _ => (None, None),
+    };
+
+    let number = |value: Option<f32>| value.map_or(String::new(), |v| v.to_string());
+    let base_object = object.get_base_object();
+    let mut fields = vec![
+        object_type.to_string(),
+        base_object.pos_x.to_string(),
+        base_object.pos_y.to_string(),
+        base_object.radius.to_string(),
+        number(param),
+        base_object
+            .group_id
+            .map_or(String::new(), |id| id.to_string()),
+        (base_object.locked as u8).to_string(),
+        String::new(),
+        number(orientation.map(f32::to_degrees)),
+    ];
+
+    if let RaytracerObjects::Emitters(emitter) = object {
+        let targets = match emitter {
+            Emitters::EmitterTargeted(o) => {
+                // the ray count follows the targets
+                fields[7] = String::new();
+                o.targets
+                    .iter()
+                    .map(|(x, y)| format!("{} {}", x, y))
+                    .collect::<Vec<_>>()
+                    .join(";")
+            }
+            _ => {
+                fields[7] = params
+                    .ray_count
+                    .map_or(String::new(), |count| count.to_string());
+                String::new()
+            }
+        };
+
+        fields.extend([
+            (emitter.is_enabled() as u8).to_string(),
+            number(emitter.get_wavelength()),
+            number(emitter.get_max_length()),
+            emitter.get_ray_width().to_string(),
+            targets,
+        ]);
+    } else {
+        fields.resize(CSV_HEADER.split(',').count(), String::new());
+    }
+
+    fields.join(",")
+}
+
+/// Saves every object in the scene as a CSV scene layout
+///
+/// The file starts with a `version,<n>` row holding `APP_SCENE_VERSION` and a
+/// `CSV_HEADER` row, followed by one row per object (see `object_to_csv_row()`). It can be loaded again with `load_csv()`.
+///
+/// # Arguments
+///
+/// * `path` - The path of the CSV file to write
+///
+/// # Returns
+///
+/// * `Ok(usize)` - The number of objects written
+/// * `Err(std::io::Error)` - If the file could not be created or written
+pub fn save_csv(path: &str) -> std::io::Result<usize> {
+    let mut file = BufWriter::new(File::create(path)?);
+    let collection = OBJ_COLLECTION.read().unwrap();
+
+    writeln!(file, "version,{}", APP_SCENE_VERSION)?;
+    writeln!(file, "{}", CSV_HEADER)?;
+    for object in collection.iter() {
+        writeln!(file, "{}", object_to_csv_row(object))?;
+    }
+    file.flush()?;
+
+    Ok(collection.len())
+}
+
+/// Formats a color as an SVG paint attribute and its opacity
+///
+/// # Arguments
+///
+/// * `attribute` - Either `fill` or `stroke`
+/// * `color` - The color to format
+///
+/// # Returns
+///
+/// The attributes, e.g. `fill="rgb(255,0,0)" fill-opacity="0.5"`
+pub fn svg_paint(attribute: &str, color: Color) -> String {
+    format!(
+        "{0}=\"rgb({1},{2},{3})\" {0}-opacity=\"{4}\"",
+        attribute,
+        (color.r * 255.0).round() as u8,
+        (color.g * 255.0).round() as u8,
+        (color.b * 255.0).round() as u8,
+        color.a
+    )
+}
+
+/// Formats an arc as an SVG element, drawn like `AbsorberArc::draw_object()`
+///
+/// A full circle can't be drawn as a single SVG arc, so it is written as an
+/// unfilled `<circle>` instead.
+fn svg_arc(arc: &AbsorberArc) -> String {
+    let base_object = &arc.base_object;
+    let (x, y, radius) = (base_object.pos_x, base_object.pos_y, base_object.radius);
+    let stroke = svg_paint("stroke", base_object.color_fill);
+    let span = arc.span();
+
+    if span >= std::f32::consts::TAU {
+        return format!(
+            "<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"none\" {} stroke-width=\"{}\"/>",
+            x, y, radius, stroke, OBJD_ARC_THICKNESS
+        );
+    }
+
+    // angles increase clockwise on screen, which is SVG's positive sweep
+    let end_angle = arc.start_angle + span;
+    format!(
+        "<path d=\"M {} {} A {} {} 0 {} 1 {} {}\" fill=\"none\" {} stroke-width=\"{}\"/>",
+        x + radius * arc.start_angle.cos(),
+        y + radius * arc.start_angle.sin(),
+        radius,
+        radius,
+        (span > std::f32::consts::PI) as u8,
+        x + radius * end_angle.cos(),
+        y + radius * end_angle.sin(),
+        stroke,
+        OBJD_ARC_THICKNESS
+    )
+}
+
+/// Draws the scene to an SVG image
+///
+/// The image is the size of the window and has the theme's background color.
+/// Objects are written in the order they are drawn: circles become `<circle>`
+/// elements, arcs become `<path>` elements, and every ray (and reflected ray)
+/// of an enabled emitter becomes a `<line>`, all in their own colors. Unlike a
+/// screenshot, the image scales cleanly for papers and slides.
+///
+/// This should be called after `check_for_occlusion()` so that the rays are
+/// the truncated ones.
+///
+/// # Arguments
+///
+/// * `path` - The path of the SVG file to write
+///
+/// # Returns
+///
+/// * `Ok(())` - If the file was written
+/// * `Err(std::io::Error)` - If the file could not be created or written
+pub fn export_svg(path: &str) -> std::io::Result<()> {
+    let mut file = BufWriter::new(File::create(path)?);
+    let collection = OBJ_COLLECTION.read().unwrap();
+    let (width, height) = (screen_width(), screen_height());
+
+    writeln!(
+        file,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{1}\" viewBox=\"0 0 {0} {1}\">",
+        width, height
+    )?;
+    writeln!(
+        file,
+        "  <rect width=\"100%\" height=\"100%\" {}/>",
+        svg_paint("fill", THEME.read().unwrap().background)
+    )?;
+
+    for object in collection.iter() {
+        let base_object = object.get_base_object();
+        let mut fill = base_object.color_fill;
+
+        match object {
+            RaytracerObjects::Absorbers(Absorbers::AbsorberArc(arc))
+            | RaytracerObjects::Absorbers(Absorbers::MirrorArc(arc)) => {
+                writeln!(file, "  {}", svg_arc(arc))?;
+                continue;
+            }
+            RaytracerObjects::Emitters(emitter) if !emitter.is_enabled() => {
+                fill.a *= OBJD_DISABLED_ALPHA;
+            }
+            _ => {}
+        }
+
+        writeln!(
+            file,
+            "  <circle cx=\"{}\" cy=\"{}\" r=\"{}\" {}/>",
+            base_object.pos_x,
+            base_object.pos_y,
+            base_object.radius,
+            svg_paint("fill", fill)
+        )?;
+
+        if let RaytracerObjects::Emitters(emitter) = object
+            && emitter.is_enabled()
+        {
+            let base_emitter = emitter.get_base_emitter();
+            for ray in base_emitter.rays.iter().chain(&base_emitter.reflections) {
+                writeln!(
+                    file,
+                    "  <line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" {} stroke-width=\"{}\"/>",
+                    ray.start_x,
+                    ray.start_y,
+                    ray.end_x,
+                    ray.end_y,
+                    svg_paint("stroke", ray.color),
+                    ray.thickness
+                )?;
+            }
+        }
+    }
+
+    writeln!(file, "</svg>")?;
+    file.flush()
+}
+
+/// Gets the path of the file that stores a preset slot
+///
+/// # Arguments
+///
+/// * `slot` - The preset slot, from 1 to 9
+pub fn preset_path(slot: usize) -> String {
+    format!("{}/slot_{}.csv", APP_PRESETS_DIR, slot)
+}
+
+/// Saves the scene to a preset slot, replacing what was saved there before
+///
+/// The preset is a CSV layout in `APP_PRESETS_DIR` (see `save_csv()`), the
+/// directory is created if it doesn't exist.
+///
+/// # Arguments
+///
+/// * `slot` - The preset slot, from 1 to 9
+///
+/// # Returns
+///
+/// * `Ok(usize)` - The number of objects saved
+/// * `Err(std::io::Error)` - If the directory or file could not be written
+pub fn save_preset(slot: usize) -> std::io::Result<usize> {
+    fs::create_dir_all(APP_PRESETS_DIR)?;
+    save_csv(&preset_path(slot))
+}
+
+/// Replaces the scene with the objects saved in a preset slot
+///
+/// The whole preset is read before the scene is cleared, so if nothing was
+/// saved to the slot, or the preset can't be read, the scene is left
+/// unchanged. The replaced objects are returned so the load can be undone
+/// with `restore_scene()`. The caller is responsible for reinitializing the
+/// rays and for dropping any indices into the old scene (e.g. the selection).
+///
+/// # Arguments
+///
+/// * `slot` - The preset slot, from 1 to 9
+///
+/// # Returns
+///
+/// * `Ok(Some((usize, Vec<RaytracerObjects>)))` - The number of objects
+///   loaded, and the objects of the scene they replaced
+/// * `Ok(None)` - If the slot is empty
+/// * `Err(std::io::Error)` - If the file could not be read, or its version is
+///   invalid or not supported
+pub fn load_preset(slot: usize) -> std::io::Result<Option<(usize, Vec<RaytracerObjects>)>> {
+    let path = preset_path(slot);
+    if !fs::exists(&path)? {
+        return Ok(None);
+    }
+
+    let objects = read_csv(&path, 1)?;
+    let replaced = std::mem::take(&mut *OBJ_COLLECTION.write().unwrap());
+    mark_absorbers_changed();
+    Ok(Some((add_csv_objects(objects, &path), replaced)))
+}
+
+/// Puts back the scene replaced by `load_preset()`
+///
+/// The objects of the preset are dropped. Like `load_preset()`, the caller is
+/// responsible for reinitializing the rays and for dropping any indices into
+/// the preset's scene.
+///
+/// # Returns
+///
+/// The number of objects put back
+pub fn restore_scene(objects: Vec<RaytracerObjects>) -> usize {
+    let restored = objects.len();
+    *OBJ_COLLECTION.write().unwrap() = objects;
+    mark_absorbers_changed();
+    restored
+}
+
+/// Gets the path given with the `--background` command line option
+///
+/// Both `--background <path>` and `--background=<path>` are accepted.
+///
+/// # Arguments
+///
+/// * `args` - The command line arguments, without the program name
+///
+/// # Returns
+///
+/// * `Some(String)` - The path of the background image
+/// * `None` - If the option was not given (or has no value)
+pub fn background_path_from_args(args: impl IntoIterator<Item = String>) -> Option<String> {
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if arg == "--background" {
+            return args.next();
+        } else if let Some(path) = arg.strip_prefix("--background=") {
+            return Some(path.to_string());
+        }
+    }
+
+    None
+}
+
+/// Loads an image to be drawn behind the scene
+///
+/// # Arguments
+///
+/// * `path` - The path of the image, any format supported by Macroquad (e.g. PNG)
+///
+/// # Returns
+///
+/// * `Some(Texture2D)` - The loaded image
+/// * `None` - If the image could not be loaded, a warning is printed
+pub async fn load_background(path: &str) -> Option<Texture2D> {
+    match load_texture(path).await {
+        Ok(texture) => {
+            println!("Raytracer Upd: Loaded background image {}", path);
+            Some(texture)
+        }
+        Err(e) => {
+            eprintln!(
+                "Raytracer Err: Failed to load background image {}: {}",
+                path, e
+            );
+            None
+        }
+    }
+}
+
+/// Draws the background image stretched over the whole window
+///
+/// This should be called right after clearing the screen so that the rays
+/// and objects are drawn over it.
+pub fn draw_background(texture: &Texture2D) {
+    draw_texture_ex(
+        texture,
+        0.0,
+        0.0,
+        WHITE,
+        DrawTextureParams {
+            dest_size: Some(vec2(screen_width(), screen_height())),
+            ..Default::default()
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn colors_are_written_as_svg_paint() {
+        assert_eq!(
+            svg_paint("fill", Color::new(1.0, 0.5, 0.0, 0.25)),
+            "fill=\"rgb(255,128,0)\" fill-opacity=\"0.25\""
+        );
+    }
+
+    #[test]
+    fn preset_slots_map_to_files() {
+        assert_eq!(preset_path(1), format!("{}/slot_1.csv", APP_PRESETS_DIR));
+        assert_eq!(preset_path(9), format!("{}/slot_9.csv", APP_PRESETS_DIR));
+    }
+
+    #[test]
+    fn rows_are_parsed_with_optional_param() {
+        assert_eq!(
+            parse_csv_row("emitter_spotlight, 100, 200, 30, 45"),
+            Ok(CsvRow {
+                object_type: "emitter_spotlight".to_string(),
+                pos_x: 100.0,
+                pos_y: 200.0,
+                radius: 30.0,
+                param: Some(45.0),
+                ..Default::default()
+            })
+        );
+        assert_eq!(parse_csv_row("absorber_perfect,1,2,3").unwrap().param, None);
+        assert_eq!(
+            parse_csv_row("absorber_perfect,1,2,3,").unwrap().param,
+            None
+        );
+    }
+
+    #[test]
+    fn saved_rows_can_be_loaded() {
+        use crate::objects::circle::ObjectCircle;
+        use macroquad::color::WHITE;
+
+        let object = RaytracerObjects::Absorbers(Absorbers::AbsorberPerfect(
+            crate::objects::absorber::AbsorberPerfect::new(ObjectCircle::new(
+                12.5, 40.0, WHITE, 30.0,
+            )),
+        ));
+        let row = object_to_csv_row(&object);
+
+        assert_eq!(row, "absorber_perfect,12.5,40,30,,,0,,,,,,,");
+        assert_eq!(
+            parse_csv_row(&row),
+            Ok(CsvRow {
+                object_type: "absorber_perfect".to_string(),
+                pos_x: 12.5,
+                pos_y: 40.0,
+                radius: 30.0,
+                ..Default::default()
+            })
+        );
+    }
+
+    #[test]
+    fn groups_and_locks_are_saved() {
+        use crate::objects::circle::ObjectCircle;
+        use macroquad::color::WHITE;
+
+        let mut object = RaytracerObjects::ObjectCircle(ObjectCircle::new(1.0, 2.0, WHITE, 3.0));
+        object.get_base_object_mut().group_id = Some(4);
+        object.get_base_object_mut().locked = true;
+        let row = parse_csv_row(&object_to_csv_row(&object)).unwrap();
+
+        assert_eq!((row.group_id, row.locked), (Some(4), true));
+        assert!(parse_csv_row("circle_none,1,2,3,,x").is_err());
+        assert!(parse_csv_row("circle_none,1,2,3,,,yes").is_err());
+    }
+
+    #[test]
+    fn emitter_settings_are_saved() {
+        use crate::objects::circle::ObjectCircle;
+        use crate::objects::emitters::{EmitterSpotlight, EmitterTargeted};
+        use macroquad::color::WHITE;
+
+        let mut spotlight = Emitters::EmitterSpotlight(EmitterSpotlight::new(
+            ObjectCircle::new(1.0, 2.0, WHITE, 3.0),
+            vec![],
+            90f32.to_radians(),
+            30f32.to_radians(),
+        ));
+        spotlight.set_enabled(false);
+        spotlight.set_wavelength(Some(500.0));
+        spotlight.set_max_length(Some(250.0));
+        let row =
+            parse_csv_row(&object_to_csv_row(&RaytracerObjects::Emitters(spotlight))).unwrap();
+
+        assert!((row.param.unwrap() - 30.0).abs() < 1e-3);
+        assert!((row.orientation.unwrap() - 90.0).abs() < 1e-3);
+        assert_eq!(row.enabled, Some(false));
+        assert_eq!(row.wavelength, Some(500.0));
+        assert_eq!(row.range, Some(250.0));
+        assert!(row.ray_count.is_some() && row.ray_width.is_some());
+
+        let targeted = RaytracerObjects::Emitters(Emitters::EmitterTargeted(EmitterTargeted::new(
+            ObjectCircle::new(1.0, 2.0, WHITE, 3.0),
+            vec![],
+            vec![(10.0, 20.0), (-5.5, 0.0)],
+        )));
+        let row = parse_csv_row(&object_to_csv_row(&targeted)).unwrap();
+
+        assert_eq!(row.targets, Some(vec![(10.0, 20.0), (-5.5, 0.0)]));
+        assert_eq!(row.ray_count, None);
+    }
+
+    #[test]
+    fn emitters_past_the_ray_count_cap_survive_a_round_trip() {
+        use crate::globals::OBJC_MAX_RAY_COUNT;
+        use crate::objects::circle::ObjectCircle;
+        use crate::objects::emitters::EmitterIsotropic;
+        use macroquad::color::WHITE;
+
+        let ray_count = OBJC_MAX_RAY_COUNT + 50;
+        let mut emitter = Emitters::EmitterIsotropic(EmitterIsotropic::new(
+            ObjectCircle::new(1.0, 2.0, WHITE, 3.0),
+            vec![],
+        ));
+        emitter.get_base_emitter_mut().ray_count = ray_count;
+        let row = parse_csv_row(&object_to_csv_row(&RaytracerObjects::Emitters(emitter))).unwrap();
+
+        let mut loaded = Emitters::EmitterIsotropic(EmitterIsotropic::new(
+            ObjectCircle::new(1.0, 2.0, WHITE, 3.0),
+            vec![],
+        ));
+        assert_eq!(apply_emitter_columns(&mut loaded, &row), Ok(()));
+        assert_eq!(loaded.get_base_emitter().ray_count, ray_count);
+
+        let too_few = CsvRow {
+            ray_count: Some(1),
+            ..row
+        };
+        assert!(apply_emitter_columns(&mut loaded, &too_few).is_err());
+    }
+
+    #[test]
+    fn arcs_keep_their_span_and_start() {
+        let mut row = parse_csv_row("absorber_arc,10,20,30,45,,0,,120").unwrap();
+        let object = object_from_row(&row).unwrap();
+        let RaytracerObjects::Absorbers(Absorbers::AbsorberArc(arc)) = &object else {
+            panic!("expected an arc absorber");
+        };
+
+        assert!((arc.start_angle - 120f32.to_radians()).abs() < 1e-4);
+        assert!((arc.span() - 45f32.to_radians()).abs() < 1e-4);
+        assert_eq!(
+            parse_csv_row(&object_to_csv_row(&object)).map(|saved| {
+                (
+                    (saved.param.unwrap() - 45.0).abs() < 1e-3,
+                    (saved.orientation.unwrap() - 120.0).abs() < 1e-3,
+                )
+            }),
+            Ok((true, true))
+        );
+
+        row.object_type = "not_a_type".to_string();
+        assert!(object_from_row(&row).is_err());
+    }
+
+    #[test]
+    fn layouts_are_read_before_anything_is_added() {
+        let path = std::env::temp_dir().join("raytracer_read_csv_test.csv");
+        let path = path.to_str().unwrap();
+
+        fs::write(path, "version,99\ncircle_none,1,2,3\n").unwrap();
+        assert!(read_csv(path, 1).is_err());
+
+        fs::write(
+            path,
+            "version,2\ncircle_none,1,2,3,,7,0\nnot_a_type,1,2,3\ncircle_none,4,5,6,,7,1\n",
+        )
+        .unwrap();
+        let objects = read_csv(path, 3).unwrap();
+        fs::remove_file(path).unwrap();
+
+        assert_eq!(
+            objects.iter().map(|(line, _)| *line).collect::<Vec<_>>(),
+            vec![2, 4]
+        );
+        // group 7 of the file becomes the first free group id
+        assert!(
+            objects
+                .iter()
+                .all(|(_, o)| o.get_base_object().group_id == Some(3))
+        );
+        assert!(objects[1].1.is_locked());
+    }
+
+    #[test]
+    fn version_rows_are_parsed() {
+        assert_eq!(parse_version_row("version,1"), Some(Ok(1)));
+        assert_eq!(parse_version_row("version, 2 "), Some(Ok(2)));
+        assert!(matches!(parse_version_row("version,one"), Some(Err(_))));
+        assert_eq!(
+            parse_version_row("type,x,y,radius,param,group,locked"),
+            None
+        );
+    }
+
+    #[test]
+    fn background_option_is_found() {
+        let args = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(
+            background_path_from_args(args(&["--background", "diagram.png"])),
+            Some("diagram.png".to_string())
+        );
+        assert_eq!(
+            background_path_from_args(args(&["--background=diagram.png"])),
+            Some("diagram.png".to_string())
+        );
+        assert_eq!(background_path_from_args(args(&["--background"])), None);
+        assert_eq!(background_path_from_args(args(&[])), None);
+    }
+
+    #[test]
+    fn invalid_rows_are_rejected() {
+        assert!(parse_csv_row("emitter_isotropic,1,2").is_err());
+        assert!(parse_csv_row("emitter_isotropic,1,two,3").is_err());
+        assert!(parse_csv_row("emitter_isotropic,1,2,0").is_err());
+        assert!(parse_csv_row("emitter_isotropic,1,2,3,4,5,0,1,2,3,4,5,6,7,8").is_err());
+        assert!(parse_csv_row("emitter_isotropic,1,2,3,,,,,,2").is_err());
+        assert!(parse_csv_row("emitter_targeted,1,2,3,,,,,,,,,,1 2;3").is_err());
+    }
+}
@@ -0,0 +1,240 @@
+//! A log of the changes made to the scene
+//!
+//! `print_all_objects()` shows the whole scene, which makes it hard to tell
+//! what a sequence of edits actually did. The main loop records each
+//! operation (creating, moving, deleting, or editing objects) in a
+//! `SceneLog`, which prints only the operations made since it was last
+//! printed.
+//!
+//! author:         Zhean Ganituen (zrygan)
+//! last updated:   April 18, 2025
+
+use std::collections::VecDeque;
+use std::fmt;
+
+use macroquad::time::get_time;
+
+use crate::globals::APP_SCENE_LOG_MAX;
+
+/// A change made to the scene
+#[derive(Clone, Debug, PartialEq)]
+pub enum SceneOperation {
+    /// An object was created
+    Added {
+        /// The display name of the object
+        name: &'static str,
+        /// Where the object was created
+        position: (f32, f32),
+    },
+    /// An object (and the rest of its group) was dragged
+    Moved {
+        /// The index of the dragged object
+        index: usize,
+        /// Where the object was before the drag
+        from: (f32, f32),
+        /// Where the object is after the drag
+        to: (f32, f32),
+    },
+    /// An object was deleted
+    Deleted {
+        /// The type of the object, see `object_type_name()`
+        name: &'static str,
+        /// The index the object was at
+        index: usize,
+    },
+    /// The last deleted object was put back
+    Restored {
+        /// The index the object was put back at
+        index: usize,
+    },
+    /// Objects were changed in place, such as resized or rotated
+    Edited(String),
+    /// Objects were loaded from a file
+    Loaded {
+        /// How many objects were loaded
+        count: usize,
+        /// Where the objects were loaded from
+        source: String,
+    },
+    /// The scene was cleared and reset to its defaults
+    Reset,
+}
+
+impl fmt::Display for SceneOperation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SceneOperation::Added { name, position } => {
+                write!(f, "Added {} at {}, {}", name, position.0, position.1)
+            }
+            SceneOperation::Moved { index, from, to } => write!(
+                f,
+                "Moved object at index {} from {}, {} to {}, {}",
+                index, from.0, from.1, to.0, to.1
+            ),
+            SceneOperation::Deleted { name, index } => {
+                write!(f, "Deleted {} object at index {}", name, index)
+            }
+            SceneOperation::Restored { index } => {
+                write!(f, "Restored a deleted object at index {}", index)
+            }
+            SceneOperation::Edited(description) => write!(f, "{}", description),
+            SceneOperation::Loaded { count, source } => {
+                write!(f, "Loaded {} object(s) from {}", count, source)
+            }
+            SceneOperation::Reset => write!(f, "Reset the scene"),
+        }
+    }
+}
+
+/// A recorded operation
+#[derive(Clone, Debug)]
+struct SceneLogEntry {
+    /// When the operation was first made, from `get_time()`
+    time: f64,
+    /// The operation
+    operation: SceneOperation,
+    /// How many times in a row the operation was made, held keys repeat the
+    /// same edit every few frames
+    repeats: usize,
+}
+
+/// The operations made to the scene, oldest first
+///
+/// Only the latest `APP_SCENE_LOG_MAX` operations are kept.
+#[derive(Clone, Debug, Default)]
+pub struct SceneLog {
+    /// The recorded operations
+    entries: VecDeque<SceneLogEntry>,
+    /// How many of the entries have already been printed
+    printed: usize,
+}
+
+impl SceneLog {
+    /// Creates an empty log
+    pub fn new() -> SceneLog {
+        SceneLog::default()
+    }
+
+    /// Records an operation made now
+    ///
+    /// An operation that is the same as the previous one (and that has not
+    /// been printed yet) is counted as a repeat instead of a new entry.
+    ///
+    /// # Arguments
+    ///
+    /// * `operation` - The operation to record
+    pub fn record(&mut self, operation: SceneOperation) {
+        self.record_at(get_time(), operation);
+    }
+
+    /// Records an operation made at a time, see `record()`
+    fn record_at(&mut self, time: f64, operation: SceneOperation) {
+        if self.entries.len() > self.printed
+            && let Some(last) = self.entries.back_mut()
+            && last.operation == operation
+        {
+            last.repeats += 1;
+            return;
+        }
+
+        self.entries.push_back(SceneLogEntry {
+            time,
+            operation,
+            repeats: 1,
+        });
+        if self.entries.len() > APP_SCENE_LOG_MAX {
+            self.entries.pop_front();
+            self.printed = self.printed.saturating_sub(1);
+        }
+    }
+
+    /// Gets the operations made since the log was last printed
+    ///
+    /// # Returns
+    ///
+    /// Each operation as a line of text, with its time and how many times it
+    /// was repeated
+    fn unprinted_lines(&self) -> Vec<String> {
+        self.entries
+            .iter()
+            .skip(self.printed)
+            .map(|entry| {
+                let mut line = format!("[{:>8.2}s] {}", entry.time, entry.operation);
+                if entry.repeats > 1 {
+                    line.push_str(&format!(" (x{})", entry.repeats));
+                }
+                line
+            })
+            .collect()
+    }
+
+    /// Prints the operations made since the log was last printed
+    pub fn print_since_last(&mut self) {
+        let lines = self.unprinted_lines();
+        if lines.is_empty() {
+            println!("Raytracer Upd: The scene has not changed since the last print");
+        } else {
+            println!(
+                "Raytracer Upd: {} change(s) since the last print:",
+                lines.len()
+            );
+            for line in lines {
+                println!("    {}", line);
+            }
+        }
+
+        self.printed = self.entries.len();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_unprinted_operations_are_listed() {
+        let mut log = SceneLog::new();
+        log.record_at(1.0, SceneOperation::Reset);
+        log.record_at(
+            2.5,
+            SceneOperation::Deleted {
+                name: "Perfect",
+                index: 3,
+            },
+        );
+        assert_eq!(
+            log.unprinted_lines(),
+            vec![
+                "[    1.00s] Reset the scene",
+                "[    2.50s] Deleted Perfect object at index 3"
+            ]
+        );
+
+        log.print_since_last();
+        assert!(log.unprinted_lines().is_empty());
+
+        log.record_at(3.0, SceneOperation::Restored { index: 3 });
+        assert_eq!(log.unprinted_lines().len(), 1);
+    }
+
+    #[test]
+    fn repeated_operations_are_merged_until_printed() {
+        let mut log = SceneLog::new();
+        let edit = || SceneOperation::Edited("Enlarged 1 object(s)".to_string());
+        log.record_at(1.0, edit());
+        log.record_at(1.1, edit());
+        log.record_at(1.2, edit());
+        assert_eq!(
+            log.unprinted_lines(),
+            vec!["[    1.00s] Enlarged 1 object(s) (x3)"]
+        );
+
+        // a printed entry is not changed afterwards
+        log.print_since_last();
+        log.record_at(2.0, edit());
+        assert_eq!(
+            log.unprinted_lines(),
+            vec!["[    2.00s] Enlarged 1 object(s)"]
+        );
+    }
+}
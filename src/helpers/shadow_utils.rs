@@ -0,0 +1,201 @@
+//! Utility functions for visualizing the shadows cast by absorbers
+//!
+//! Occlusion truncates each ray at the first absorber it hits, but with few
+//! rays the shadow behind an absorber is hard to see. This module computes the
+//! whole shadow region geometrically so it can be filled with a dim overlay.
+//!
+//! For a point light the shadow is the cone between the two lines from the
+//! emitter tangent to the absorber. For a collimated emitter the rays are
+//! parallel, so the shadow is a band as wide as the absorber instead. The
+//! shadows ignore the extent of the beam (e.g. a spotlight's beam angle) and
+//! only perfect absorbers cast them, since an arc absorber only blocks part of
+//! its circle.
+//!
+//! author:         Zhean Ganituen (zrygan)
+//! last updated:   April 18, 2025
+
+use crate::globals::{OBJ_COLLECTION, OBJD_SHADOW_COLOR};
+use crate::objects::absorber::Absorbers;
+use crate::objects::behavior::RaytracerObjects;
+use crate::objects::emitters::Emitters;
+use macroquad::math::vec2;
+use macroquad::shapes::draw_triangle;
+use macroquad::window::{screen_height, screen_width};
+
+/// Gets the shadow a circle casts from a point light
+///
+/// # Arguments
+///
+/// * `light` - The position of the light
+/// * `center` - The center of the circle
+/// * `radius` - The radius of the circle
+/// * `length` - How far from the light the shadow extends
+///
+/// # Returns
+///
+/// * `Some([(f32, f32); 4])` - The corners of the shadow, in order: the two
+///   tangent points on the circle followed by the far ends of the two tangent
+///   lines
+/// * `None` - If the light is inside the circle, where there are no tangents
+pub fn point_shadow(
+    light: (f32, f32),
+    center: (f32, f32),
+    radius: f32,
+    length: f32,
+) -> Option<[(f32, f32); 4]> {
+    let (dx, dy) = (center.0 - light.0, center.1 - light.1);
+    let distance = (dx.powi(2) + dy.powi(2)).sqrt();
+    if distance <= radius {
+        return None;
+    }
+
+    // the tangent lines are the line to the center turned by +/- half_angle
+    let angle = dy.atan2(dx);
+    let half_angle = (radius / distance).asin();
+    let tangent_length = (distance.powi(2) - radius.powi(2)).sqrt();
+    let along = |angle: f32, length: f32| {
+        (
+            light.0 + angle.cos() * length,
+            light.1 + angle.sin() * length,
+        )
+    };
+
+    Some([
+        along(angle - half_angle, tangent_length),
+        along(angle + half_angle, tangent_length),
+        along(angle + half_angle, length.max(tangent_length)),
+        along(angle - half_angle, length.max(tangent_length)),
+    ])
+}
+
+/// Gets the shadow a circle casts from parallel light
+///
+/// # Arguments
+///
+/// * `light` - A point the light passes through (e.g. the emitter's center)
+/// * `direction` - The unit vector the light travels along
+/// * `center` - The center of the circle
+/// * `radius` - The radius of the circle
+/// * `length` - How far past the circle the shadow extends
+///
+/// # Returns
+///
+/// * `Some([(f32, f32); 4])` - The corners of the shadow, in order: the two
+///   points on the circle's sides followed by their far ends
+/// * `None` - If the circle is behind the light
+pub fn parallel_shadow(
+    light: (f32, f32),
+    direction: (f32, f32),
+    center: (f32, f32),
+    radius: f32,
+    length: f32,
+) -> Option<[(f32, f32); 4]> {
+    let ahead = (center.0 - light.0) * direction.0 + (center.1 - light.1) * direction.1;
+    if ahead <= 0.0 {
+        return None;
+    }
+
+    let (px, py) = (-direction.1 * radius, direction.0 * radius);
+    let (fx, fy) = (direction.0 * length, direction.1 * length);
+
+    Some([
+        (center.0 + px, center.1 + py),
+        (center.0 - px, center.1 - py),
+        (center.0 - px + fx, center.1 - py + fy),
+        (center.0 + px + fx, center.1 + py + fy),
+    ])
+}
+
+/// Fills the shadow region behind every perfect absorber for every emitter
+///
+/// The shadows are drawn with `OBJD_SHADOW_COLOR`, they are long enough to
+/// reach past the edge of the window.
+///
+/// # Thread Safety
+///
+/// This function acquires a read lock on the `OBJ_COLLECTION` global.
+pub fn draw_shadow_regions() {
+    let collection = OBJ_COLLECTION.read().unwrap();
+    let length = (screen_width().powi(2) + screen_height().powi(2)).sqrt();
+
+    let absorbers: Vec<((f32, f32), f32)> = collection
+        .iter()
+        .filter_map(|obj| match obj {
+            RaytracerObjects::Absorbers(Absorbers::AbsorberPerfect(o)) => Some((
+                (o.base_object.pos_x, o.base_object.pos_y),
+                o.base_object.radius,
+            )),
+            _ => None,
+        })
+        .collect();
+
+    for obj in collection.iter() {
+        let RaytracerObjects::Emitters(emitter) = obj else {
+            continue;
+        };
+        if !emitter.is_enabled() {
+            continue;
+        }
+        let base_object = emitter.get_base_object();
+        let light = (base_object.pos_x, base_object.pos_y);
+
+        for &(center, radius) in &absorbers {
+            let shadow = match emitter {
+                Emitters::EmitterCollimated(e) => parallel_shadow(
+                    light,
+                    (e.orientation.cos(), e.orientation.sin()),
+                    center,
+                    radius,
+                    length,
+                ),
+                _ => point_shadow(light, center, radius, length),
+            };
+
+            if let Some([a, b, c, d]) = shadow {
+                let [a, b, c, d] = [a, b, c, d].map(|(x, y)| vec2(x, y));
+                draw_triangle(a, b, c, OBJD_SHADOW_COLOR);
+                draw_triangle(a, c, d, OBJD_SHADOW_COLOR);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: (f32, f32), b: (f32, f32)) -> bool {
+        (a.0 - b.0).abs() < 1e-3 && (a.1 - b.1).abs() < 1e-3
+    }
+
+    #[test]
+    fn point_shadow_starts_at_tangent_points() {
+        // light at the origin, circle of radius 3 at distance 5, so the
+        // tangent points are 4 away from the light
+        let [t1, t2, f1, f2] = point_shadow((0.0, 0.0), (5.0, 0.0), 3.0, 100.0).unwrap();
+
+        assert!(close(t1, (3.2, -2.4)));
+        assert!(close(t2, (3.2, 2.4)));
+        // the far ends are on the same lines, 100 away from the light
+        assert!(close(f1, (t2.0 * 25.0, t2.1 * 25.0)));
+        assert!(close(f2, (t1.0 * 25.0, t1.1 * 25.0)));
+
+        assert_eq!(point_shadow((5.0, 1.0), (5.0, 0.0), 3.0, 100.0), None);
+    }
+
+    #[test]
+    fn parallel_shadow_is_as_wide_as_the_circle() {
+        let [a, b, c, d] = parallel_shadow((0.0, 0.0), (1.0, 0.0), (10.0, 0.0), 2.0, 50.0).unwrap();
+
+        assert!(close(a, (10.0, 2.0)));
+        assert!(close(b, (10.0, -2.0)));
+        assert!(close(c, (60.0, -2.0)));
+        assert!(close(d, (60.0, 2.0)));
+
+        // a circle behind the light casts no shadow
+        assert_eq!(
+            parallel_shadow((0.0, 0.0), (1.0, 0.0), (-10.0, 0.0), 2.0, 50.0),
+            None
+        );
+    }
+}
@@ -0,0 +1,152 @@
+//! Utility functions for advancing the simulation over time
+//!
+//! The main loop runs input, ray recomputation, and rendering once per frame,
+//! so anything that changes with time would otherwise depend on the frame rate.
+//! This module turns frame times into fixed time steps, which keeps time-based
+//! updates (such as held keys repeating their effect) the same regardless of FPS.
+//!
+//! author:         Zhean Ganituen (zrygan)
+//! last updated:   April 18, 2025
+
+/// An accumulator that turns variable frame times into fixed simulation steps
+///
+/// Each frame, the frame time is added to the accumulator and one step is taken
+/// for every whole `step` it contains. The remainder carries over to the next
+/// frame, so over one real second the simulation advances `1 / step` times.
+#[derive(Clone, Debug)]
+pub struct FixedTimestep {
+    /// The length of one simulation step (in seconds)
+    pub step: f32,
+    /// The most steps taken in a single frame, this keeps a long frame (e.g.
+    /// while the window is being dragged) from stalling the next frames
+    pub max_steps: u32,
+    /// The time not yet consumed by a step (in seconds)
+    pub accumulator: f32,
+}
+
+impl FixedTimestep {
+    /// Creates a new accumulator with no time accumulated
+    ///
+    /// # Arguments
+    ///
+    /// * `step` - The length of one simulation step (in seconds)
+    /// * `max_steps` - The most steps taken in a single frame
+    pub fn new(step: f32, max_steps: u32) -> FixedTimestep {
+        FixedTimestep {
+            step,
+            max_steps,
+            accumulator: 0.0,
+        }
+    }
+
+    /// Adds a frame's time to the accumulator and consumes it in whole steps
+    ///
+    /// If more than `max_steps` steps are due, the extra time is dropped.
+    ///
+    /// # Arguments
+    ///
+    /// * `frame_time` - The time since the last frame (in seconds)
+    ///
+    /// # Returns
+    ///
+    /// The number of simulation steps to run this frame
+    pub fn advance(&mut self, frame_time: f32) -> u32 {
+        self.accumulator += frame_time;
+
+        let mut steps = 0;
+        while self.accumulator >= self.step && steps < self.max_steps {
+            self.accumulator -= self.step;
+            steps += 1;
+        }
+
+        if steps == self.max_steps {
+            self.accumulator = self.accumulator.min(self.step);
+        }
+
+        steps
+    }
+}
+
+/// Checks whether a held adjustment key (enlarge, rotate, add rays, ...)
+/// should apply its effect this frame
+///
+/// A key applies its effect as soon as it is pressed, and then once every
+/// `clock.step` seconds while it is held, however fast the frames are. The
+/// clock should have a `max_steps` of 1 so a long frame does not repeat the
+/// effect several times at once.
+///
+/// # Arguments
+///
+/// * `clock` - The repeat timer, shared by every adjustment key
+/// * `pressed` - Whether an adjustment key was pressed this frame
+/// * `held` - Whether an adjustment key is down
+/// * `frame_time` - The time since the last frame (in seconds)
+///
+/// # Returns
+///
+/// `true` if the held keys apply their effect this frame
+pub fn key_repeat_due(
+    clock: &mut FixedTimestep,
+    pressed: bool,
+    held: bool,
+    frame_time: f32,
+) -> bool {
+    if pressed || !held {
+        clock.accumulator = 0.0;
+        return pressed;
+    }
+
+    clock.advance(frame_time) > 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn steps_are_independent_of_frame_rate() {
+        // one second at 30 FPS and at 144 FPS both take 60 steps
+        for fps in [30.0, 144.0] {
+            let mut clock = FixedTimestep::new(1.0 / 60.0, 10);
+            let steps: u32 = (0..fps as u32).map(|_| clock.advance(1.0 / fps)).sum();
+            assert!(
+                (59..=60).contains(&steps),
+                "{} FPS took {} steps",
+                fps,
+                steps
+            );
+        }
+    }
+
+    #[test]
+    fn held_keys_repeat_at_a_fixed_rate() {
+        // holding a key for one second repeats it 20 times at any frame rate
+        for fps in [30.0, 144.0] {
+            let mut clock = FixedTimestep::new(0.05, 1);
+            let mut repeats = u32::from(key_repeat_due(&mut clock, true, true, 1.0 / fps));
+            for _ in 0..fps as u32 {
+                repeats += u32::from(key_repeat_due(&mut clock, false, true, 1.0 / fps));
+            }
+            assert!(
+                (20..=21).contains(&repeats),
+                "{} FPS repeated {} times",
+                fps,
+                repeats
+            );
+        }
+
+        // releasing the key starts over
+        let mut clock = FixedTimestep::new(0.05, 1);
+        key_repeat_due(&mut clock, false, true, 0.04);
+        assert!(!key_repeat_due(&mut clock, false, false, 0.04));
+        assert!(!key_repeat_due(&mut clock, false, true, 0.04));
+    }
+
+    #[test]
+    fn long_frames_are_capped() {
+        let mut clock = FixedTimestep::new(0.1, 3);
+        assert_eq!(clock.advance(5.0), 3);
+        // the dropped time is not made up on the next frame
+        assert!(clock.advance(0.0) <= 1);
+    }
+}
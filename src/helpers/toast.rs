@@ -0,0 +1,144 @@
+//! On-screen notifications
+//!
+//! Most actions only print to the terminal, which users running the Raytracer
+//! without one never see. A toast shows a short message on screen as well,
+//! fading out over its duration. Toasts are queued from anywhere with
+//! `push_toast()` and stacked in the top right corner, or shown next to a
+//! point (such as the cursor) with `push_toast_at()`. The main loop draws them
+//! every frame with `draw_toasts()`.
+//!
+//! author:         Zhean Ganituen (zrygan)
+//! last updated:   April 18, 2025
+
+use crate::globals::{HUD_TOAST_FONT_SIZE, HUD_TOAST_MARGIN, HUD_TOAST_MAX, HUD_TOAST_OFFSET};
+use macroquad::color::Color;
+use macroquad::text::{draw_text, measure_text};
+use macroquad::time::get_time;
+use macroquad::window::screen_width;
+use once_cell::sync::Lazy;
+use std::sync::RwLock;
+
+/// The toasts that have not faded out yet, oldest first
+static TOASTS: Lazy<RwLock<Vec<Toast>>> = Lazy::new(|| RwLock::new(Vec::new()));
+
+/// A message shown on screen for a while
+struct Toast {
+    /// The text to show
+    message: String,
+    /// The point the toast is shown next to, `None` to stack it in the corner
+    position: Option<(f32, f32)>,
+    /// When the toast was shown, from `get_time()`
+    shown_at: f64,
+    /// How long the toast takes to fade out, in seconds
+    duration: f64,
+}
+
+/// Shows a message in the top right corner of the window
+///
+/// Newer toasts are stacked below older ones. Once more than `HUD_TOAST_MAX`
+/// toasts are stacked, the oldest one is dropped.
+///
+/// # Arguments
+///
+/// * `message` - The text to show
+/// * `duration` - How long the toast takes to fade out, in seconds
+pub fn push_toast(message: impl Into<String>, duration: f64) {
+    push(message.into(), None, duration);
+}
+
+/// Shows a message next to a point, such as where the user clicked
+///
+/// # Arguments
+///
+/// * `message` - The text to show
+/// * `duration` - How long the toast takes to fade out, in seconds
+/// * `position` - The point to show the toast next to, offset by
+///   `HUD_TOAST_OFFSET`
+pub fn push_toast_at(message: impl Into<String>, duration: f64, position: (f32, f32)) {
+    push(message.into(), Some(position), duration);
+}
+
+/// Adds a toast to the queue, see `push_toast()`
+fn push(message: String, position: Option<(f32, f32)>, duration: f64) {
+    let mut toasts = TOASTS.write().unwrap();
+    toasts.push(Toast {
+        message,
+        position,
+        shown_at: get_time(),
+        duration,
+    });
+
+    let stacked = toasts.iter().filter(|t| t.position.is_none()).count();
+    if stacked > HUD_TOAST_MAX
+        && let Some(oldest) = toasts.iter().position(|t| t.position.is_none())
+    {
+        toasts.remove(oldest);
+    }
+}
+
+/// Draws every toast and drops the ones that have faded out
+///
+/// # Arguments
+///
+/// * `color` - The color of the text, its alpha is scaled as the toasts fade
+pub fn draw_toasts(color: Color) {
+    let mut toasts = TOASTS.write().unwrap();
+    let now = get_time();
+    toasts.retain(|t| toast_alpha(now - t.shown_at, t.duration).is_some());
+
+    let mut row = 0;
+    for toast in toasts.iter() {
+        let Some(alpha) = toast_alpha(now - toast.shown_at, toast.duration) else {
+            continue;
+        };
+        let color = Color {
+            a: color.a * alpha,
+            ..color
+        };
+
+        let (x, y) = match toast.position {
+            Some((x, y)) => (x + HUD_TOAST_OFFSET.0, y + HUD_TOAST_OFFSET.1),
+            None => {
+                row += 1;
+                let width =
+                    measure_text(&toast.message, None, HUD_TOAST_FONT_SIZE as u16, 1.0).width;
+                (
+                    screen_width() - width - HUD_TOAST_MARGIN,
+                    HUD_TOAST_MARGIN + HUD_TOAST_FONT_SIZE * row as f32,
+                )
+            }
+        };
+
+        draw_text(&toast.message, x, y, HUD_TOAST_FONT_SIZE, color);
+    }
+}
+
+/// Gets how opaque a toast is
+///
+/// Toasts start fully opaque and fade out linearly over their duration.
+///
+/// # Arguments
+///
+/// * `age` - How long ago the toast was shown, in seconds
+/// * `duration` - How long the toast takes to fade out, in seconds
+///
+/// # Returns
+///
+/// The alpha (from 0 to 1) to scale the toast's color by, or `None` once the
+/// toast has faded out
+fn toast_alpha(age: f64, duration: f64) -> Option<f32> {
+    (age < duration).then(|| (1.0 - age.max(0.0) / duration) as f32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn toasts_fade_out_over_their_duration() {
+        assert_eq!(toast_alpha(0.0, 2.0), Some(1.0));
+        assert_eq!(toast_alpha(1.5, 2.0), Some(0.25));
+        assert_eq!(toast_alpha(2.0, 2.0), None);
+        assert_eq!(toast_alpha(1.0, 0.0), None);
+    }
+}
@@ -7,26 +7,62 @@
 //! author:         Zhean Ganituen (zrygan)
 //! last updated:   April 18, 2025
 
+mod errors;
 mod globals;
 mod helpers;
 mod objects;
 mod user_input;
 
-use globals::*;
+use globals::{
+    collection::{for_each_object, map_objects, mark_absorbers_changed},
+    *,
+};
 use helpers::{
     action_utils::{
-        object_at_cursor_index, object_at_cursor_type, print_all_objects, remove_object_at_index,
+        creation_drag_size, edit_targets, get_object_scope, group_deletion_order, group_members,
+        index_after_insertion, index_after_move, index_after_removal, next_group_id, next_index,
+        object_at_cursor_index, object_at_cursor_type, object_type_name, previous_index,
+        print_all_objects, remove_from_selection, remove_object_at_index, reset_scene_to_defaults,
+        stop_at_objects, take_last_deleted, unlocked_targets,
+    },
+    coverage_utils::{emitter_blocked_fraction, illuminated_fraction},
+    export_utils::{export_lightmap_png, export_rays_json, export_render_png},
+    image_source_utils::image_source,
+    lightmap_utils::Lightmap,
+    object_utils::{
+        center_all_objects_in_window, clamp_all_objects_to_screen, clamp_to_window,
+        find_ray_intersections, init_all_rays, insert_object_at, move_object_to_index,
+        ray_signature, total_ray_count, wrap_to_window,
     },
-    object_utils::init_all_rays,
+    scene_io::{
+        background_path_from_args, draw_background, export_svg, load_background, load_csv,
+        load_preset, preset_path, restore_scene, save_preset,
+    },
+    scene_log::{SceneLog, SceneOperation},
+    shadow_utils::draw_shadow_regions,
+    simulation_utils::{FixedTimestep, key_repeat_due},
+    toast::{draw_toasts, push_toast, push_toast_at},
 };
 use macroquad::prelude::*;
-use macroquad::time::draw_fps;
+use macroquad::time::{draw_fps, get_time};
 use objects::emitters::*;
-use objects::{behavior::*, occlusion::check_for_occlusion};
+use objects::{
+    behavior::*,
+    occlusion::check_for_occlusion,
+    ray::{ObjectRay, additive_ray_material},
+};
 use std::{thread::sleep, time::Duration};
 use user_input::{
-    add_to_scene_actions::add_object_to_scene,
-    emitter_actions::{object_change_orientation, object_change_size},
+    add_to_scene_actions::{add_object_to_scene, add_preset_to_scene, draw_object_preview},
+    console::{Console, ConsoleCommand, ConsoleState},
+    emitter_actions::{
+        autotune_ray_counts, convert_emitter, next_emitter_type, object_aim_at,
+        object_change_orientation, object_change_size, object_change_size_scaling_rays,
+        object_reset_orientation,
+    },
+    help_overlay::{draw_help_overlay, help_key_pressed, help_page_count},
+    measure::AngleMeasure,
+    numeric_entry::{NumericEntry, NumericEntryState},
 };
 
 /// Configures the application window settings.
@@ -69,14 +105,89 @@ async fn main() {
     // is moved.
     let mut re_init_rays: bool = false;
 
-    let mut cursor_on_object_index: Option<usize> = None;
+    let mut cursor_on_object_index: Option<usize>;
     let mut cursor_on_object_type: &'static str;
     let mut cursor_is_moving_object: bool = false;
+    // the object being dragged, kept separate from the hovered object so key
+    // handlers can't change it mid-drag
+    let mut dragged_object_index: Option<usize> = None;
+    // object center minus cursor position when the drag started
+    let mut drag_offset: (f32, f32) = (0.0, 0.0);
+    // where the left mouse button was pressed, a drag only starts once the
+    // cursor moves more than OBJC_DRAG_THRESHOLD away from it
+    let mut mouse_down_position: Option<(f32, f32)> = None;
+    // the emitter being aimed at the cursor, grabbed with KEYB_RTC_ROTATE_DRAG
+    // held instead of being moved
+    let mut rotated_object_index: Option<usize> = None;
+    // deleted objects and the index they had, the last one is restored first
+    let mut deleted_objects: Vec<(usize, RaytracerObjects)> = Vec::new();
+    // the scene replaced by the last preset load, restored once every object
+    // deleted since has been restored
+    let mut replaced_scene: Option<Vec<RaytracerObjects>> = None;
+    // indices of the selected objects, edits apply to all of them at once
+    let mut selected_objects: Vec<usize> = Vec::new();
+    // the object picked with KEYB_SELECT_NEXT, edits apply to it instead of
+    // the object at the cursor
+    let mut selected_index: Option<usize> = None;
     let mut mouse_x: f32;
     let mut mouse_y: f32;
     let mut mouse_delta: Vec2 = vec2(0.0, 0.0);
     let mut collection_size = 0;
     let mut ft;
+    // held adjustment keys repeat on this timer instead of every frame
+    let mut key_repeat_clock = FixedTimestep::new(KEYB_REPEAT_INTERVAL, 1);
+    let mut window_size: (f32, f32) = (screen_width(), screen_height());
+    let mut numeric_entry: Option<NumericEntry> = None;
+    let mut angle_measure: Option<AngleMeasure> = None;
+    let mut console: Option<Console> = None;
+    // the page of the help overlay, when it is open
+    let mut help_page: Option<usize> = None;
+    let mut scene_log = SceneLog::new();
+    // where the dragged object was when the drag started
+    let mut drag_start_position: (f32, f32) = (0.0, 0.0);
+    // the creation key being held, the object type, its display name, and
+    // where the cursor was when the key was pressed
+    let mut pending_creation: Option<(KeyCode, &'static str, &'static str, (f32, f32))> = None;
+    // how long the last occlusion pass took (in seconds), shown in the HUD to
+    // tell occlusion cost apart from drawing cost
+    let mut occlusion_time: f64 = 0.0;
+    // the scene the rays were last built for, see `ray_signature()`, and how
+    // many ray passes were run or skipped because it had not changed
+    let mut last_ray_signature: Option<u64> = None;
+    let mut ray_passes: (u32, u32) = (0, 0);
+    // emitters inside an absorber, all of their light is absorbed at the source
+    let mut buried_emitters: Vec<usize> = Vec::new();
+    // how long the previous frame slept to cap the frame rate, so the tuner
+    // only sees the time spent working
+    let mut frame_sleep: f32 = 0.0;
+    let mut ray_intersections: Vec<(f32, f32)> = Vec::new();
+    let mut export_rays_requested: bool = false;
+    let mut export_svg_requested: bool = false;
+    let mut export_render_requested: bool = false;
+    // frozen copies of emitter rays, drawn faintly and never occluded
+    let mut ray_snapshots: Vec<ObjectRay> = Vec::new();
+    // the (emitter, absorber) indices whose image source is shown, and the
+    // image source's position and phantom rays
+    let mut image_source_pair: Option<(usize, usize)> = None;
+    let mut image_source_view: Option<((f32, f32), Vec<ObjectRay>)> = None;
+    // rays accumulated over time for a long exposure, `None` when off
+    let mut lightmap: Option<Lightmap> = None;
+    // optional image drawn behind the scene, given with `--background <image>`
+    let background = match background_path_from_args(std::env::args().skip(1)) {
+        Some(path) => load_background(&path).await,
+        None => None,
+    };
+    // without it, the additive rays toggle does nothing
+    let additive_material = match additive_ray_material() {
+        Ok(material) => Some(material),
+        Err(e) => {
+            eprintln!(
+                "Raytracer Err: Failed to create the additive ray material: {}",
+                e
+            );
+            None
+        }
+    };
 
     // print app information
     println!(
@@ -85,87 +196,305 @@ async fn main() {
     );
 
     loop {
-        let keybind_increase_rays =
-            is_key_pressed(KEYB_EMM_INC_RAYS) || is_key_down(KEYB_EMM_INC_RAYS);
-        let keybind_decrease_rays =
-            is_key_pressed(KEYB_EMM_DEC_RAYS) || is_key_down(KEYB_EMM_DEC_RAYS);
+        ft = get_frame_time();
 
-        let keybind_emitter_secondary_inc =
-            is_key_pressed(KEYB_EMM_SEC_INC) || is_key_down(KEYB_EMM_SEC_INC);
+        // keys that repeat while held, at a fixed rate regardless of FPS
+        let adjustment_keys = [
+            KEYB_EMM_INC_RAYS,
+            KEYB_EMM_DEC_RAYS,
+            KEYB_EMM_SEC_INC,
+            KEYB_EMM_SEC_DEC,
+            KEYB_RTC_ENLARGE,
+            KEYB_RTC_SHRINK,
+            KEYB_RTC_INC_ORIENTATION,
+            KEYB_RTC_DEC_ORIENTATION,
+        ];
+        let repeat_due = key_repeat_due(
+            &mut key_repeat_clock,
+            adjustment_keys.iter().any(|key| is_key_pressed(*key)),
+            adjustment_keys.iter().any(|key| is_key_down(*key)),
+            ft,
+        );
+        let adjustment_key = |key: KeyCode| repeat_due && is_key_down(key);
 
-        let keybind_emitter_secondary_dec =
-            is_key_pressed(KEYB_EMM_SEC_DEC) || is_key_down(KEYB_EMM_SEC_DEC);
+        let keybind_increase_rays = adjustment_key(KEYB_EMM_INC_RAYS);
+        let keybind_decrease_rays = adjustment_key(KEYB_EMM_DEC_RAYS);
+
+        let keybind_emitter_secondary_inc = adjustment_key(KEYB_EMM_SEC_INC);
+
+        let keybind_emitter_secondary_dec = adjustment_key(KEYB_EMM_SEC_DEC);
+
+        // the preset slot (1 to 9) whose key was pressed, if any
+        let keybind_preset_slot = KEYB_PRESET_SLOTS
+            .iter()
+            .position(|key| is_key_pressed(*key))
+            .map(|position| position + 1);
 
-        ft = get_frame_time();
         // Clear the screen with the background color
-        clear_background(WINDOW_BG_COLOR);
+        let theme = *THEME.read().unwrap();
+        clear_background(theme.background);
+        if let Some(texture) = &background {
+            draw_background(texture);
+        }
         draw_fps();
         (mouse_x, mouse_y) = mouse_position();
 
+        // While the help overlay is open, it captures all keyboard input
+        if let Some(page) = help_page.as_mut() {
+            if help_key_pressed() || is_key_pressed(KeyCode::Escape) {
+                help_page = None;
+            } else if is_key_pressed(HELP_NEXT_PAGE) {
+                *page = (*page + 1).min(help_page_count(screen_width(), screen_height()) - 1);
+            } else if is_key_pressed(HELP_PREV_PAGE) {
+                *page = page.saturating_sub(1);
+            }
+        }
+        // While the numeric entry is open, it captures all keyboard input
+        else if let Some(entry) = numeric_entry.as_mut() {
+            match entry.capture_input() {
+                NumericEntryState::Editing => {}
+                NumericEntryState::Submitted => match entry.apply() {
+                    Ok(()) => {
+                        println!(
+                            "Raytracer Upd: Set {} to {} for object at index {}",
+                            entry.field().label(),
+                            entry.buffer,
+                            entry.target_index
+                        );
+                        scene_log.record(SceneOperation::Edited(format!(
+                            "Set {} to {} for object at index {}",
+                            entry.field().label(),
+                            entry.buffer,
+                            entry.target_index
+                        )));
+                        numeric_entry = None;
+                        re_init_rays = true;
+                    }
+                    Err(e) => {
+                        println!("Raytracer ~Err: Failed to set value, {}", e);
+                        entry.buffer.clear();
+                    }
+                },
+                NumericEntryState::Cancelled => {
+                    println!("Raytracer Upd: Closed numeric entry.");
+                    numeric_entry = None;
+                }
+            }
+        }
+        // While the console is open, it captures all keyboard input
+        else if let Some(open_console) = console.as_mut() {
+            match open_console.capture_input() {
+                ConsoleState::Editing => {}
+                ConsoleState::Submitted(line) => {
+                    let result = ConsoleCommand::parse(&line).and_then(|command| {
+                        let message = command.execute(&selected_objects)?;
+                        if command == ConsoleCommand::Clear {
+                            deleted_objects.clear();
+                            replaced_scene = None;
+                            selected_objects.clear();
+                            selected_index = None;
+                            image_source_pair = None;
+                            image_source_view = None;
+                            dragged_object_index = None;
+                            rotated_object_index = None;
+                        }
+                        Ok(message)
+                    });
+
+                    match result {
+                        Ok(message) => {
+                            println!("Raytracer Upd: {}", message);
+                            scene_log.record(SceneOperation::Edited(format!("Ran `{}`", line)));
+                            open_console.output = message;
+                            collection_size = OBJ_COLLECTION.read().unwrap().len();
+                            re_init_rays = true;
+                        }
+                        Err(e) => {
+                            println!("Raytracer ~Err: Console command failed, {}", e);
+                            open_console.output = e;
+                        }
+                    }
+                }
+                ConsoleState::Closed => {
+                    println!("Raytracer Upd: Closed console.");
+                    console = None;
+                }
+            }
+        }
+        // Reset everything to a clean slate, even when the scene is full
+        else if is_key_pressed(KEYB_RESET_ALL) {
+            reset_scene_to_defaults();
+            collection_size = 0;
+            deleted_objects.clear();
+            replaced_scene = None;
+            selected_objects.clear();
+            selected_index = None;
+            pending_creation = None;
+            dragged_object_index = None;
+            rotated_object_index = None;
+            cursor_is_moving_object = false;
+            ray_intersections.clear();
+            ray_snapshots.clear();
+            image_source_pair = None;
+            image_source_view = None;
+            lightmap = None;
+            re_init_rays = true;
+            println!("Raytracer Upd: Reset the scene and all settings to their defaults");
+            scene_log.record(SceneOperation::Reset);
+        }
+        // Help is always available, even when the scene is full
+        else if help_key_pressed() {
+            help_page = Some(0);
+        }
+        // The console can clear or edit a full scene, so it can be opened too
+        else if is_key_pressed(KEYB_CONSOLE) {
+            console = Some(Console::open());
+        }
+        // Presets replace the scene, so they can be loaded even when it is full
+        else if let Some(slot) = keybind_preset_slot {
+            if is_key_down(KEYB_SAVE_PRESET_MODIFIER) {
+                match save_preset(slot) {
+                    Ok(saved) => {
+                        println!(
+                            "Raytracer Upd: Saved {} object(s) to preset {}",
+                            saved, slot
+                        );
+                        push_toast(format!("Saved preset {}", slot), HUD_TOAST_DURATION);
+                    }
+                    Err(e) => {
+                        eprintln!("Raytracer Err: Failed to save {}: {}", preset_path(slot), e);
+                        push_toast(
+                            format!("Failed to save preset {}: {}", slot, e),
+                            HUD_TOAST_DURATION,
+                        );
+                    }
+                }
+            } else {
+                match load_preset(slot) {
+                    Ok(Some((loaded, replaced))) => {
+                        println!(
+                            "Raytracer Upd: Loaded {} object(s) from preset {}",
+                            loaded, slot
+                        );
+                        push_toast(format!("Loaded preset {}", slot), HUD_TOAST_DURATION);
+                        scene_log.record(SceneOperation::Loaded {
+                            count: loaded,
+                            source: preset_path(slot),
+                        });
+                        // the old indices point into a different scene, the
+                        // old scene itself can still be restored
+                        deleted_objects.clear();
+                        replaced_scene = Some(replaced);
+                        selected_objects.clear();
+                        selected_index = None;
+                        dragged_object_index = None;
+                        rotated_object_index = None;
+                        image_source_pair = None;
+                        image_source_view = None;
+                        collection_size = OBJ_COLLECTION.read().unwrap().len();
+                        re_init_rays = true;
+                    }
+                    Ok(None) => println!(
+                        "Raytracer ~Err: Nothing is saved in preset {}, save it first with {:?}+{}",
+                        slot, KEYB_SAVE_PRESET_MODIFIER, slot
+                    ),
+                    Err(e) => {
+                        eprintln!("Raytracer Err: Failed to load {}: {}", preset_path(slot), e);
+                        push_toast(
+                            format!("Failed to load preset {}: {}", slot, e),
+                            HUD_TOAST_DURATION,
+                        );
+                    }
+                }
+            }
+        }
         // Handle user input for object creation
-        if OBJC_MAX_OBJ_COUNT as usize > collection_size {
+        else if OBJC_MAX_OBJ_COUNT as usize > collection_size {
             // ============================================================
-            // =============== EMITTERS
+            // =============== CREATION (previewed until the key is released)
             // ============================================================
             if is_key_pressed(KEYB_SIMPLE_CIRCLE) {
-                println!(
-                    "Raytracer Upd: Simple circle created at {}, {}",
-                    mouse_x, mouse_y
-                );
-                add_object_to_scene("circle_none");
-                re_init_rays = true;
-                collection_size += 1;
+                pending_creation = Some((
+                    KEYB_SIMPLE_CIRCLE,
+                    "circle_none",
+                    "Simple circle",
+                    (mouse_x, mouse_y),
+                ));
             } else if is_key_pressed(KEYB_EMITTER_ISOTROPIC) {
-                println!(
-                    "Raytracer Upd: Isotropic emitter object created at {}, {}",
-                    mouse_x, mouse_y
-                );
-                add_object_to_scene("emitter_isotropic");
-                re_init_rays = true;
-                collection_size += 1;
+                pending_creation = Some((
+                    KEYB_EMITTER_ISOTROPIC,
+                    "emitter_isotropic",
+                    "Isotropic emitter object",
+                    (mouse_x, mouse_y),
+                ));
             } else if is_key_pressed(KEYB_EMITTER_COLLIMATED) {
-                println!(
-                    "Raytracer Upd: Collimated emitter object created at {}, {}",
-                    mouse_x, mouse_y
-                );
-                add_object_to_scene("emitter_collimated");
-                re_init_rays = true;
-                collection_size += 1;
+                pending_creation = Some((
+                    KEYB_EMITTER_COLLIMATED,
+                    "emitter_collimated",
+                    "Collimated emitter object",
+                    (mouse_x, mouse_y),
+                ));
             } else if is_key_pressed(KEYB_EMITTER_SPOTLIGHT) {
-                println!(
-                    "Raytracer Upd: Spotlight emitter object created at {}, {}",
-                    mouse_x, mouse_y
-                );
-                add_object_to_scene("emitter_spotlight");
-                re_init_rays = true;
-                collection_size += 1;
+                pending_creation = Some((
+                    KEYB_EMITTER_SPOTLIGHT,
+                    "emitter_spotlight",
+                    "Spotlight emitter object",
+                    (mouse_x, mouse_y),
+                ));
+            } else if is_key_pressed(KEYB_EMITTER_CONVERGENT) {
+                pending_creation = Some((
+                    KEYB_EMITTER_CONVERGENT,
+                    "emitter_convergent",
+                    "Convergent emitter object",
+                    (mouse_x, mouse_y),
+                ));
+            } else if is_key_pressed(KEYB_EMITTER_TARGETED) {
+                pending_creation = Some((
+                    KEYB_EMITTER_TARGETED,
+                    "emitter_targeted",
+                    "Targeted emitter object",
+                    (mouse_x, mouse_y),
+                ));
             }
             // ============================================================
             // =============== INCREASE/DECREASE EMITTER RAYS
             // ============================================================
             else if keybind_increase_rays || keybind_decrease_rays {
-                cursor_on_object_type = object_at_cursor_type(mouse_x, mouse_y, false);
-                cursor_on_object_index = object_at_cursor_index(mouse_x, mouse_y);
-
-                if cursor_on_object_type == "Emitter" {
-                    if let Some(index) = cursor_on_object_index {
-                        let mut collection = OBJ_COLLECTION.write().unwrap();
+                let ray_delta = if keybind_increase_rays { 1 } else { -1 };
+                let mut collection = OBJ_COLLECTION.write().unwrap();
+                let mut edited = 0;
 
-                        if let Some(RaytracerObjects::Emitters(o)) = collection.get_mut(index) {
-                            let ray_delta = if keybind_increase_rays { 1 } else { -1 };
-                            o.change_rays_count(ray_delta);
+                let (targets, locked) = unlocked_targets(
+                    &collection,
+                    edit_targets(&selected_objects, selected_index, mouse_x, mouse_y),
+                );
+                if locked > 0 {
+                    println!(
+                        "Raytracer ~Err: Cannot change the rays of {} locked object(s)",
+                        locked
+                    );
+                }
 
-                            println!(
-                                "Raytracer Upd: {} rays to Emitter object at {}, {}",
-                                if ray_delta > 0 { "Adding" } else { "Reducing" },
-                                mouse_x,
-                                mouse_y
-                            );
+                for index in targets {
+                    let scene_total_rays = total_ray_count(&collection);
 
-                            re_init_rays = true;
+                    if let Some(RaytracerObjects::Emitters(o)) = collection.get_mut(index) {
+                        match o.change_rays_count(ray_delta, scene_total_rays) {
+                            Ok(()) => edited += 1,
+                            Err(e) => println!("Raytracer ~Err: Cannot change rays, {}", e),
                         }
                     }
                 }
+
+                if edited > 0 {
+                    println!(
+                        "Raytracer Upd: {} rays to {} Emitter object(s)",
+                        if ray_delta > 0 { "Adding" } else { "Reducing" },
+                        edited
+                    );
+
+                    re_init_rays = true;
+                }
             }
             // ============================================================
             // =============== INCREASE/DECREASE SECONDARY TRAIT
@@ -173,99 +502,61 @@ async fn main() {
             // ===== SPOTLIGHT  = Spotlight Beam Angle
             // ============================================================
             else if keybind_emitter_secondary_inc || keybind_emitter_secondary_dec {
-                cursor_on_object_type = object_at_cursor_type(mouse_x, mouse_y, true);
-                cursor_on_object_index = object_at_cursor_index(mouse_x, mouse_y);
+                let mut collection = OBJ_COLLECTION.write().unwrap();
+                let mut multiplier = if keybind_emitter_secondary_inc {
+                    1.0
+                } else {
+                    -1.0
+                };
+                if is_key_down(KeyCode::LeftShift) {
+                    multiplier *= KEYB_RTC_MULTIPLIER as f32;
+                }
 
-                if let Some(index) = cursor_on_object_index {
-                    let mut collection = OBJ_COLLECTION.write().unwrap();
-                    if cursor_on_object_type == "Collimated" {
-                        if let Some(RaytracerObjects::Emitters(Emitters::EmitterCollimated(o))) =
-                            collection.get_mut(index)
-                        {
-                            let mut width_delta = if keybind_emitter_secondary_inc {
-                                KEYB_EMM_SEC_COLL_WIDTH_DELTA
-                            } else {
-                                -KEYB_EMM_SEC_COLL_WIDTH_DELTA
-                            };
-
-                            if is_key_down(KeyCode::LeftShift) {
-                                width_delta *= KEYB_RTC_MULTIPLIER
-                            };
-
-                            // Make sure we don't go below minimum width
-                            if o.collimated_beam_diameter + width_delta as f32 <= 0.0
-                                && width_delta < 0
-                            {
-                                println!("Raytracer ~Err: Cannot decrease beam diameter below 0");
-                                // Skip the update
-                            } else {
-                                // Apply the width change
-                                o.collimated_beam_diameter += width_delta as f32;
-
-                                println!(
-                                    "Raytracer Upd: {} collimated beam diameter to Emitter object at {}, {}",
-                                    if width_delta > 0 {
-                                        "Increasing"
-                                    } else {
-                                        "Decreasing"
-                                    },
-                                    mouse_x,
-                                    mouse_y
-                                );
-
-                                re_init_rays = true;
-                            }
+                let (targets, locked) = unlocked_targets(
+                    &collection,
+                    edit_targets(&selected_objects, selected_index, mouse_x, mouse_y),
+                );
+                if locked > 0 {
+                    println!("Raytracer ~Err: Cannot change {} locked object(s)", locked);
+                }
+
+                for index in targets {
+                    let Some(object) = collection.get_mut(index) else {
+                        continue;
+                    };
+                    let (param, delta) = match object {
+                        RaytracerObjects::Emitters(Emitters::EmitterCollimated(_)) => (
+                            ObjectParam::BeamDiameter,
+                            KEYB_EMM_SEC_COLL_WIDTH_DELTA as f32,
+                        ),
+                        RaytracerObjects::Emitters(Emitters::EmitterSpotlight(_)) => {
+                            (ObjectParam::BeamAngle, KEYB_EMM_SEC_SPOT_ANGLE_DELTA)
                         }
-                    } else if cursor_on_object_type == "Spotlight" {
-                        if let Some(RaytracerObjects::Emitters(Emitters::EmitterSpotlight(o))) =
-                            collection.get_mut(index)
-                        {
-                            let mut angle_delta = if keybind_emitter_secondary_inc {
-                                KEYB_EMM_SEC_SPOT_ANGLE_DELTA
-                            } else {
-                                -KEYB_EMM_SEC_SPOT_ANGLE_DELTA
-                            };
-
-                            if is_key_down(KeyCode::LeftShift) {
-                                angle_delta *= KEYB_RTC_MULTIPLIER as f32;
-                            };
-
-                            // Define min and max angle bounds (0 to 2π)
-                            let min_angle: f32 = 0.0;
-                            let max_angle: f32 = std::f32::consts::PI * 2.0; // 360 degrees in radians
-
-                            // Check if new angle would be outside bounds
-                            let new_angle = o.spotlight_beam_angle + angle_delta;
-
-                            if new_angle < min_angle && angle_delta < 0.0 {
-                                println!(
-                                    "Raytracer ~Err: Cannot decrease spotlight beam angle below 0 radians"
-                                );
-                                // Skip the update
-                            } else if new_angle > max_angle && angle_delta > 0.0 {
-                                println!(
-                                    "Raytracer ~Err: Cannot increase spotlight beam angle above 2π radians (360°)"
-                                );
-                                // Skip the update
-                            } else {
-                                // Apply the angle change
-                                o.spotlight_beam_angle = new_angle;
-
-                                println!(
-                                    "Raytracer Upd: {} spotlight beam angle to Emitter object at {}, {} (current: {:.2} radians)",
-                                    if angle_delta > 0.0 {
-                                        "Increasing"
-                                    } else {
-                                        "Decreasing"
-                                    },
-                                    mouse_x,
-                                    mouse_y,
-                                    o.spotlight_beam_angle
-                                );
-
-                                re_init_rays = true;
-                            }
+                        _ => continue,
+                    };
+                    let Some(value) = object.params().get(param) else {
+                        continue;
+                    };
+
+                    match object.set_param(param, value + multiplier * delta) {
+                        Ok(needs_re_init) => {
+                            let (pos_x, pos_y) = object.get_pos();
+                            println!(
+                                "Raytracer Upd: {} {} of Emitter object at {}, {} (current: {:.2})",
+                                if multiplier > 0.0 {
+                                    "Increasing"
+                                } else {
+                                    "Decreasing"
+                                },
+                                param,
+                                pos_x,
+                                pos_y,
+                                value + multiplier * delta
+                            );
+
+                            re_init_rays |= needs_re_init;
                         }
+                        Err(e) => println!("Raytracer ~Err: Cannot change {}, {}", param, e),
                     }
                 }
             }
@@ -273,20 +564,40 @@ async fn main() {
             // =============== ABSORBERS
             // ============================================================
             else if is_key_pressed(KEYB_ABSORBER_PERFECT) {
-                println!(
-                    "Raytracer Upd: Perfect absorber object created at {}, {}",
-                    mouse_x, mouse_y
-                );
-                add_object_to_scene("absorber_perfect");
-                re_init_rays = true;
-                collection_size += 1;
+                pending_creation = Some((
+                    KEYB_ABSORBER_PERFECT,
+                    "absorber_perfect",
+                    "Perfect absorber object",
+                    (mouse_x, mouse_y),
+                ));
+            } else if is_key_pressed(KEYB_ABSORBER_ARC) {
+                pending_creation = Some((
+                    KEYB_ABSORBER_ARC,
+                    "absorber_arc",
+                    "Arc absorber object",
+                    (mouse_x, mouse_y),
+                ));
+            } else if is_key_pressed(KEYB_MIRROR_ARC) {
+                pending_creation = Some((
+                    KEYB_MIRROR_ARC,
+                    "mirror_arc",
+                    "Arc mirror object",
+                    (mouse_x, mouse_y),
+                ));
             }
             // ============================================================
             // =============== ENLARGE AND REDUCE
             // ============================================================
-            else if is_key_down(KEYB_RTC_ENLARGE) || is_key_down(KEYB_RTC_SHRINK) {
-                let cursor_object_type = object_at_cursor_type(mouse_x, mouse_y, false);
-                if cursor_object_type != "None" {
+            else if adjustment_key(KEYB_RTC_ENLARGE) || adjustment_key(KEYB_RTC_SHRINK) {
+                let (targets, locked) = unlocked_targets(
+                    &OBJ_COLLECTION.read().unwrap(),
+                    edit_targets(&selected_objects, selected_index, mouse_x, mouse_y),
+                );
+                if locked > 0 {
+                    println!("Raytracer ~Err: Cannot resize {} locked object(s)", locked);
+                }
+
+                if !targets.is_empty() {
                     let mut multiplier = if is_key_down(KEYB_RTC_ENLARGE) {
                         1.
                     } else {
@@ -297,20 +608,39 @@ async fn main() {
                         multiplier *= KEYB_RTC_MULTIPLIER as f32;
                     }
 
-                    object_change_size(mouse_x, mouse_y, multiplier * OBJD_SIZE_DELTA_FACTOR);
+                    for index in &targets {
+                        if OBJC_SCALE_RAYS_WITH_SIZE {
+                            if let Err(e) = object_change_size_scaling_rays(
+                                *index,
+                                multiplier * OBJD_SIZE_DELTA_FACTOR,
+                            ) {
+                                println!("Raytracer ~Err: Resized without changing rays, {}", e);
+                            }
+                        } else {
+                            object_change_size(*index, multiplier * OBJD_SIZE_DELTA_FACTOR);
+                        }
+                    }
 
                     println!(
-                        "Raytracer Upd: {} object at {}, {}",
-                        if multiplier == 1. {
+                        "Raytracer Upd: {} {} object(s)",
+                        if multiplier > 0. {
                             "Enlarged"
                         } else {
                             "Shrunk"
                         },
-                        mouse_x,
-                        mouse_y
+                        targets.len()
                     );
+                    scene_log.record(SceneOperation::Edited(format!(
+                        "{} object(s) at {:?}",
+                        if multiplier > 0. {
+                            "Enlarged"
+                        } else {
+                            "Shrunk"
+                        },
+                        targets
+                    )));
                     re_init_rays = true;
-                } else {
+                } else if locked == 0 {
                     println!(
                         "Raytracer ~Err: Failed to enlarge or shrink an object, there is no object at {}, {}",
                         mouse_x, mouse_y
@@ -320,9 +650,18 @@ async fn main() {
             // ============================================================
             // =============== CHANGE ORIENTATION
             // ============================================================
-            else if is_key_down(KEYB_RTC_INC_ORIENTATION) || is_key_down(KEYB_RTC_DEC_ORIENTATION)
+            else if adjustment_key(KEYB_RTC_INC_ORIENTATION)
+                || adjustment_key(KEYB_RTC_DEC_ORIENTATION)
             {
-                if object_at_cursor_type(mouse_x, mouse_y, false) != "None" {
+                let (targets, locked) = unlocked_targets(
+                    &OBJ_COLLECTION.read().unwrap(),
+                    edit_targets(&selected_objects, selected_index, mouse_x, mouse_y),
+                );
+                if locked > 0 {
+                    println!("Raytracer ~Err: Cannot rotate {} locked object(s)", locked);
+                }
+
+                if !targets.is_empty() {
                     let mut delta = if is_key_down(KEYB_RTC_INC_ORIENTATION) {
                         OBJD_ORIENTATION_DELTA_FACTOR
                     } else {
@@ -333,21 +672,26 @@ async fn main() {
                         delta *= KEYB_RTC_MULTIPLIER as f32;
                     }
 
-                    object_change_orientation(mouse_x, mouse_y, delta);
+                    for index in &targets {
+                        object_change_orientation(*index, delta);
+                    }
 
                     println!(
-                        "Raytracer Upd: {} orientation for object at {}, {}",
+                        "Raytracer Upd: {} orientation for {} object(s)",
                         if delta > 0.0 {
                             "Increased"
                         } else {
                             "Decreased"
                         },
-                        mouse_x,
-                        mouse_y
+                        targets.len()
                     );
+                    scene_log.record(SceneOperation::Edited(format!(
+                        "Rotated object(s) at {:?}",
+                        targets
+                    )));
 
                     re_init_rays = true;
-                } else {
+                } else if locked == 0 {
                     println!(
                         "Raytracer ~Err: Failed to change orientation, there is no object at {}, {}",
                         mouse_x, mouse_y
@@ -355,105 +699,1417 @@ async fn main() {
                 }
             }
             // ============================================================
-            // =============== DEBUG AND OTHER KEYBINDS
+            // =============== CONVERT EMITTER TYPE
             // ============================================================
-            else if is_key_pressed(KEYB_DELETE) {
-                if collection_size >= 1 {
-                    if let Some(i) = object_at_cursor_index(mouse_x, mouse_y) {
-                        println!("Raytracer Upd: Deleted object at {}, {}", mouse_x, mouse_y);
-                        remove_object_at_index(i);
-                        re_init_rays = true;
-                        collection_size -= 1;
-                    } else {
-                        println!(
-                            "Raytracer ~Err: Failed to delete object, there is no object at {}, {}",
-                            mouse_x, mouse_y
-                        );
-                    }
+            else if is_key_pressed(KEYB_EMM_CONVERT) {
+                cursor_on_object_type = object_at_cursor_type(mouse_x, mouse_y, true);
+                cursor_on_object_index = object_at_cursor_index(mouse_x, mouse_y);
+                let target = next_emitter_type(cursor_on_object_type);
+
+                if cursor_on_object_index
+                    .is_some_and(|i| OBJ_COLLECTION.read().unwrap()[i].is_locked())
+                {
+                    println!(
+                        "Raytracer ~Err: Cannot convert the emitter at {}, {}, it is locked",
+                        mouse_x, mouse_y
+                    );
+                } else if let Some(index) = cursor_on_object_index
+                    && convert_emitter(index, target)
+                {
+                    println!(
+                        "Raytracer Upd: Converted {} emitter at {}, {} to {}",
+                        cursor_on_object_type, mouse_x, mouse_y, target
+                    );
+                    scene_log.record(SceneOperation::Edited(format!(
+                        "Converted the emitter at index {} to {}",
+                        index, target
+                    )));
+                    re_init_rays = true;
                 } else {
                     println!(
-                        "Raytracer ~Err: Failed to delete object, there is no object on the scene"
-                    )
+                        "Raytracer ~Err: Failed to convert, there is no emitter at {}, {}",
+                        mouse_x, mouse_y
+                    );
                 }
-            } else if is_key_pressed(KEYB_DEBUG_SHOW_ALL_OBJ) {
-                println!("Raytracer Debug: Showing all objects inside OBJ_COLLECTION.");
-                print_all_objects();
-                println!("Raytracer Debug: Done showing all objects in OBJ_COLLECTION.");
             }
-        } else {
-            eprintln!(
-                "Raytracer Err: Too many RaytracerObjects in the scene, you can only have {}",
-                OBJC_MAX_OBJ_COUNT
-            );
-        }
+            // ============================================================
+            // =============== ENABLE/DISABLE EMITTERS
+            // ============================================================
+            else if is_key_pressed(KEYB_EMM_TOGGLE_ENABLED) {
+                let mut collection = OBJ_COLLECTION.write().unwrap();
+                let mut toggled = 0;
+
+                let (targets, locked) = unlocked_targets(
+                    &collection,
+                    edit_targets(&selected_objects, selected_index, mouse_x, mouse_y),
+                );
+                if locked > 0 {
+                    println!(
+                        "Raytracer ~Err: Cannot switch {} locked object(s) on or off",
+                        locked
+                    );
+                }
+
+                for index in targets {
+                    if let Some(RaytracerObjects::Emitters(o)) = collection.get_mut(index) {
+                        o.set_enabled(!o.is_enabled());
+                        toggled += 1;
+                    }
+                }
 
-        // Check if the user wants to move an object
-        if is_mouse_button_down(MouseButton::Left) {
-            cursor_on_object_index = object_at_cursor_index(mouse_x, mouse_y);
-            if cursor_on_object_index.is_some() {
-                cursor_is_moving_object = true
+                if toggled > 0 {
+                    println!("Raytracer Upd: Switched {} emitter(s) on or off", toggled);
+                    scene_log.record(SceneOperation::Edited(format!(
+                        "Switched {} emitter(s) on or off",
+                        toggled
+                    )));
+                    re_init_rays = true;
+                } else if locked == 0 {
+                    println!(
+                        "Raytracer ~Err: Failed to switch emitter, there is no emitter at {}, {}",
+                        mouse_x, mouse_y
+                    );
+                }
             }
-        }
+            // ============================================================
+            // =============== FREEZE RAYS (SNAPSHOTS)
+            // ============================================================
+            else if is_key_pressed(KEYB_EMM_SNAPSHOT_RAYS) {
+                cursor_on_object_index = object_at_cursor_index(mouse_x, mouse_y);
 
-        // If the user is not moving an object, remove dragging_index
-        if !is_mouse_button_down(MouseButton::Left) && cursor_is_moving_object == true {
-            println!("Raytracer Upd: Stopped moving object.");
-            cursor_is_moving_object = false;
-        }
+                if let Some(index) = cursor_on_object_index
+                    && let Some(RaytracerObjects::Emitters(o)) =
+                        OBJ_COLLECTION.read().unwrap().get(index)
+                {
+                    ray_snapshots.extend(o.get_rays().iter().cloned().map(|mut ray| {
+                        ray.color.a = OBJD_SNAPSHOT_ALPHA;
+                        ray
+                    }));
+                    println!(
+                        "Raytracer Upd: Froze the rays of Emitter object at {}, {}",
+                        mouse_x, mouse_y
+                    );
+                } else {
+                    println!(
+                        "Raytracer ~Err: Failed to freeze rays, there is no emitter at {}, {}",
+                        mouse_x, mouse_y
+                    );
+                }
+            } else if is_key_pressed(KEYB_CLEAR_SNAPSHOTS) {
+                ray_snapshots.clear();
+                println!("Raytracer Upd: Cleared all frozen rays.");
+            }
+            // ============================================================
+            // =============== MEASURE ANGLE
+            // ============================================================
+            else if is_key_pressed(KEYB_MEASURE_ANGLE) && is_key_down(KEYB_MEASURE_ANGLE_MODIFIER)
+            {
+                angle_measure = match angle_measure {
+                    Some(_) => None,
+                    None => Some(AngleMeasure::new()),
+                };
+                let message = if angle_measure.is_some() {
+                    "Angle measuring on, click a vertex and two arms"
+                } else {
+                    "Angle measuring off"
+                };
+                println!("Raytracer Upd: {}.", message);
+                push_toast(message.to_string(), HUD_TOAST_DURATION);
+            }
+            // ============================================================
+            // =============== IMAGE SOURCE (selected emitter and absorber)
+            // ============================================================
+            else if is_key_pressed(KEYB_IMAGE_SOURCE) {
+                if image_source_pair.is_some() {
+                    image_source_pair = None;
+                    println!("Raytracer Upd: Hid the image source.");
+                } else {
+                    let collection = OBJ_COLLECTION.read().unwrap();
+                    let selected_of = |wanted: &str| {
+                        selected_objects.iter().copied().find(|i| {
+                            collection
+                                .get(*i)
+                                .is_some_and(|o| object_type_name(o, false) == wanted)
+                        })
+                    };
 
-        // If user is moving the cursor and is dragging an object,
-        // move that object
-        if mouse_delta != vec2(0.0, 0.0) && cursor_is_moving_object {
-            if let Some(index) = cursor_on_object_index {
-                let mut collection = OBJ_COLLECTION.write().unwrap();
-                if let Some(object) = collection.get_mut(index) {
-                    match object {
-                        RaytracerObjects::ObjectCircle(o) => {
-                            o.move_object(mouse_x, mouse_y);
-                        }
-                        RaytracerObjects::Emitters(o) => {
-                            o.move_object(mouse_x, mouse_y);
-                        }
-                        RaytracerObjects::Absorbers(o) => {
-                            o.move_object(mouse_x, mouse_y);
+                    match (selected_of("Emitter"), selected_of("Absorber")) {
+                        (Some(emitter), Some(absorber)) if selected_objects.len() == 2 => {
+                            image_source_pair = Some((emitter, absorber));
+                            println!(
+                                "Raytracer Upd: Showing the image source of the selected emitter."
+                            );
                         }
+                        _ => println!(
+                            "Raytracer ~Err: Select exactly one emitter and one absorber to show an image source"
+                        ),
                     }
-                    re_init_rays = true;
                 }
+
+                re_init_rays = true;
             }
-        }
+            // ============================================================
+            // =============== AIM AT CURSOR
+            // ============================================================
+            else if is_key_pressed(KEYB_RTC_AIM_AT_CURSOR) && is_key_down(KEYB_RTC_AIM_MODIFIER) {
+                let mut aimed = 0;
+                let (targets, locked) = unlocked_targets(
+                    &OBJ_COLLECTION.read().unwrap(),
+                    edit_targets(&selected_objects, selected_index, mouse_x, mouse_y),
+                );
 
-        if re_init_rays {
-            // re-initialize all rays
-            init_all_rays();
+                for index in targets {
+                    if let Some(orientation) = object_aim_at(index, mouse_x, mouse_y) {
+                        println!(
+                            "Raytracer Upd: Aimed object at index {} at the cursor, orientation {:.1} degrees",
+                            index,
+                            orientation.to_degrees()
+                        );
+                        scene_log.record(SceneOperation::Edited(format!(
+                            "Aimed object at index {} at {}, {}",
+                            index, mouse_x, mouse_y
+                        )));
+                        aimed += 1;
+                    }
+                }
 
-            // Check for occlusion
-            check_for_occlusion();
+                if locked > 0 {
+                    println!("Raytracer ~Err: Cannot aim {} locked object(s)", locked);
+                }
 
-            re_init_rays = false;
-        }
+                if aimed > 0 {
+                    re_init_rays = true;
+                } else if locked == 0 {
+                    println!(
+                        "Raytracer ~Err: Only collimated, spotlight, and convergent emitters can be aimed"
+                    );
+                }
+            }
+            // ============================================================
+            // =============== RESET ORIENTATION
+            // ============================================================
+            else if is_key_pressed(KEYB_RTC_RESET_ORIENTATION) {
+                let mut reset = 0;
+                let (targets, locked) = unlocked_targets(
+                    &OBJ_COLLECTION.read().unwrap(),
+                    edit_targets(&selected_objects, selected_index, mouse_x, mouse_y),
+                );
 
-        // Draw all objects in the global collection
-        for r_obj in OBJ_COLLECTION.read().unwrap().iter() {
-            match r_obj {
-                RaytracerObjects::ObjectCircle(object) => {
-                    object.draw_object();
+                for index in targets {
+                    if object_reset_orientation(index) {
+                        reset += 1;
+                    }
                 }
-                RaytracerObjects::Emitters(object) => {
-                    object.draw_object();
+
+                if locked > 0 {
+                    println!(
+                        "Raytracer ~Err: Cannot reset the orientation of {} locked object(s)",
+                        locked
+                    );
                 }
-                RaytracerObjects::Absorbers(object) => {
-                    object.draw_object();
+
+                if reset > 0 {
+                    println!("Raytracer Upd: Reset orientation for {} object(s)", reset);
+                    scene_log.record(SceneOperation::Edited(format!(
+                        "Reset orientation for {} object(s)",
+                        reset
+                    )));
+
+                    re_init_rays = true;
+                } else if locked == 0 {
+                    println!(
+                        "Raytracer ~Err: Only collimated, spotlight, and convergent emitters have an orientation to reset"
+                    );
                 }
             }
+            // ============================================================
+            // =============== SELECTION
+            // ============================================================
+            else if is_key_pressed(KEYB_SELECT_TOGGLE) {
+                if let Some(i) = object_at_cursor_index(mouse_x, mouse_y) {
+                    // a group is selected or deselected as a whole
+                    let mut members = group_members(&OBJ_COLLECTION.read().unwrap(), i);
+                    members.push(i);
+
+                    if selected_objects.contains(&i) {
+                        selected_objects.retain(|s| !members.contains(s));
+                        println!(
+                            "Raytracer Upd: Deselected {} object(s) at {}, {}",
+                            members.len(),
+                            mouse_x,
+                            mouse_y
+                        );
+                    } else {
+                        for member in &members {
+                            if !selected_objects.contains(member) {
+                                selected_objects.push(*member);
+                            }
+                        }
+                        println!(
+                            "Raytracer Upd: Selected {} object(s) at {}, {}",
+                            members.len(),
+                            mouse_x,
+                            mouse_y
+                        );
+                    }
+                } else {
+                    println!(
+                        "Raytracer ~Err: Failed to select object, there is no object at {}, {}",
+                        mouse_x, mouse_y
+                    );
+                }
+            } else if is_key_pressed(KEYB_SELECT_NEXT) {
+                let collection = OBJ_COLLECTION.read().unwrap();
+                // Shift cycles backward
+                selected_index =
+                    if is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift) {
+                        previous_index(selected_index, collection.len())
+                    } else {
+                        next_index(selected_index, collection.len())
+                    };
+                match selected_index {
+                    Some(i) => {
+                        let (x, y) = collection[i].get_pos();
+                        println!(
+                            "Raytracer Upd: Keyboard selection is now the {} at {}, {}",
+                            object_type_name(&collection[i], true),
+                            x,
+                            y
+                        );
+                    }
+                    None => println!(
+                        "Raytracer ~Err: Failed to select object, there is no object on the scene"
+                    ),
+                }
+            } else if is_key_pressed(KEYB_SELECT_CLEAR)
+                && (!selected_objects.is_empty() || selected_index.is_some())
+            {
+                selected_objects.clear();
+                selected_index = None;
+                println!("Raytracer Upd: Cleared selection.");
+            } else if is_key_pressed(KEYB_GROUP) {
+                let mut collection = OBJ_COLLECTION.write().unwrap();
+                let (targets, locked) = unlocked_targets(&collection, selected_objects.clone());
+                if locked > 0 {
+                    println!("Raytracer ~Err: Cannot group {} locked object(s)", locked);
+                }
+
+                if targets.len() >= 2 {
+                    let group_id = next_group_id(&collection);
+                    for index in &targets {
+                        if let Some(object) = collection.get_mut(*index) {
+                            object.get_base_object_mut().group_id = Some(group_id);
+                        }
+                    }
+                    println!(
+                        "Raytracer Upd: Grouped {} objects as group {}",
+                        targets.len(),
+                        group_id
+                    );
+                } else {
+                    println!("Raytracer ~Err: Select at least two unlocked objects to group them");
+                }
+            } else if is_key_pressed(KEYB_UNGROUP) {
+                let mut collection = OBJ_COLLECTION.write().unwrap();
+                let mut ungrouped = Vec::new();
+                let (targets, locked) = unlocked_targets(
+                    &collection,
+                    edit_targets(&selected_objects, selected_index, mouse_x, mouse_y),
+                );
+                if locked > 0 {
+                    println!("Raytracer ~Err: Cannot ungroup {} locked object(s)", locked);
+                }
+
+                for index in targets {
+                    if let Some(group_id) = collection
+                        .get(index)
+                        .and_then(|o| o.get_base_object().group_id)
+                        && !ungrouped.contains(&group_id)
+                    {
+                        ungrouped.push(group_id);
+                    }
+                }
+
+                // locked members are not edited, they stay in the group
+                for object in collection.iter_mut() {
+                    let base_object = object.get_base_object_mut();
+                    if !base_object.locked
+                        && base_object
+                            .group_id
+                            .is_some_and(|id| ungrouped.contains(&id))
+                    {
+                        base_object.group_id = None;
+                    }
+                }
+
+                if !ungrouped.is_empty() {
+                    println!("Raytracer Upd: Ungrouped group(s) {:?}", ungrouped);
+                } else if locked == 0 {
+                    println!("Raytracer ~Err: There is no group to ungroup");
+                }
+            }
+            // ============================================================
+            // =============== DEBUG AND OTHER KEYBINDS
+            // ============================================================
+            else if is_key_pressed(KEYB_DELETE) {
+                if collection_size >= 1 {
+                    let cursor_index = object_at_cursor_index(mouse_x, mouse_y);
+                    if cursor_index.is_some_and(|i| OBJ_COLLECTION.read().unwrap()[i].is_locked()) {
+                        println!(
+                            "Raytracer ~Err: Cannot delete the object at {}, {}, it is locked",
+                            mouse_x, mouse_y
+                        );
+                    } else if let Some(index) = cursor_index {
+                        // the rest of its group (if any) is deleted with it
+                        let indices = group_deletion_order(&OBJ_COLLECTION.read().unwrap(), index);
+                        for i in indices {
+                            match remove_object_at_index(i) {
+                                Ok(removed) => {
+                                    let (x, y) = removed.get_pos();
+                                    println!(
+                                        "Raytracer Upd: Deleted {} object at {}, {}",
+                                        object_type_name(&removed, true),
+                                        x,
+                                        y
+                                    );
+                                    push_toast(
+                                        format!("Deleted {}", object_type_name(&removed, true)),
+                                        HUD_TOAST_DURATION,
+                                    );
+                                    remove_from_selection(&mut selected_objects, i);
+                                    selected_index =
+                                        selected_index.and_then(|s| index_after_removal(s, i));
+                                    image_source_pair = image_source_pair.and_then(|(e, a)| {
+                                        Some((
+                                            index_after_removal(e, i)?,
+                                            index_after_removal(a, i)?,
+                                        ))
+                                    });
+                                    scene_log.record(SceneOperation::Deleted {
+                                        name: object_type_name(&removed, true),
+                                        index: i,
+                                    });
+                                    re_init_rays = true;
+                                    collection_size -= 1;
+                                    deleted_objects.push((i, removed));
+                                }
+                                Err(e) => {
+                                    eprintln!("Raytracer Err: Failed to delete object, {}", e);
+                                    push_toast(
+                                        format!("Failed to delete object: {}", e),
+                                        HUD_TOAST_DURATION,
+                                    );
+                                    break;
+                                }
+                            }
+                        }
+                    } else {
+                        println!(
+                            "Raytracer ~Err: Failed to delete object, there is no object at {}, {}",
+                            mouse_x, mouse_y
+                        );
+                    }
+                } else {
+                    println!(
+                        "Raytracer ~Err: Failed to delete object, there is no object on the scene"
+                    )
+                }
+            } else if is_key_pressed(KEYB_RESTORE_DELETED) && is_key_down(KEYB_RESTORE_MODIFIER) {
+                let restoring = take_last_deleted(&mut deleted_objects);
+                if restoring.is_empty() {
+                    match replaced_scene.take() {
+                        Some(objects) => {
+                            collection_size = restore_scene(objects);
+                            println!(
+                                "Raytracer Upd: Restored the {} object(s) replaced by the last preset",
+                                collection_size
+                            );
+                            push_toast("Restored the scene before the preset", HUD_TOAST_DURATION);
+                            scene_log.record(SceneOperation::Edited(format!(
+                                "Restored the {} object(s) replaced by the last preset",
+                                collection_size
+                            )));
+                            selected_objects.clear();
+                            selected_index = None;
+                            dragged_object_index = None;
+                            rotated_object_index = None;
+                            image_source_pair = None;
+                            image_source_view = None;
+                            re_init_rays = true;
+                        }
+                        None => println!("Raytracer ~Err: There is no deleted object to restore"),
+                    }
+                }
+                // a deleted group comes back together
+                for (index, object) in restoring {
+                    // objects deleted since then may have shortened the scene
+                    let index = index.min(collection_size);
+                    match insert_object_at(index, object) {
+                        Ok(()) => {
+                            println!("Raytracer Upd: Restored the last deleted object");
+                            push_toast("Restored the last deleted object", HUD_TOAST_DURATION);
+                            scene_log.record(SceneOperation::Restored { index });
+                            for selected in selected_objects.iter_mut() {
+                                *selected = index_after_insertion(*selected, index);
+                            }
+                            selected_index =
+                                selected_index.map(|s| index_after_insertion(s, index));
+                            image_source_pair = image_source_pair.map(|(e, a)| {
+                                (
+                                    index_after_insertion(e, index),
+                                    index_after_insertion(a, index),
+                                )
+                            });
+                            collection_size += 1;
+                            re_init_rays = true;
+                        }
+                        Err(e) => {
+                            println!("Raytracer ~Err: Cannot restore the deleted object, {}", e)
+                        }
+                    }
+                }
+            } else if is_key_pressed(KEYB_BRING_TO_FRONT) || is_key_pressed(KEYB_SEND_TO_BACK) {
+                // objects are drawn in collection order, the last one on top
+                let to_front = is_key_pressed(KEYB_BRING_TO_FRONT);
+                if let Some(from) = object_at_cursor_index(mouse_x, mouse_y) {
+                    let to = if to_front { collection_size - 1 } else { 0 };
+                    match move_object_to_index(from, to) {
+                        Ok(()) => {
+                            println!(
+                                "Raytracer Upd: {} the object at {}, {}",
+                                if to_front {
+                                    "Brought to the front"
+                                } else {
+                                    "Sent to the back"
+                                },
+                                mouse_x,
+                                mouse_y
+                            );
+                            for selected in selected_objects.iter_mut() {
+                                *selected = index_after_move(*selected, from, to);
+                            }
+                            selected_index = selected_index.map(|s| index_after_move(s, from, to));
+                            image_source_pair = image_source_pair.map(|(e, a)| {
+                                (index_after_move(e, from, to), index_after_move(a, from, to))
+                            });
+                            re_init_rays = true;
+                        }
+                        Err(e) => println!("Raytracer ~Err: Cannot reorder the object, {}", e),
+                    }
+                } else {
+                    println!(
+                        "Raytracer ~Err: Failed to reorder object, there is no object at {}, {}",
+                        mouse_x, mouse_y
+                    );
+                }
+            } else if is_key_pressed(KEYB_NUMERIC_ENTRY) {
+                let cursor_index = object_at_cursor_index(mouse_x, mouse_y);
+                if cursor_index.is_some_and(|i| OBJ_COLLECTION.read().unwrap()[i].is_locked()) {
+                    println!(
+                        "Raytracer ~Err: Cannot open numeric entry for the object at {}, {}, it is locked",
+                        mouse_x, mouse_y
+                    );
+                } else if let Some(i) = cursor_index {
+                    println!(
+                        "Raytracer Upd: Opened numeric entry for object at {}, {}",
+                        mouse_x, mouse_y
+                    );
+                    numeric_entry = NumericEntry::open(i);
+                } else {
+                    println!(
+                        "Raytracer ~Err: Failed to open numeric entry, there is no object at {}, {}",
+                        mouse_x, mouse_y
+                    );
+                }
+            } else if is_key_pressed(KEYB_TOGGLE_RAY_ENDPOINTS)
+                && is_key_down(KEYB_RAY_ENDPOINTS_MODIFIER)
+            {
+                let mut show_endpoints = SHOW_RAY_ENDPOINTS.write().unwrap();
+                *show_endpoints = !*show_endpoints;
+                println!(
+                    "Raytracer Upd: {} ray endpoints",
+                    if *show_endpoints { "Showing" } else { "Hiding" }
+                );
+            } else if is_key_pressed(KEYB_TOGGLE_INTERSECTIONS) {
+                let mut show_intersections = SHOW_INTERSECTIONS.write().unwrap();
+                *show_intersections = !*show_intersections;
+                println!(
+                    "Raytracer Upd: {} ray intersection markers",
+                    if *show_intersections {
+                        "Showing"
+                    } else {
+                        "Hiding"
+                    }
+                );
+                re_init_rays = true;
+            } else if is_key_pressed(KEYB_TOGGLE_OCCLUSION) {
+                let mut occlusion_enabled = OCCLUSION_ENABLED.write().unwrap();
+                *occlusion_enabled = !*occlusion_enabled;
+                println!(
+                    "Raytracer Upd: Occlusion {}",
+                    if *occlusion_enabled {
+                        "enabled, rays are truncated by absorbers"
+                    } else {
+                        "disabled, showing unoccluded rays"
+                    }
+                );
+                re_init_rays = true;
+            } else if is_key_pressed(KEYB_TOGGLE_LOCK) {
+                match object_at_cursor_index(mouse_x, mouse_y) {
+                    Some(index) => {
+                        let mut collection = OBJ_COLLECTION.write().unwrap();
+                        let base_object = collection[index].get_base_object_mut();
+                        base_object.locked = !base_object.locked;
+                        println!(
+                            "Raytracer Upd: {} object at {}, {}",
+                            if base_object.locked {
+                                "Locked"
+                            } else {
+                                "Unlocked"
+                            },
+                            mouse_x,
+                            mouse_y
+                        );
+                    }
+                    None => println!(
+                        "Raytracer ~Err: Failed to lock an object, there is no object at {}, {}",
+                        mouse_x, mouse_y
+                    ),
+                }
+            } else if is_key_pressed(KEYB_TOGGLE_SHADOWS) {
+                let mut show_shadows = SHOW_SHADOWS.write().unwrap();
+                *show_shadows = !*show_shadows;
+                println!(
+                    "Raytracer Upd: {} absorber shadows",
+                    if *show_shadows { "Showing" } else { "Hiding" }
+                );
+            } else if is_key_pressed(KEYB_TOGGLE_DRAG_WRAP) && is_key_down(KEYB_DRAG_WRAP_MODIFIER)
+            {
+                let mut wrap_on_drag = WRAP_ON_DRAG.write().unwrap();
+                *wrap_on_drag = !*wrap_on_drag;
+                println!(
+                    "Raytracer Upd: Objects dragged past a window edge {}",
+                    if *wrap_on_drag {
+                        "now wrap around to the opposite edge"
+                    } else {
+                        "no longer wrap around"
+                    }
+                );
+            } else if is_key_pressed(KEYB_TOGGLE_DRAG_CLAMP)
+                && is_key_down(KEYB_DRAG_CLAMP_MODIFIER)
+            {
+                let mut clamp_on_drag = CLAMP_ON_DRAG.write().unwrap();
+                *clamp_on_drag = !*clamp_on_drag;
+                println!(
+                    "Raytracer Upd: Dragged objects {}",
+                    if *clamp_on_drag {
+                        "now stay inside the window"
+                    } else {
+                        "can be placed off-screen"
+                    }
+                );
+            } else if is_key_pressed(KEYB_TOGGLE_DRAG_COLLISION) {
+                let mut drag_collision_enabled = DRAG_COLLISION_ENABLED.write().unwrap();
+                *drag_collision_enabled = !*drag_collision_enabled;
+                println!(
+                    "Raytracer Upd: Dragged objects {}",
+                    if *drag_collision_enabled {
+                        "now stop at other objects"
+                    } else {
+                        "can overlap other objects again"
+                    }
+                );
+            } else if is_key_pressed(KEYB_TOGGLE_LIGHTMAP) {
+                lightmap = match lightmap {
+                    Some(_) => {
+                        println!("Raytracer Upd: Stopped the long exposure");
+                        None
+                    }
+                    None => {
+                        println!("Raytracer Upd: Started a long exposure");
+                        Some(Lightmap::new())
+                    }
+                };
+            } else if is_key_pressed(KEYB_CLEAR_LIGHTMAP) {
+                match &lightmap {
+                    Some(lightmap) => {
+                        lightmap.clear();
+                        println!("Raytracer Upd: Cleared the long exposure");
+                    }
+                    None => println!(
+                        "Raytracer ~Err: There is no long exposure to clear, start one with {:?}",
+                        KEYB_TOGGLE_LIGHTMAP
+                    ),
+                }
+            } else if is_key_pressed(KEYB_EXPORT_LIGHTMAP) {
+                match &lightmap {
+                    Some(lightmap) => match export_lightmap_png(lightmap, APP_EXPORT_LIGHTMAP_PATH)
+                    {
+                        Ok(()) => {
+                            println!(
+                                "Raytracer Upd: Exported the long exposure to {}",
+                                APP_EXPORT_LIGHTMAP_PATH
+                            );
+                            push_toast(
+                                format!("Exported {}", APP_EXPORT_LIGHTMAP_PATH),
+                                HUD_TOAST_DURATION,
+                            );
+                        }
+                        Err(e) => {
+                            eprintln!(
+                                "Raytracer Err: Failed to export the long exposure to {}: {}",
+                                APP_EXPORT_LIGHTMAP_PATH, e
+                            );
+                            push_toast(
+                                format!("Failed to export {}: {}", APP_EXPORT_LIGHTMAP_PATH, e),
+                                HUD_TOAST_DURATION,
+                            );
+                        }
+                    },
+                    None => println!(
+                        "Raytracer ~Err: There is no long exposure to export, start one with {:?}",
+                        KEYB_TOGGLE_LIGHTMAP
+                    ),
+                }
+            } else if is_key_pressed(KEYB_TOGGLE_AUTOTUNE) {
+                let mut autotune_enabled = AUTOTUNE_ENABLED.write().unwrap();
+                *autotune_enabled = !*autotune_enabled;
+                println!(
+                    "Raytracer Upd: Ray counts {}",
+                    if *autotune_enabled {
+                        "now follow the frame time budget"
+                    } else {
+                        "are no longer tuned"
+                    }
+                );
+            } else if is_key_pressed(KEYB_TOGGLE_THEME) {
+                let mut theme = THEME.write().unwrap();
+                *theme = if *theme == THEME_DARK {
+                    THEME_LIGHT
+                } else {
+                    THEME_DARK
+                };
+                println!(
+                    "Raytracer Upd: Switched to the {} theme, new objects use its colors",
+                    theme.name
+                );
+            } else if is_key_pressed(KEYB_TOGGLE_ADDITIVE_RAYS) {
+                if additive_material.is_some() {
+                    let mut additive_rays = ADDITIVE_RAYS.write().unwrap();
+                    *additive_rays = !*additive_rays;
+                    println!(
+                        "Raytracer Upd: Drawing rays with {} blending",
+                        if *additive_rays { "additive" } else { "normal" }
+                    );
+                } else {
+                    println!(
+                        "Raytracer ~Err: Additive blending is unavailable, the material failed to load"
+                    );
+                }
+            } else if is_key_pressed(KEYB_TOGGLE_RAY_FLOW) && is_key_down(KEYB_RAY_FLOW_MODIFIER) {
+                let mut flowing = FLOWING_RAYS.write().unwrap();
+                *flowing = !*flowing;
+                println!(
+                    "Raytracer Upd: Rays are {}",
+                    if *flowing {
+                        "flowing from their source"
+                    } else {
+                        "drawn as solid lines"
+                    }
+                );
+            } else if is_key_pressed(KEYB_TOGGLE_ORIENTATION_ARROWS) {
+                let mut show_arrows = SHOW_ORIENTATION_ARROWS.write().unwrap();
+                *show_arrows = !*show_arrows;
+                println!(
+                    "Raytracer Upd: {} emitter orientation arrows",
+                    if *show_arrows { "Showing" } else { "Hiding" }
+                );
+            } else if is_key_pressed(KEYB_REPORT_COVERAGE) {
+                println!(
+                    "Raytracer Upd: {:.2}% of the window is illuminated",
+                    illuminated_fraction() * 100.0
+                );
+                if *OCCLUSION_ENABLED.read().unwrap() {
+                    let is_emitter = map_objects(
+                        |obj| matches!(obj, RaytracerObjects::Emitters(e) if e.is_enabled()),
+                    );
+                    for index in (0..is_emitter.len()).filter(|i| is_emitter[*i]) {
+                        println!(
+                            "    Emitter at index {}: {:.2}% of its rays are blocked",
+                            index,
+                            emitter_blocked_fraction(index) * 100.0
+                        );
+                    }
+                }
+            } else if is_key_pressed(KEYB_EXPORT_RAYS) {
+                // deferred until after occlusion so the end points are truncated
+                export_rays_requested = true;
+            } else if is_key_pressed(KEYB_EXPORT_SVG) {
+                // deferred for the same reason as the ray export
+                export_svg_requested = true;
+            } else if is_key_pressed(KEYB_EXPORT_RENDER) {
+                // deferred for the same reason as the ray export
+                export_render_requested = true;
+            } else if is_key_pressed(KEYB_IMPORT_CSV) {
+                match load_csv(APP_IMPORT_CSV_PATH) {
+                    Ok(added) => {
+                        println!(
+                            "Raytracer Upd: Loaded {} object(s) from {}",
+                            added, APP_IMPORT_CSV_PATH
+                        );
+                        push_toast(
+                            format!("Loaded {} object(s) from {}", added, APP_IMPORT_CSV_PATH),
+                            HUD_TOAST_DURATION,
+                        );
+                        scene_log.record(SceneOperation::Loaded {
+                            count: added,
+                            source: APP_IMPORT_CSV_PATH.to_string(),
+                        });
+                        collection_size = OBJ_COLLECTION.read().unwrap().len();
+                        re_init_rays = true;
+                    }
+                    Err(e) => {
+                        eprintln!(
+                            "Raytracer Err: Failed to load {}: {}",
+                            APP_IMPORT_CSV_PATH, e
+                        );
+                        push_toast(
+                            format!("Failed to load {}: {}", APP_IMPORT_CSV_PATH, e),
+                            HUD_TOAST_DURATION,
+                        );
+                    }
+                }
+            } else if is_key_pressed(KEYB_PRESET_SUN) || is_key_pressed(KEYB_PRESET_ECLIPSE) {
+                let preset = if is_key_pressed(KEYB_PRESET_SUN) {
+                    "sun"
+                } else {
+                    "eclipse"
+                };
+
+                let added = add_preset_to_scene(preset);
+                if added > 0 {
+                    println!(
+                        "Raytracer Upd: {} preset ({} object(s)) created at {}, {}",
+                        preset, added, mouse_x, mouse_y
+                    );
+                    collection_size += added;
+                    re_init_rays = true;
+                }
+            } else if is_key_pressed(KEYB_FIT_TO_WINDOW) {
+                match center_all_objects_in_window() {
+                    Some(true) => {
+                        println!("Raytracer Upd: Centered all objects in the window");
+                        re_init_rays = true;
+                    }
+                    Some(false) => {
+                        println!(
+                            "Raytracer ~Err: Centered all objects, but they are too spread out to fit in the window"
+                        );
+                        re_init_rays = true;
+                    }
+                    None => println!("Raytracer ~Err: There are no objects to fit in the window"),
+                }
+            } else if is_key_pressed(KEYB_RAY_LENGTH_INC) || is_key_pressed(KEYB_RAY_LENGTH_DEC) {
+                let mut max_length = RAY_MAX_LENGTH.write().unwrap();
+                let mut delta = if is_key_pressed(KEYB_RAY_LENGTH_INC) {
+                    OBJD_RAY_LENGTH_DELTA
+                } else {
+                    -OBJD_RAY_LENGTH_DELTA
+                };
+
+                if is_key_down(KeyCode::LeftShift) {
+                    delta *= KEYB_RTC_MULTIPLIER as f32;
+                }
+
+                *max_length = (*max_length + delta).max(0.0);
+
+                if *max_length == 0.0 {
+                    println!("Raytracer Upd: Rays now extend to the screen edge");
+                } else {
+                    println!("Raytracer Upd: Ray length capped at {}", *max_length);
+                }
+
+                re_init_rays = true;
+            } else if is_key_pressed(KEYB_PRINT_CHANGES) && is_key_down(KEYB_PRINT_CHANGES_MODIFIER)
+            {
+                scene_log.print_since_last();
+            } else if is_key_pressed(KEYB_DEBUG_SHOW_ALL_OBJ) {
+                println!("Raytracer Debug: Showing all objects inside OBJ_COLLECTION.");
+                print_all_objects();
+                println!("Raytracer Debug: Done showing all objects in OBJ_COLLECTION.");
+            }
+
+            // Place the previewed object once its key is released or on click,
+            // where the key was pressed and sized by how far the cursor moved
+            if let Some((key, object_type, name, origin)) = pending_creation
+                && (is_key_released(key) || is_mouse_button_pressed(MouseButton::Left))
+            {
+                let size = creation_drag_size(origin, (mouse_x, mouse_y));
+                if add_object_to_scene(object_type, origin, size) {
+                    println!(
+                        "Raytracer Upd: {} created at {}, {}{}",
+                        name,
+                        origin.0,
+                        origin.1,
+                        size.map(|size| format!(", sized by a {:.0} px drag", size))
+                            .unwrap_or_default()
+                    );
+                    push_toast(format!("{} created", name), HUD_TOAST_DURATION);
+                    scene_log.record(SceneOperation::Added {
+                        name,
+                        position: origin,
+                    });
+                    re_init_rays = true;
+                    collection_size += 1;
+                }
+                pending_creation = None;
+            }
+        }
+        // The scene is full, tell the user why nothing was created
+        else if KEYB_CREATION_KEYS.iter().any(|key| is_key_pressed(*key)) {
+            eprintln!(
+                "Raytracer Err: Too many RaytracerObjects in the scene, you can only have {}",
+                OBJC_MAX_OBJ_COUNT
+            );
+            push_toast_at(
+                format!("Object limit reached ({})", OBJC_MAX_OBJ_COUNT),
+                HUD_TOAST_DURATION,
+                (mouse_x, mouse_y),
+            );
+        }
+
+        // While measuring, clicks place the points of the angle instead of
+        // grabbing objects
+        if is_mouse_button_pressed(MouseButton::Left)
+            && let Some(measure) = angle_measure.as_mut()
+        {
+            if let Some(angle) = measure.click(mouse_x, mouse_y) {
+                println!("Raytracer Upd: Measured an angle of {:.1} degrees.", angle);
+            }
+        }
+        // Check if the user wants to move an object, the object is picked
+        // once when the button is pressed so it can't switch objects mid-drag
+        else if is_mouse_button_pressed(MouseButton::Left) {
+            mouse_down_position = Some((mouse_x, mouse_y));
+            dragged_object_index = object_at_cursor_index(mouse_x, mouse_y);
+            if let Some(index) = dragged_object_index
+                && OBJ_COLLECTION.read().unwrap()[index].is_locked()
+            {
+                println!(
+                    "Raytracer ~Err: Cannot move the object at {}, {}, it is locked",
+                    mouse_x, mouse_y
+                );
+                dragged_object_index = None;
+            }
+            // with the modifier held, an oriented emitter is aimed instead of
+            // moved, other objects are still moved
+            if is_key_down(KEYB_RTC_ROTATE_DRAG)
+                && let Some(index) = dragged_object_index
+                && object_aim_at(index, mouse_x, mouse_y).is_some()
+            {
+                println!("Raytracer Upd: Started aiming emitter at cursor.");
+                rotated_object_index = Some(index);
+                dragged_object_index = None;
+                re_init_rays = true;
+            }
+            if let Some(index) = dragged_object_index {
+                // keep the object where it was grabbed instead of snapping its
+                // center to the cursor
+                let (x, y) = OBJ_COLLECTION.read().unwrap()[index].get_pos();
+                drag_offset = (x - mouse_x, y - mouse_y);
+                drag_start_position = (x, y);
+            }
+        }
+
+        // Only start dragging once the cursor has moved far enough from where
+        // the button was pressed, so a jittery click doesn't move the object
+        if is_mouse_button_down(MouseButton::Left)
+            && !cursor_is_moving_object
+            && dragged_object_index.is_some()
+            && let Some((down_x, down_y)) = mouse_down_position
+            && ((mouse_x - down_x).powi(2) + (mouse_y - down_y).powi(2)).sqrt()
+                > OBJC_DRAG_THRESHOLD
+        {
+            cursor_is_moving_object = true;
+        }
+
+        // If the user is not moving an object, remove dragging_index
+        if !is_mouse_button_down(MouseButton::Left) {
+            if cursor_is_moving_object {
+                println!("Raytracer Upd: Stopped moving object.");
+                if let Some(index) = dragged_object_index
+                    && let Some(object) = OBJ_COLLECTION.read().unwrap().get(index)
+                    && object.get_pos() != drag_start_position
+                {
+                    scene_log.record(SceneOperation::Moved {
+                        index,
+                        from: drag_start_position,
+                        to: object.get_pos(),
+                    });
+                }
+                cursor_is_moving_object = false;
+            }
+            mouse_down_position = None;
+            if let Some(index) = rotated_object_index.take() {
+                println!("Raytracer Upd: Stopped aiming emitter.");
+                scene_log.record(SceneOperation::Edited(format!(
+                    "Aimed object at index {} by dragging",
+                    index
+                )));
+            }
+        }
+
+        // Aim the grabbed emitter at the cursor, the rays follow live
+        if mouse_delta != vec2(0.0, 0.0)
+            && let Some(index) = rotated_object_index
+            && object_aim_at(index, mouse_x, mouse_y).is_some()
+        {
+            re_init_rays = true;
+        }
+
+        // If user is moving the cursor and is dragging an object,
+        // move that object
+        if mouse_delta != vec2(0.0, 0.0)
+            && cursor_is_moving_object
+            && let Some(index) = dragged_object_index
+        {
+            let mut collection = OBJ_COLLECTION.write().unwrap();
+            if index < collection.len() {
+                let (target_x, target_y) = (mouse_x + drag_offset.0, mouse_y + drag_offset.1);
+                let (move_x, move_y) = if *WRAP_ON_DRAG.read().unwrap() {
+                    wrap_to_window(target_x, target_y)
+                } else if *CLAMP_ON_DRAG.read().unwrap() {
+                    let radius = get_object_scope(&collection[index]).1.unwrap_or(0.0);
+                    clamp_to_window(target_x, target_y, radius)
+                } else {
+                    (target_x, target_y)
+                };
+
+                // stop at contact instead of overlapping other objects
+                let (move_x, move_y) = if *DRAG_COLLISION_ENABLED.read().unwrap() {
+                    stop_at_objects(&collection, index, (move_x, move_y))
+                } else {
+                    (move_x, move_y)
+                };
+
+                // a drag held against an object or the window edge changes
+                // nothing, so nothing is moved and the rays are not rebuilt
+                let (old_x, old_y) = collection[index].get_pos();
+                if (move_x, move_y) != (old_x, old_y) {
+                    // the rest of the group (if any) moves by the same amount,
+                    // locked members stay where they are
+                    let mut moves = vec![(index, move_x, move_y)];
+                    for member in group_members(&collection, index) {
+                        if !collection[member].is_locked() {
+                            let (x, y) = collection[member].get_pos();
+                            moves.push((member, x + move_x - old_x, y + move_y - old_y));
+                        }
+                    }
+
+                    for (index, move_x, move_y) in moves {
+                        match &mut collection[index] {
+                            RaytracerObjects::ObjectCircle(o) => {
+                                o.move_object(move_x, move_y);
+                            }
+                            RaytracerObjects::Emitters(o) => {
+                                o.move_object(move_x, move_y);
+                            }
+                            RaytracerObjects::Absorbers(o) => {
+                                o.move_object(move_x, move_y);
+                                mark_absorbers_changed();
+                            }
+                        }
+                    }
+                    re_init_rays = true;
+                }
+            }
+        }
+
+        // Trade rays for frame time, the sleep that caps the frame rate is not
+        // part of the work
+        if *AUTOTUNE_ENABLED.read().unwrap()
+            && autotune_ray_counts(ft - frame_sleep, WINDOW_FRAME_RATE)
+        {
+            re_init_rays = true;
+        }
+
+        // Rays are built against the screen size, so a resize invalidates them
+        if window_size != (screen_width(), screen_height()) {
+            window_size = (screen_width(), screen_height());
+            println!(
+                "Raytracer Upd: Window resized to {}x{}",
+                window_size.0, window_size.1
+            );
+
+            if WINDOW_CLAMP_ON_RESIZE {
+                let moved = clamp_all_objects_to_screen();
+                if moved > 0 {
+                    println!(
+                        "Raytracer Upd: Moved {} object(s) back into the window",
+                        moved
+                    );
+                }
+            }
+
+            re_init_rays = true;
+        }
+
+        if re_init_rays {
+            // the rays only need rebuilding if something they depend on
+            // changed, a no-op move or edit keeps the last pass
+            let signature = ray_signature(
+                &OBJ_COLLECTION.read().unwrap(),
+                *OCCLUSION_ENABLED.read().unwrap(),
+            );
+            if last_ray_signature == Some(signature) {
+                ray_passes.1 += 1;
+            } else {
+                ray_passes.0 += 1;
+                last_ray_signature = Some(signature);
+
+                // re-initialize all rays
+                init_all_rays();
+
+                // Check for occlusion
+                if *OCCLUSION_ENABLED.read().unwrap() {
+                    let occlusion_start = get_time();
+                    buried_emitters = check_for_occlusion();
+                    occlusion_time = get_time() - occlusion_start;
+                } else {
+                    buried_emitters.clear();
+                }
+            }
+
+            // The image source mirrors the (occluded) rays of its emitter
+            image_source_view = image_source_pair.and_then(|(e, a)| image_source(e, a));
+
+            // Intersections are found after occlusion so blocked rays don't count
+            ray_intersections.clear();
+            if *SHOW_INTERSECTIONS.read().unwrap() {
+                match find_ray_intersections() {
+                    Some(points) => ray_intersections = points,
+                    None => {
+                        println!(
+                            "Raytracer ~Err: Too many rays to show intersections, you can only have {}",
+                            OBJC_MAX_INTERSECTION_RAYS
+                        );
+                        *SHOW_INTERSECTIONS.write().unwrap() = false;
+                    }
+                }
+            }
+
+            re_init_rays = false;
+        }
+
+        if export_rays_requested {
+            match export_rays_json(APP_EXPORT_RAYS_PATH) {
+                Ok(()) => {
+                    println!("Raytracer Upd: Exported rays to {}", APP_EXPORT_RAYS_PATH);
+                    push_toast(
+                        format!("Exported {}", APP_EXPORT_RAYS_PATH),
+                        HUD_TOAST_DURATION,
+                    );
+                }
+                Err(e) => {
+                    eprintln!(
+                        "Raytracer Err: Failed to export rays to {}: {}",
+                        APP_EXPORT_RAYS_PATH, e
+                    );
+                    push_toast(
+                        format!("Failed to export {}: {}", APP_EXPORT_RAYS_PATH, e),
+                        HUD_TOAST_DURATION,
+                    );
+                }
+            }
+            export_rays_requested = false;
+        }
+
+        if export_svg_requested {
+            match export_svg(APP_EXPORT_SVG_PATH) {
+                Ok(()) => {
+                    println!(
+                        "Raytracer Upd: Exported the scene to {}",
+                        APP_EXPORT_SVG_PATH
+                    );
+                    push_toast(
+                        format!("Exported {}", APP_EXPORT_SVG_PATH),
+                        HUD_TOAST_DURATION,
+                    );
+                }
+                Err(e) => {
+                    eprintln!(
+                        "Raytracer Err: Failed to export the scene to {}: {}",
+                        APP_EXPORT_SVG_PATH, e
+                    );
+                    push_toast(
+                        format!("Failed to export {}: {}", APP_EXPORT_SVG_PATH, e),
+                        HUD_TOAST_DURATION,
+                    );
+                }
+            }
+            export_svg_requested = false;
+        }
+
+        if export_render_requested {
+            match export_render_png(APP_EXPORT_RENDER_PATH, APP_EXPORT_RENDER_SUPERSAMPLE) {
+                Ok(()) => {
+                    println!(
+                        "Raytracer Upd: Exported a {}x supersampled render to {}",
+                        APP_EXPORT_RENDER_SUPERSAMPLE, APP_EXPORT_RENDER_PATH
+                    );
+                    push_toast(
+                        format!("Exported {}", APP_EXPORT_RENDER_PATH),
+                        HUD_TOAST_DURATION,
+                    );
+                }
+                Err(e) => {
+                    eprintln!(
+                        "Raytracer Err: Failed to export the render to {}: {}",
+                        APP_EXPORT_RENDER_PATH, e
+                    );
+                    push_toast(
+                        format!("Failed to export {}: {}", APP_EXPORT_RENDER_PATH, e),
+                        HUD_TOAST_DURATION,
+                    );
+                }
+            }
+            export_render_requested = false;
+        }
+
+        // the current rays are added after occlusion, then everything
+        // accumulated so far is drawn under the scene
+        if let Some(lightmap) = &mut lightmap {
+            lightmap.accumulate();
+            lightmap.draw();
+        }
+
+        // drawn first so that the rays and objects are drawn over them
+        if *SHOW_SHADOWS.read().unwrap() {
+            draw_shadow_regions();
+        }
+
+        for ray in &ray_snapshots {
+            ray.draw_object();
+        }
+
+        if let Some(((x, y), rays)) = &image_source_view {
+            for ray in rays {
+                ray.draw_object();
+            }
+            draw_circle_lines(
+                *x,
+                *y,
+                OBJD_CIRCLE_RADIUS / 2.0,
+                OBJD_SELECTION_THICKNESS,
+                OBJD_IMAGE_SOURCE_COLOR,
+            );
+        }
+
+        // With additive blending, every emitter's rays are drawn in one pass
+        // before the objects, the emitters then leave them out
+        if *ADDITIVE_RAYS.read().unwrap()
+            && let Some(material) = &additive_material
+        {
+            gl_use_material(material);
+            for_each_object(|r_obj| {
+                if let RaytracerObjects::Emitters(emitter) = r_obj {
+                    emitter.draw_rays();
+                }
+            });
+            gl_use_default_material();
+        }
+
+        // Draw all objects in the global collection, new objects fade in
+        let now = get_time();
+        for_each_object(|r_obj| {
+            let alpha = if OBJC_FADE_IN_NEW_OBJECTS {
+                r_obj.fade_in_alpha(now, OBJD_FADE_IN_DURATION)
+            } else {
+                1.0
+            };
+
+            match r_obj {
+                _ if alpha < 1.0 => r_obj.draw_with_alpha(alpha),
+                RaytracerObjects::ObjectCircle(object) => {
+                    object.draw_object();
+                }
+                RaytracerObjects::Emitters(object) => {
+                    object.draw_object();
+                }
+                RaytracerObjects::Absorbers(object) => {
+                    object.draw_object();
+                }
+            }
+
+            if r_obj.is_locked() {
+                r_obj.get_base_object().draw_lock_indicator();
+            }
+        });
+
+        // The preview is never added to the collection, so it isn't occluded
+        if let Some((_, object_type, _, origin)) = pending_creation {
+            draw_object_preview(
+                object_type,
+                origin,
+                creation_drag_size(origin, (mouse_x, mouse_y)),
+            );
+        }
+
+        // Outline the object under the cursor, drawn over the objects so it
+        // stays visible
+        if let Some(index) = object_at_cursor_index(mouse_x, mouse_y)
+            && let Some(object) = OBJ_COLLECTION.read().unwrap().get(index)
+        {
+            let ((x, y), radius) = get_object_scope(object);
+            draw_circle_lines(
+                x,
+                y,
+                radius.unwrap_or(0.0) + OBJD_SELECTION_MARGIN / 2.0,
+                OBJD_SELECTION_THICKNESS,
+                OBJD_HOVER_COLOR,
+            );
+        }
+
+        // Emitters buried in an absorber have no visible rays, so they are
+        // ringed to show why
+        for index in &buried_emitters {
+            if let Some(object) = OBJ_COLLECTION.read().unwrap().get(*index) {
+                let ((x, y), radius) = get_object_scope(object);
+                draw_circle_lines(
+                    x,
+                    y,
+                    radius.unwrap_or(0.0) + OBJD_SELECTION_MARGIN,
+                    OBJD_SELECTION_THICKNESS,
+                    OBJD_BURIED_COLOR,
+                );
+            }
+        }
+
+        for index in &selected_objects {
+            if let Some(object) = OBJ_COLLECTION.read().unwrap().get(*index) {
+                let ((x, y), radius) = get_object_scope(object);
+                draw_circle_lines(
+                    x,
+                    y,
+                    radius.unwrap_or(0.0) + OBJD_SELECTION_MARGIN,
+                    OBJD_SELECTION_THICKNESS,
+                    OBJD_SELECTION_COLOR,
+                );
+            }
+        }
+
+        if let Some(index) = selected_index
+            && let Some(object) = OBJ_COLLECTION.read().unwrap().get(index)
+        {
+            let ((x, y), radius) = get_object_scope(object);
+            draw_circle_lines(
+                x,
+                y,
+                radius.unwrap_or(0.0) + OBJD_SELECTION_MARGIN * 2.0,
+                OBJD_SELECTION_THICKNESS,
+                OBJD_KEYBOARD_SELECTION_COLOR,
+            );
+        }
+
+        for (x, y) in &ray_intersections {
+            draw_circle(*x, *y, OBJD_INTERSECTION_RADIUS, OBJD_INTERSECTION_COLOR);
+        }
+
+        draw_text(
+            &format!(
+                "Rays: {}/{}, occlusion: {} ({} run, {} skipped){}{}",
+                total_ray_count(&OBJ_COLLECTION.read().unwrap()),
+                OBJC_MAX_TOTAL_RAYS,
+                if *OCCLUSION_ENABLED.read().unwrap() {
+                    format!("{:.2} ms", occlusion_time * 1000.0)
+                } else {
+                    "off".to_string()
+                },
+                ray_passes.0,
+                ray_passes.1,
+                match *RAY_DRAW_STRIDE.read().unwrap() {
+                    1 => String::new(),
+                    stride => format!(", drawing 1 in {}", stride),
+                },
+                match buried_emitters.len() {
+                    0 => String::new(),
+                    count => format!(", {} emitter(s) inside an absorber", count),
+                }
+            ),
+            HUD_POS_X,
+            HUD_POS_Y,
+            HUD_FONT_SIZE,
+            theme.hud_text,
+        );
+
+        // Show the ray count and range of the hovered emitter, and the group
+        // of any hovered object
+        if let Some(index) = object_at_cursor_index(mouse_x, mouse_y)
+            && let Some(object) = OBJ_COLLECTION.read().unwrap().get(index)
+        {
+            let mut lines = Vec::new();
+            let params = object.params();
+            if let RaytracerObjects::Emitters(o) = object {
+                lines.push(format!(
+                    "{} emitter rays: {}{}",
+                    o.type_name(),
+                    params.ray_count.unwrap_or_default(),
+                    if o.is_enabled() { "" } else { " (off)" }
+                ));
+                lines.push(match o.get_max_length() {
+                    Some(max_length) => format!("Range: {:.0} px", max_length),
+                    None => "Range: unlimited".to_string(),
+                });
+                if *OCCLUSION_ENABLED.read().unwrap() && o.is_enabled() {
+                    lines.push(format!(
+                        "Blocked: {:.0}% of rays",
+                        o.blocked_fraction() * 100.0
+                    ));
+                }
+            }
+            if let Some(orientation) = params.orientation {
+                lines.push(format!("Orientation: {:.1} deg", orientation.to_degrees()));
+            }
+            if let Some(group_id) = object.get_base_object().group_id {
+                lines.push(format!("Group: {}", group_id));
+            }
+
+            for (line, text) in lines.iter().enumerate() {
+                draw_text(
+                    text,
+                    HUD_POS_X,
+                    HUD_POS_Y + HUD_FONT_SIZE * (line + 1) as f32,
+                    HUD_FONT_SIZE,
+                    theme.hud_text,
+                );
+            }
+        }
+
+        if let Some(measure) = &angle_measure {
+            measure.draw(mouse_x, mouse_y, theme.hud_text);
+        }
+
+        draw_toasts(theme.hud_text);
+
+        if let Some(entry) = &numeric_entry {
+            entry.draw_object();
+        }
+
+        if let Some(open_console) = &console {
+            open_console.draw_object();
+        }
+
+        if let Some(page) = help_page {
+            draw_help_overlay(page);
         }
 
         mouse_delta = mouse_delta_position();
 
+        frame_sleep = 0.0;
         if (ft < WINDOW_FRAME_RATE) && WINDOW_USE_FRAME_RATE {
-            sleep(Duration::from_millis(
-                ((WINDOW_FRAME_RATE - ft) * 1000.) as u64,
-            ));
+            let duration = Duration::from_millis(((WINDOW_FRAME_RATE - ft) * 1000.) as u64);
+            sleep(duration);
+            frame_sleep = duration.as_secs_f32();
         }
 
         next_frame().await;
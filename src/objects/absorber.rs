@@ -2,11 +2,15 @@
 //!
 //! This module provides light absorber implementation for the raytracer system.
 //! Absorbers are objects that can block or absorb light rays in the simulation.
-//! Currently, the system supports perfect absorbers that completely block light.
+//! Currently, the system supports perfect absorbers that completely block light,
+//! either as a full circle or as a circular arc, and arc mirrors that stop a
+//! ray and send a reflected one back out.
 //!
 //! # Types of Absorbers
 //!
 //! * `AbsorberPerfect` - A perfect light absorber that completely blocks all light rays
+//! * `AbsorberArc` - A partial ring that only blocks rays hitting its arc
+//! * `MirrorArc` - A partial ring that reflects rays hitting its arc
 //!
 //! # Usage
 //!
@@ -29,15 +33,42 @@
 
 use super::behavior::*;
 use super::circle::ObjectCircle;
+use crate::globals::OBJD_ARC_THICKNESS;
+use macroquad::shapes::draw_arc;
+use std::f32::consts::TAU;
 
 /// Enum representing different types of light absorbing objects
 ///
 /// This enum allows for polymorphic handling of different absorber types
 /// through the system. All variants implement the `Drawable` and `Movable` traits.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum Absorbers {
     /// A perfect absorber that completely blocks light rays
     AbsorberPerfect(AbsorberPerfect),
+    /// A circular arc that blocks light rays only along the arc
+    AbsorberArc(AbsorberArc),
+    /// A circular arc that reflects light rays along the arc, shaped like an
+    /// arc absorber (see `check_for_occlusion()`)
+    MirrorArc(AbsorberArc),
+}
+
+impl Absorbers {
+    /// Gets the circle that defines the absorber's position and radius
+    pub fn get_base_object(&self) -> &ObjectCircle {
+        match self {
+            Absorbers::AbsorberPerfect(obj) => &obj.base_object,
+            Absorbers::AbsorberArc(obj) | Absorbers::MirrorArc(obj) => &obj.base_object,
+        }
+    }
+
+    /// Gets a mutable reference to the circle that defines the absorber's
+    /// position and radius
+    pub fn get_base_object_mut(&mut self) -> &mut ObjectCircle {
+        match self {
+            Absorbers::AbsorberPerfect(obj) => &mut obj.base_object,
+            Absorbers::AbsorberArc(obj) | Absorbers::MirrorArc(obj) => &mut obj.base_object,
+        }
+    }
 }
 
 impl Drawable for Absorbers {
@@ -47,6 +78,7 @@ impl Drawable for Absorbers {
     fn draw_object(&self) {
         match self {
             Absorbers::AbsorberPerfect(obj) => obj.base_object.draw_object(),
+            Absorbers::AbsorberArc(obj) | Absorbers::MirrorArc(obj) => obj.draw_object(),
         }
     }
 }
@@ -59,9 +91,7 @@ impl Movable for Absorbers {
     /// * `pos_x` - The new X coordinate
     /// * `pos_y` - The new Y coordinate
     fn move_object(&mut self, pos_x: f32, pos_y: f32) {
-        match self {
-            Absorbers::AbsorberPerfect(obj) => obj.base_object.move_object(pos_x, pos_y),
-        }
+        self.get_base_object_mut().move_object(pos_x, pos_y);
     }
 }
 
@@ -72,26 +102,25 @@ impl VariableSize for Absorbers {
     ///
     /// * `factor` - The change size factor
     fn change_radius(&mut self, factor: f32) {
-        match self {
-            Absorbers::AbsorberPerfect(obj) => {
-                let new_radius = obj.base_object.radius + factor;
-                obj.base_object.radius = if new_radius > 0.0 { new_radius } else { 0.0 };
-            }
-        }
+        let base_object = self.get_base_object_mut();
+        let new_radius = base_object.radius + factor;
+        base_object.radius = if new_radius > 0.0 { new_radius } else { 0.0 };
     }
 
     fn get_radius(&self) -> f32 {
-        match self {
-            Absorbers::AbsorberPerfect(obj) => obj.base_object.radius,
-        }
+        self.get_base_object().radius
+    }
+
+    fn set_radius(&mut self, radius: f32) {
+        self.get_base_object_mut().set_radius(radius);
     }
 }
 
 /// A perfect absorber that completely blocks all light
 ///
 /// This absorber type will stop any ray that intersects with it.
-/// It is represented visually as a black circle.
-#[derive(Clone, Debug)]
+/// It is drawn as a filled circle, `OBJD_ABSORBER_FILL` by default.
+#[derive(Clone, Debug, PartialEq)]
 pub struct AbsorberPerfect {
     /// The underlying circle object that defines the absorber's shape and position
     pub base_object: ObjectCircle,
@@ -111,3 +140,78 @@ impl AbsorberPerfect {
         AbsorberPerfect { base_object }
     }
 }
+
+/// A perfect absorber shaped like a circular arc (a partial ring)
+///
+/// Only rays that hit the circle within the angular range
+/// `[start_angle, end_angle]` are blocked, rays that hit the rest of the circle
+/// pass through. Angles are in radians and measured around the center from the
+/// positive x-axis, increasing clockwise on screen (since y points down). The
+/// range goes from `start_angle` to `end_angle` in that direction, so it may
+/// wrap past 0.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AbsorberArc {
+    /// The circle that defines the arc's center and radius
+    pub base_object: ObjectCircle,
+    /// The angle where the arc starts (in radians)
+    pub start_angle: f32,
+    /// The angle where the arc ends (in radians)
+    pub end_angle: f32,
+}
+
+impl AbsorberArc {
+    /// Creates a new arc absorber on a circle
+    ///
+    /// # Parameters
+    ///
+    /// * `base_object` - The circle that defines the arc's center and radius
+    /// * `start_angle` - The angle where the arc starts (in radians)
+    /// * `end_angle` - The angle where the arc ends (in radians)
+    ///
+    /// # Returns
+    ///
+    /// A new `AbsorberArc` instance
+    pub fn new(base_object: ObjectCircle, start_angle: f32, end_angle: f32) -> AbsorberArc {
+        AbsorberArc {
+            base_object,
+            start_angle,
+            end_angle,
+        }
+    }
+
+    /// Gets the angle covered by the arc, from 0 (exclusive) to 2π (inclusive)
+    pub fn span(&self) -> f32 {
+        let span = (self.end_angle - self.start_angle).rem_euclid(TAU);
+        if span == 0.0 { TAU } else { span }
+    }
+
+    /// Checks if the point at `angle` around the center lies on the arc
+    ///
+    /// # Parameters
+    ///
+    /// * `angle` - The angle around the center (in radians), any value is
+    ///   accepted and wrapped to `[0, 2π)`
+    pub fn contains_angle(&self, angle: f32) -> bool {
+        (angle - self.start_angle).rem_euclid(TAU) <= self.span()
+    }
+}
+
+impl Drawable for AbsorberArc {
+    /// Draws only the arc segment of the circle
+    ///
+    /// The arc is drawn `OBJD_ARC_THICKNESS` pixels thick, centered on the
+    /// circle's radius.
+    fn draw_object(&self) {
+        let base_object = &self.base_object;
+        draw_arc(
+            base_object.pos_x,
+            base_object.pos_y,
+            64,
+            base_object.radius - OBJD_ARC_THICKNESS / 2.0,
+            self.start_angle.to_degrees(),
+            OBJD_ARC_THICKNESS,
+            self.span().to_degrees(),
+            base_object.get_color(),
+        );
+    }
+}
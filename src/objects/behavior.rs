@@ -38,9 +38,14 @@
 //! author:         Zhean Ganituen (zrygan)
 //! last updated:   April 18, 2025
 
+use std::f32::consts::TAU;
+use std::fmt;
+use std::ops::RangeInclusive;
+
 use super::absorber::Absorbers;
 use super::circle::ObjectCircle;
 use super::emitters::Emitters;
+use crate::errors::RaytracerError;
 
 /// Enum that represents all possible object types in the raytracer.
 ///
@@ -81,30 +86,271 @@ impl RaytracerObjects {
     /// assert_eq!(y, 100.0);
     /// ```
     pub fn get_pos(&self) -> (f32, f32) {
-        match self {
-            RaytracerObjects::ObjectCircle(object) => (object.pos_x, object.pos_y),
-            RaytracerObjects::Emitters(emitter) => match emitter {
-                Emitters::EmitterIsotropic(object) => {
-                    (object.base_object.pos_x, object.base_object.pos_y)
+        self.params().position
+    }
+
+    /// Gets the parameters of any RaytracerObject
+    ///
+    /// Reads the same parameters for every type of object, so callers don't
+    /// need to match on the concrete type. Parameters the object doesn't have
+    /// are `None`.
+    ///
+    /// # Returns
+    ///
+    /// An `ObjectParams` with the object's position and radius, and for
+    /// emitters, their ray count and the orientation, beam angle, or beam
+    /// diameter of their type
+    pub fn params(&self) -> ObjectParams {
+        let base_object = self.get_base_object();
+        let mut params = ObjectParams {
+            position: (base_object.pos_x, base_object.pos_y),
+            radius: base_object.radius,
+            orientation: None,
+            beam_angle: None,
+            beam_diameter: None,
+            ray_count: None,
+        };
+
+        if let RaytracerObjects::Emitters(emitter) = self {
+            params.ray_count = Some(emitter.get_base_emitter().ray_count);
+            match emitter {
+                Emitters::EmitterCollimated(o) => {
+                    params.orientation = Some(o.orientation);
+                    params.beam_diameter = Some(o.collimated_beam_diameter);
                 }
-                Emitters::EmitterCollimated(object) => (
-                    object.base_emitter.base_object.pos_x,
-                    object.base_emitter.base_object.pos_y,
-                ),
-                Emitters::EmitterSpotlight(object) => (
-                    object.base_emitter.base_object.pos_x,
-                    object.base_emitter.base_object.pos_y,
+                Emitters::EmitterSpotlight(o) => {
+                    params.orientation = Some(o.orientation);
+                    params.beam_angle = Some(o.spotlight_beam_angle);
+                }
+                Emitters::EmitterConvergent(o) => params.orientation = Some(o.orientation()),
+                Emitters::EmitterIsotropic(_) | Emitters::EmitterTargeted(_) => {}
+            }
+        }
+
+        params
+    }
+
+    /// Sets a parameter of any RaytracerObject
+    ///
+    /// The write counterpart of `params()`. The value is checked against the
+    /// parameter's bounds (see `ObjectParam::bounds()`) before anything is
+    /// changed. The caller is responsible for reinitializing the rays.
+    ///
+    /// # Arguments
+    ///
+    /// * `param` - The parameter to set
+    /// * `value` - The new value, angles are in radians
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(bool)` - If the parameter was set, `true` if the object is an
+    ///   emitter so its rays need to be reinitialized
+    /// * `Err(RaytracerError)` - If the object doesn't have the parameter or
+    ///   the value is out of bounds
+    pub fn set_param(&mut self, param: ObjectParam, value: f32) -> Result<bool, RaytracerError> {
+        let (range, bounds) = param.bounds();
+        if !range.contains(&value) {
+            return Err(RaytracerError::ParamOutOfRange {
+                param: param.name(),
+                bounds,
+            });
+        }
+
+        let not_applicable = Err(RaytracerError::ParamNotApplicable(param.name()));
+        match (param, &mut *self) {
+            (ObjectParam::Radius, _) => self.get_base_object_mut().set_radius(value),
+            (
+                ObjectParam::Orientation,
+                RaytracerObjects::Emitters(
+                    o @ (Emitters::EmitterCollimated(_)
+                    | Emitters::EmitterSpotlight(_)
+                    | Emitters::EmitterConvergent(_)),
                 ),
-            },
-            RaytracerObjects::Absorbers(absorber) => match absorber {
-                Absorbers::AbsorberPerfect(object) => {
-                    (object.base_object.pos_x, object.base_object.pos_y)
+            ) => o.set_orientation(value),
+            (ObjectParam::BeamAngle, RaytracerObjects::Emitters(Emitters::EmitterSpotlight(o))) => {
+                o.spotlight_beam_angle = value
+            }
+            (
+                ObjectParam::BeamDiameter,
+                RaytracerObjects::Emitters(Emitters::EmitterCollimated(o)),
+            ) => o.collimated_beam_diameter = value,
+            _ => return not_applicable,
+        }
+
+        Ok(matches!(self, RaytracerObjects::Emitters(_)))
+    }
+
+    /// Gets the circle that represents any RaytracerObject
+    pub fn get_base_object(&self) -> &ObjectCircle {
+        match self {
+            RaytracerObjects::ObjectCircle(object) => object,
+            RaytracerObjects::Emitters(object) => object.get_base_object(),
+            RaytracerObjects::Absorbers(object) => object.get_base_object(),
+        }
+    }
+
+    /// Gets a mutable reference to the circle that represents any RaytracerObject
+    pub fn get_base_object_mut(&mut self) -> &mut ObjectCircle {
+        match self {
+            RaytracerObjects::ObjectCircle(object) => object,
+            RaytracerObjects::Emitters(object) => object.get_base_object_mut(),
+            RaytracerObjects::Absorbers(object) => object.get_base_object_mut(),
+        }
+    }
+
+    /// Gets how opaque a newly added object is drawn
+    ///
+    /// Objects fade in linearly over `duration` after they were added to the
+    /// scene (see `ObjectCircle::created_at`).
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - The current time, from `get_time()`
+    /// * `duration` - How long the fade takes, in seconds
+    ///
+    /// # Returns
+    ///
+    /// The alpha (from 0 to 1) to scale the object's colors by
+    pub fn fade_in_alpha(&self, now: f64, duration: f64) -> f32 {
+        let age = now - self.get_base_object().created_at;
+        (age / duration).clamp(0.0, 1.0) as f32
+    }
+
+    /// Draws the object with its colors (and those of its rays) made more
+    /// transparent
+    ///
+    /// # Arguments
+    ///
+    /// * `alpha` - The factor (from 0 to 1) to scale every color's alpha by
+    pub fn draw_with_alpha(&self, alpha: f32) {
+        let mut object = self.clone();
+        object.get_base_object_mut().color_fill.a *= alpha;
+
+        match &mut object {
+            RaytracerObjects::ObjectCircle(o) => o.draw_object(),
+            RaytracerObjects::Emitters(o) => {
+                let base_emitter = o.get_base_emitter_mut();
+                for ray in base_emitter
+                    .rays
+                    .iter_mut()
+                    .chain(base_emitter.reflections.iter_mut())
+                {
+                    ray.color.a *= alpha;
                 }
-            },
+                o.draw_object();
+            }
+            RaytracerObjects::Absorbers(o) => o.draw_object(),
+        }
+    }
+
+    /// Checks whether the object is locked against edits
+    pub fn is_locked(&self) -> bool {
+        self.get_base_object().locked
+    }
+
+    /// Gets the axis-aligned bounding box of any RaytracerObject
+    ///
+    /// The box encloses the object's circle, the rays of emitters are not
+    /// included.
+    ///
+    /// # Returns
+    ///
+    /// A tuple `(min_x, min_y, max_x, max_y)` of the box's corners
+    pub fn get_bounds(&self) -> (f32, f32, f32, f32) {
+        let ObjectParams {
+            position: (x, y),
+            radius,
+            ..
+        } = self.params();
+
+        (x - radius, y - radius, x + radius, y + radius)
+    }
+}
+
+/// The parameters of a RaytracerObject, see `RaytracerObjects::params()`
+///
+/// Every object has a position and a radius, the other parameters are only
+/// set for the objects that have them.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ObjectParams {
+    /// The (x, y) coordinates of the object's center
+    pub position: (f32, f32),
+    /// The radius of the object's circle
+    pub radius: f32,
+    /// The direction (in radians) of a collimated, spotlight, or convergent
+    /// emitter
+    pub orientation: Option<f32>,
+    /// The beam angle (in radians) of a spotlight emitter
+    pub beam_angle: Option<f32>,
+    /// The beam diameter of a collimated emitter
+    pub beam_diameter: Option<f32>,
+    /// The number of rays of an emitter
+    pub ray_count: Option<i32>,
+}
+
+impl ObjectParams {
+    /// Gets the value of a parameter that can be set with
+    /// `RaytracerObjects::set_param()`
+    ///
+    /// # Returns
+    ///
+    /// The value, or `None` if the object doesn't have the parameter
+    pub fn get(&self, param: ObjectParam) -> Option<f32> {
+        match param {
+            ObjectParam::Radius => Some(self.radius),
+            ObjectParam::Orientation => self.orientation,
+            ObjectParam::BeamAngle => self.beam_angle,
+            ObjectParam::BeamDiameter => self.beam_diameter,
+        }
+    }
+}
+
+/// A parameter that can be set with `RaytracerObjects::set_param()`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ObjectParam {
+    /// The radius of any object
+    Radius,
+    /// The direction of a collimated, spotlight, or convergent emitter
+    Orientation,
+    /// The beam angle of a spotlight emitter
+    BeamAngle,
+    /// The beam diameter of a collimated emitter
+    BeamDiameter,
+}
+
+impl ObjectParam {
+    /// Gets the name of the parameter, as used in messages
+    pub fn name(&self) -> &'static str {
+        match self {
+            ObjectParam::Radius => "radius",
+            ObjectParam::Orientation => "orientation",
+            ObjectParam::BeamAngle => "beam angle",
+            ObjectParam::BeamDiameter => "beam diameter",
+        }
+    }
+
+    /// Gets the values the parameter may take
+    ///
+    /// # Returns
+    ///
+    /// The range of valid values, and a description of it for error messages
+    pub fn bounds(&self) -> (RangeInclusive<f32>, &'static str) {
+        match self {
+            ObjectParam::Radius | ObjectParam::BeamDiameter => {
+                (f32::MIN_POSITIVE..=f32::MAX, "above 0")
+            }
+            ObjectParam::Orientation => (f32::MIN..=f32::MAX, "a finite number"),
+            ObjectParam::BeamAngle => (0.0..=TAU, "from 0 to 2π radians"),
         }
     }
 }
 
+impl fmt::Display for ObjectParam {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
 /// Trait for objects that can be rendered to the screen.
 ///
 /// All visible objects in the raytracer must implement this trait,
@@ -164,8 +410,200 @@ pub trait Movable {
 pub trait VariableSize {
     fn change_radius(&mut self, factor: f32);
     fn get_radius(&self) -> f32;
+    fn set_radius(&mut self, radius: f32);
 }
 
 pub trait VariableOrientation {
     fn change_orientation(&mut self, factor: f32);
+    fn reset_orientation(&mut self);
+    fn set_orientation(&mut self, orientation: f32);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::objects::absorber::{AbsorberArc, AbsorberPerfect};
+    use crate::objects::emitters::{
+        EmitterCollimated, EmitterConvergent, EmitterIsotropic, EmitterSpotlight, EmitterTargeted,
+    };
+    use crate::objects::ray::ObjectRay;
+    use macroquad::color::WHITE;
+
+    fn circle() -> ObjectCircle {
+        ObjectCircle::new(10.0, 20.0, WHITE, 5.0)
+    }
+
+    fn rays() -> Vec<ObjectRay> {
+        vec![ObjectRay::new(10.0, 20.0, 30.0, 20.0, 1.0, WHITE); 3]
+    }
+
+    /// The parameters every object has, the rest are filled in per test
+    fn base_params() -> ObjectParams {
+        ObjectParams {
+            position: (10.0, 20.0),
+            radius: 5.0,
+            orientation: None,
+            beam_angle: None,
+            beam_diameter: None,
+            ray_count: None,
+        }
+    }
+
+    #[test]
+    fn circles_and_absorbers_only_have_a_position_and_radius() {
+        let objects = [
+            RaytracerObjects::ObjectCircle(circle()),
+            RaytracerObjects::Absorbers(Absorbers::AbsorberPerfect(AbsorberPerfect::new(circle()))),
+            RaytracerObjects::Absorbers(Absorbers::AbsorberArc(AbsorberArc::new(
+                circle(),
+                0.0,
+                1.0,
+            ))),
+            RaytracerObjects::Absorbers(Absorbers::MirrorArc(AbsorberArc::new(circle(), 0.0, 1.0))),
+        ];
+
+        for object in objects {
+            assert_eq!(object.params(), base_params());
+        }
+    }
+
+    #[test]
+    fn emitters_have_the_parameters_of_their_type() {
+        let emitter = |e: Emitters| RaytracerObjects::Emitters(e).params();
+        let with_rays = ObjectParams {
+            ray_count: Some(3),
+            ..base_params()
+        };
+
+        assert_eq!(
+            emitter(Emitters::EmitterIsotropic(EmitterIsotropic::new(
+                circle(),
+                rays()
+            ))),
+            with_rays
+        );
+        assert_eq!(
+            emitter(Emitters::EmitterTargeted(EmitterTargeted::new(
+                circle(),
+                rays(),
+                vec![(0.0, 0.0); 3]
+            ))),
+            with_rays
+        );
+        assert_eq!(
+            emitter(Emitters::EmitterCollimated(EmitterCollimated::new(
+                circle(),
+                rays(),
+                0.5,
+                40.0
+            ))),
+            ObjectParams {
+                orientation: Some(0.5),
+                beam_diameter: Some(40.0),
+                ..with_rays.clone()
+            }
+        );
+        assert_eq!(
+            emitter(Emitters::EmitterSpotlight(EmitterSpotlight::new(
+                circle(),
+                rays(),
+                0.5,
+                1.0
+            ))),
+            ObjectParams {
+                orientation: Some(0.5),
+                beam_angle: Some(1.0),
+                ..with_rays.clone()
+            }
+        );
+        // the orientation of a convergent emitter points at its target
+        assert_eq!(
+            emitter(Emitters::EmitterConvergent(EmitterConvergent::new(
+                circle(),
+                rays(),
+                (10.0, 50.0)
+            ))),
+            ObjectParams {
+                orientation: Some(std::f32::consts::FRAC_PI_2),
+                ..with_rays
+            }
+        );
+    }
+
+    #[test]
+    fn new_objects_fade_in() {
+        let mut object = RaytracerObjects::ObjectCircle(circle());
+        object.get_base_object_mut().created_at = 10.0;
+
+        assert_eq!(object.fade_in_alpha(10.0, 0.5), 0.0);
+        assert_eq!(object.fade_in_alpha(10.25, 0.5), 0.5);
+        assert_eq!(object.fade_in_alpha(11.0, 0.5), 1.0);
+        // a clock behind the creation time doesn't go below transparent
+        assert_eq!(object.fade_in_alpha(9.0, 0.5), 0.0);
+    }
+
+    #[test]
+    fn params_are_set_within_bounds() {
+        let mut spotlight = RaytracerObjects::Emitters(Emitters::EmitterSpotlight(
+            EmitterSpotlight::new(circle(), rays(), 0.5, 1.0),
+        ));
+
+        assert_eq!(spotlight.set_param(ObjectParam::BeamAngle, 2.0), Ok(true));
+        assert_eq!(
+            spotlight.set_param(ObjectParam::Orientation, -1.0),
+            Ok(true)
+        );
+        assert_eq!(spotlight.set_param(ObjectParam::Radius, 8.0), Ok(true));
+        let params = spotlight.params();
+        assert_eq!(
+            [
+                params.get(ObjectParam::BeamAngle),
+                params.get(ObjectParam::Orientation),
+                params.get(ObjectParam::Radius),
+            ],
+            [Some(2.0), Some(-1.0), Some(8.0)]
+        );
+
+        // nothing is changed by an invalid value
+        assert_eq!(
+            spotlight.set_param(ObjectParam::BeamAngle, 7.0),
+            Err(RaytracerError::ParamOutOfRange {
+                param: "beam angle",
+                bounds: "from 0 to 2π radians",
+            })
+        );
+        assert!(spotlight.set_param(ObjectParam::Radius, 0.0).is_err());
+        assert!(
+            spotlight
+                .set_param(ObjectParam::Orientation, f32::NAN)
+                .is_err()
+        );
+        assert_eq!(spotlight.params(), params);
+    }
+
+    #[test]
+    fn params_are_only_set_on_objects_that_have_them() {
+        let mut absorber =
+            RaytracerObjects::Absorbers(Absorbers::AbsorberPerfect(AbsorberPerfect::new(circle())));
+        assert_eq!(absorber.set_param(ObjectParam::Radius, 8.0), Ok(false));
+        assert_eq!(
+            absorber.set_param(ObjectParam::Orientation, 1.0),
+            Err(RaytracerError::ParamNotApplicable("orientation"))
+        );
+
+        let mut collimated = RaytracerObjects::Emitters(Emitters::EmitterCollimated(
+            EmitterCollimated::new(circle(), rays(), 0.5, 40.0),
+        ));
+        assert_eq!(
+            collimated.set_param(ObjectParam::BeamDiameter, 60.0),
+            Ok(true)
+        );
+        assert_eq!(collimated.params().beam_diameter, Some(60.0));
+        assert!(collimated.set_param(ObjectParam::BeamAngle, 1.0).is_err());
+
+        let mut isotropic = RaytracerObjects::Emitters(Emitters::EmitterIsotropic(
+            EmitterIsotropic::new(circle(), rays()),
+        ));
+        assert!(isotropic.set_param(ObjectParam::Orientation, 1.0).is_err());
+    }
 }
@@ -9,6 +9,7 @@
 //! last updated:   April 16, 2025
 
 use super::behavior::{Drawable, Movable, VariableSize};
+use crate::globals::{OBJD_LOCK_COLOR, OBJD_LOCK_SIZE, OBJD_LOCK_THICKNESS};
 
 use macroquad::prelude::*;
 
@@ -17,7 +18,7 @@ use macroquad::prelude::*;
 /// Circle objects are the fundamental building blocks for many raytracer
 /// elements including barriers, emitter bases, and interactive elements.
 /// They have position, color, and size properties.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct ObjectCircle {
     /// X-coordinate of the circle's center position
     pub pos_x: f32,
@@ -27,6 +28,14 @@ pub struct ObjectCircle {
     pub color_fill: Color,
     /// Radius of the circle in pixels
     pub radius: f32,
+    /// Whether the object is protected from moving, deleting, resizing, and
+    /// rotating
+    pub locked: bool,
+    /// The group the object belongs to, objects in a group are dragged together
+    pub group_id: Option<u32>,
+    /// When the object was added to the scene, from `get_time()`, 0 until it
+    /// is added
+    pub created_at: f64,
 }
 
 impl ObjectCircle {
@@ -44,15 +53,54 @@ impl ObjectCircle {
     ///
     /// # Returns
     ///
-    /// A new `ObjectCircle` instance with the specified parameters
+    /// A new `ObjectCircle` instance with the specified parameters, unlocked
+    /// and not in a group
     pub fn new(pos_x: f32, pos_y: f32, color_fill: Color, radius: f32) -> ObjectCircle {
         ObjectCircle {
             pos_x,
             pos_y,
             color_fill,
             radius,
+            locked: false,
+            group_id: None,
+            created_at: 0.0,
         }
     }
+
+    /// Gets the fill color of the circle
+    pub fn get_color(&self) -> Color {
+        self.color_fill
+    }
+
+    /// Sets the fill color of the circle
+    pub fn set_color(&mut self, color: Color) {
+        self.color_fill = color;
+    }
+
+    /// Draws a small padlock at the top right of the circle
+    ///
+    /// This marks objects that are locked, the padlock is a filled body with
+    /// a shackle above it.
+    pub fn draw_lock_indicator(&self) {
+        let offset = self.radius * std::f32::consts::FRAC_1_SQRT_2;
+        let (x, y) = (self.pos_x + offset, self.pos_y - offset);
+        let size = OBJD_LOCK_SIZE;
+
+        draw_circle_lines(
+            x,
+            y - size / 2.0,
+            size / 3.0,
+            OBJD_LOCK_THICKNESS,
+            OBJD_LOCK_COLOR,
+        );
+        draw_rectangle(
+            x - size / 2.0,
+            y - size / 2.0,
+            size,
+            size * 0.75,
+            OBJD_LOCK_COLOR,
+        );
+    }
 }
 
 /// Drawable Implementation for a Circle
@@ -62,7 +110,7 @@ impl Drawable for ObjectCircle {
     /// Uses the macroquad rendering function to draw a filled circle
     /// at the object's position with its color and radius.
     fn draw_object(&self) {
-        draw_circle(self.pos_x, self.pos_y, self.radius, self.color_fill);
+        draw_circle(self.pos_x, self.pos_y, self.radius, self.get_color());
     }
 }
 
@@ -96,4 +144,8 @@ impl VariableSize for ObjectCircle {
     fn get_radius(&self) -> f32 {
         self.radius
     }
+
+    fn set_radius(&mut self, radius: f32) {
+        self.radius = radius.max(0.0);
+    }
 }
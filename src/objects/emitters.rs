@@ -1,25 +1,37 @@
 //! Emitter objects initialization and behaviors
 //!
 //! This module provides light emitter implementations for the raytracer system.
-//! It defines three types of emitters: isotropic (radiating in all directions),
-//! collimated (parallel rays, like a laser), and spotlight (cone-shaped beam).
+//! It defines five types of emitters: isotropic (radiating in all directions),
+//! collimated (parallel rays, like a laser), spotlight (cone-shaped beam),
+//! convergent (rays focused on a point), and targeted (one ray per target
+//! point).
 //!
 //! author:         Zhean Ganituen
 //! last updated:   April 18, 2025
 
-use macroquad::shapes::draw_circle;
+use macroquad::color::Color;
+use macroquad::shapes::{draw_circle, draw_line};
+use std::f32::consts::PI;
 
-use crate::globals::{OBJC_MAX_RAY_COUNT, OBJC_MIN_RAY_COUNT, OBJD_RAY_COUNT};
+use crate::errors::RaytracerError;
+use crate::globals::{
+    ADDITIVE_RAYS, MACROQUAD_BATCH_RAYS, OBJC_MAX_RAY_COUNT, OBJC_MAX_TOTAL_RAYS,
+    OBJC_MIN_RAY_COUNT, OBJD_ARROW_COLOR, OBJD_ARROW_LENGTH, OBJD_ARROW_THICKNESS,
+    OBJD_COLLIMATED_ORIENTATION, OBJD_CONVERGENT_DISTANCE, OBJD_CONVERGENT_ORIENTATION,
+    OBJD_DISABLED_ALPHA, OBJD_RAY_COUNT, OBJD_RAY_WIDTH, OBJD_SPOTLIGHT_ORIENTATION,
+    OBJD_TARGETED_OFFSETS, RAY_DRAW_STRIDE, SHOW_ORIENTATION_ARROWS, THEME,
+};
 
 use super::behavior::{Drawable, Movable, VariableOrientation, VariableSize};
 use super::circle::ObjectCircle;
-use super::ray::{ObjectRay, init_collimated_rays, init_isotropic_rays, init_spotlight_rays};
+use super::ray::{ObjectRay, draw_rays_batched};
 
 /// Enumeration of all emitter types supported by the raytracer.
 ///
 /// This enum allows different emitter types to be treated polymorphically
 /// in the rendering and physics systems.
 #[derive(Clone, Debug)]
+#[allow(clippy::enum_variant_names)]
 pub enum Emitters {
     /// Standard isotropic emitter that radiates light in all directions
     EmitterIsotropic(EmitterIsotropic),
@@ -27,82 +39,315 @@ pub enum Emitters {
     EmitterCollimated(EmitterCollimated),
     /// Spotlight emitter that produces a cone-shaped beam
     EmitterSpotlight(EmitterSpotlight),
+    /// Focusing emitter whose rays converge on a target point
+    EmitterConvergent(EmitterConvergent),
+    /// Emitter with exactly one ray aimed at each of its target points
+    EmitterTargeted(EmitterTargeted),
+}
+
+impl Emitters {
+    /// Gets the name of the concrete emitter type
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Emitters::EmitterIsotropic(_) => "Isotropic",
+            Emitters::EmitterCollimated(_) => "Collimated",
+            Emitters::EmitterSpotlight(_) => "Spotlight",
+            Emitters::EmitterConvergent(_) => "Convergent",
+            Emitters::EmitterTargeted(_) => "Targeted",
+        }
+    }
+
+    /// Gets the rays of the emitter regardless of its concrete type
+    pub fn get_rays(&self) -> &Vec<ObjectRay> {
+        match self {
+            Emitters::EmitterIsotropic(obj) => &obj.rays,
+            Emitters::EmitterCollimated(obj) => &obj.base_emitter.rays,
+            Emitters::EmitterSpotlight(obj) => &obj.base_emitter.rays,
+            Emitters::EmitterConvergent(obj) => &obj.base_emitter.rays,
+            Emitters::EmitterTargeted(obj) => &obj.base_emitter.rays,
+        }
+    }
+
+    /// Gets a mutable reference to the rays of the emitter
+    pub fn get_rays_mut(&mut self) -> &mut Vec<ObjectRay> {
+        match self {
+            Emitters::EmitterIsotropic(obj) => &mut obj.rays,
+            Emitters::EmitterCollimated(obj) => &mut obj.base_emitter.rays,
+            Emitters::EmitterSpotlight(obj) => &mut obj.base_emitter.rays,
+            Emitters::EmitterConvergent(obj) => &mut obj.base_emitter.rays,
+            Emitters::EmitterTargeted(obj) => &mut obj.base_emitter.rays,
+        }
+    }
+
+    /// Gets the isotropic emitter that every emitter type is built on, it
+    /// holds the base object, rays, and ray settings
+    pub fn get_base_emitter(&self) -> &EmitterIsotropic {
+        match self {
+            Emitters::EmitterIsotropic(obj) => obj,
+            Emitters::EmitterCollimated(obj) => &obj.base_emitter,
+            Emitters::EmitterSpotlight(obj) => &obj.base_emitter,
+            Emitters::EmitterConvergent(obj) => &obj.base_emitter,
+            Emitters::EmitterTargeted(obj) => &obj.base_emitter,
+        }
+    }
+
+    /// Gets a mutable reference to the isotropic emitter that every emitter
+    /// type is built on
+    pub fn get_base_emitter_mut(&mut self) -> &mut EmitterIsotropic {
+        match self {
+            Emitters::EmitterIsotropic(obj) => obj,
+            Emitters::EmitterCollimated(obj) => &mut obj.base_emitter,
+            Emitters::EmitterSpotlight(obj) => &mut obj.base_emitter,
+            Emitters::EmitterConvergent(obj) => &mut obj.base_emitter,
+            Emitters::EmitterTargeted(obj) => &mut obj.base_emitter,
+        }
+    }
+
+    /// Gets the fraction (from 0 to 1) of the emitter's rays that ended on an
+    /// absorber in the last occlusion pass, 0 if it has no rays
+    pub fn blocked_fraction(&self) -> f32 {
+        let base_emitter = self.get_base_emitter();
+        if base_emitter.rays.is_empty() {
+            return 0.0;
+        }
+
+        base_emitter.blocked_rays as f32 / base_emitter.rays.len() as f32
+    }
+
+    /// Gets the range of the emitter, `None` if its rays are not limited
+    pub fn get_max_length(&self) -> Option<f32> {
+        self.get_base_emitter().max_length
+    }
+
+    /// Sets the range of the emitter and caps its current rays to it
+    ///
+    /// Like the ray width, `init_all_rays()` applies the range again whenever
+    /// the rays are rebuilt, before occlusion shortens them further.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_length` - The farthest distance (in pixels) a ray reaches from
+    ///   its start, or `None` for no limit
+    pub fn set_max_length(&mut self, max_length: Option<f32>) {
+        let base_emitter = self.get_base_emitter_mut();
+        base_emitter.max_length = max_length;
+
+        if let Some(max_length) = max_length {
+            for ray in base_emitter.rays.iter_mut() {
+                ray.cap_length(max_length);
+            }
+        }
+    }
+
+    /// Gets the thickness of the emitter's rays
+    pub fn get_ray_width(&self) -> f32 {
+        match self {
+            Emitters::EmitterIsotropic(obj) => obj.ray_width,
+            Emitters::EmitterCollimated(obj) => obj.base_emitter.ray_width,
+            Emitters::EmitterSpotlight(obj) => obj.base_emitter.ray_width,
+            Emitters::EmitterConvergent(obj) => obj.base_emitter.ray_width,
+            Emitters::EmitterTargeted(obj) => obj.base_emitter.ray_width,
+        }
+    }
+
+    /// Sets the thickness of the emitter's rays, including its current ones
+    ///
+    /// Rays are rebuilt with `OBJD_RAY_WIDTH` whenever the emitter changes, so
+    /// `init_all_rays()` applies this width again afterwards.
+    pub fn set_ray_width(&mut self, ray_width: f32) {
+        match self {
+            Emitters::EmitterIsotropic(obj) => obj.ray_width = ray_width,
+            Emitters::EmitterCollimated(obj) => obj.base_emitter.ray_width = ray_width,
+            Emitters::EmitterSpotlight(obj) => obj.base_emitter.ray_width = ray_width,
+            Emitters::EmitterConvergent(obj) => obj.base_emitter.ray_width = ray_width,
+            Emitters::EmitterTargeted(obj) => obj.base_emitter.ray_width = ray_width,
+        }
+
+        for ray in self.get_rays_mut() {
+            ray.thickness = ray_width;
+        }
+    }
+
+    /// Gets the color of the emitter's rays
+    pub fn get_ray_color(&self) -> Color {
+        self.get_base_emitter().ray_color
+    }
+
+    /// Sets the color of the emitter's rays, including its current ones
+    ///
+    /// Like the ray width, `init_all_rays()` applies the color again whenever
+    /// the rays are rebuilt.
+    pub fn set_ray_color(&mut self, ray_color: Color) {
+        self.get_base_emitter_mut().ray_color = ray_color;
+
+        for ray in self.get_rays_mut() {
+            ray.color = ray_color;
+        }
+    }
+
+    /// Gets the wavelength of the emitter's light, `None` if its rays only
+    /// have a color
+    pub fn get_wavelength(&self) -> Option<f32> {
+        self.get_base_emitter().wavelength_nm
+    }
+
+    /// Sets the wavelength of the emitter's light, including its current rays
+    ///
+    /// Rays with a wavelength are drawn in its color, clearing it gives the
+    /// rays the emitter's ray color again. Like the ray color,
+    /// `init_all_rays()` applies the wavelength again whenever the rays are
+    /// rebuilt.
+    ///
+    /// # Arguments
+    ///
+    /// * `wavelength_nm` - The wavelength in nanometers, or `None` to clear it
+    pub fn set_wavelength(&mut self, wavelength_nm: Option<f32>) {
+        self.get_base_emitter_mut().wavelength_nm = wavelength_nm;
+        let ray_color = self.get_ray_color();
+
+        for ray in self.get_rays_mut() {
+            ray.set_wavelength(wavelength_nm);
+            if wavelength_nm.is_none() {
+                ray.color = ray_color;
+            }
+        }
+    }
+
+    /// Checks if the emitter is switched on
+    pub fn is_enabled(&self) -> bool {
+        self.get_base_emitter().enabled
+    }
+
+    /// Switches the emitter on or off, its rays and settings are kept
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.get_base_emitter_mut().enabled = enabled;
+    }
+
+    /// Draws only the emitter's rays
+    ///
+    /// Used for the additive blending pass, see `ADDITIVE_RAYS`.
+    pub fn draw_rays(&self) {
+        self.get_base_emitter().draw_rays();
+    }
+
+    /// Gets the circle that represents the emitter
+    pub fn get_base_object(&self) -> &ObjectCircle {
+        match self {
+            Emitters::EmitterIsotropic(obj) => &obj.base_object,
+            Emitters::EmitterCollimated(obj) => &obj.base_emitter.base_object,
+            Emitters::EmitterSpotlight(obj) => &obj.base_emitter.base_object,
+            Emitters::EmitterConvergent(obj) => &obj.base_emitter.base_object,
+            Emitters::EmitterTargeted(obj) => &obj.base_emitter.base_object,
+        }
+    }
+
+    /// Gets a mutable reference to the circle that represents the emitter
+    pub fn get_base_object_mut(&mut self) -> &mut ObjectCircle {
+        match self {
+            Emitters::EmitterIsotropic(obj) => &mut obj.base_object,
+            Emitters::EmitterCollimated(obj) => &mut obj.base_emitter.base_object,
+            Emitters::EmitterSpotlight(obj) => &mut obj.base_emitter.base_object,
+            Emitters::EmitterConvergent(obj) => &mut obj.base_emitter.base_object,
+            Emitters::EmitterTargeted(obj) => &mut obj.base_emitter.base_object,
+        }
+    }
 }
 
 pub trait VariableRays {
-    fn change_rays_count(&mut self, change_rays: i32);
+    fn change_rays_count(
+        &mut self,
+        change_rays: i32,
+        scene_total_rays: i32,
+    ) -> Result<(), RaytracerError>;
+    fn get_rays_count(&self) -> i32;
 }
 
 impl Drawable for Emitters {
     /// Draws the emitter on the screen.
     ///
     /// Delegates to the appropriate draw_object method based on the emitter type.
+    /// If `SHOW_ORIENTATION_ARROWS` is set, an arrow showing the orientation is
+    /// drawn over emitters that have one.
     fn draw_object(&self) {
         match self {
             Emitters::EmitterIsotropic(e) => e.draw_object(),
             Emitters::EmitterCollimated(e) => e.base_emitter.draw_object(),
             Emitters::EmitterSpotlight(e) => e.base_emitter.draw_object(),
+            Emitters::EmitterConvergent(e) => e.base_emitter.draw_object(),
+            Emitters::EmitterTargeted(e) => e.base_emitter.draw_object(),
+        }
+
+        if *SHOW_ORIENTATION_ARROWS.read().unwrap() {
+            // the direction on screen, spotlight rays use an upward y-axis
+            let direction = match self {
+                Emitters::EmitterIsotropic(_) | Emitters::EmitterTargeted(_) => None,
+                Emitters::EmitterCollimated(e) => Some((e.orientation.cos(), e.orientation.sin())),
+                Emitters::EmitterSpotlight(e) => Some((e.orientation.cos(), -e.orientation.sin())),
+                Emitters::EmitterConvergent(e) => {
+                    Some((e.orientation().cos(), e.orientation().sin()))
+                }
+            };
+
+            if let Some(direction) = direction {
+                draw_orientation_arrow(self.get_base_object(), direction);
+            }
         }
     }
 }
 
+/// Draws an arrow from the center of an emitter in the given direction
+///
+/// The arrow reaches `OBJD_ARROW_LENGTH` pixels past the emitter's edge.
+///
+/// # Arguments
+///
+/// * `base_object` - The circle that represents the emitter
+/// * `direction` - The unit vector (x, y) the arrow points along, in screen
+///   coordinates
+fn draw_orientation_arrow(base_object: &ObjectCircle, direction: (f32, f32)) {
+    let (x, y) = (base_object.pos_x, base_object.pos_y);
+    let length = base_object.radius + OBJD_ARROW_LENGTH;
+    let (tip_x, tip_y) = (x + direction.0 * length, y + direction.1 * length);
+
+    draw_line(x, y, tip_x, tip_y, OBJD_ARROW_THICKNESS, OBJD_ARROW_COLOR);
+
+    // two short lines angled back from the tip form the head
+    let angle = direction.1.atan2(direction.0);
+    for side in [-1.0, 1.0] {
+        let head_angle = angle + PI + side * PI / 6.0;
+        draw_line(
+            tip_x,
+            tip_y,
+            tip_x + head_angle.cos() * OBJD_ARROW_LENGTH / 3.0,
+            tip_y + head_angle.sin() * OBJD_ARROW_LENGTH / 3.0,
+            OBJD_ARROW_THICKNESS,
+            OBJD_ARROW_COLOR,
+        );
+    }
+}
+
 impl Movable for Emitters {
     /// Moves the emitter to a new position.
     ///
-    /// Delegates to the appropriate move_object method based on the emitter type.
+    /// A convergent emitter's target moves along with it, the targets of a
+    /// targeted emitter stay in place. The rays are not rebuilt here, since
+    /// `init_all_rays()` also applies the range, width, color, and occlusion
+    /// that a raw rebuild would lose.
     ///
     /// # Arguments
     ///
     /// * `pos_x` - The new x-coordinate position
     /// * `pos_y` - The new y-coordinate position
     fn move_object(&mut self, pos_x: f32, pos_y: f32) {
-        match self {
-            Emitters::EmitterIsotropic(obj) => {
-                let ray_count = if obj.rays.is_empty() {
-                    OBJD_RAY_COUNT
-                } else {
-                    obj.rays.len() as i32
-                };
-
-                obj.base_object.pos_x = pos_x;
-                obj.base_object.pos_y = pos_y;
-                obj.rays = init_isotropic_rays(pos_x, pos_y, ray_count);
-            }
-            Emitters::EmitterCollimated(obj) => {
-                let ray_count = if obj.base_emitter.rays.is_empty() {
-                    OBJD_RAY_COUNT
-                } else {
-                    obj.base_emitter.rays.len() as i32
-                };
-
-                obj.base_emitter.base_object.pos_x = pos_x;
-                obj.base_emitter.base_object.pos_y = pos_y;
-                obj.base_emitter.rays = init_collimated_rays(
-                    pos_x,
-                    pos_y,
-                    obj.orientation,
-                    obj.collimated_beam_diameter,
-                    ray_count,
-                );
-            }
-            Emitters::EmitterSpotlight(obj) => {
-                let ray_count = if obj.base_emitter.rays.is_empty() {
-                    OBJD_RAY_COUNT
-                } else {
-                    obj.base_emitter.rays.len() as i32
-                };
-
-                obj.base_emitter.base_object.pos_x = pos_x;
-                obj.base_emitter.base_object.pos_y = pos_y;
-                obj.base_emitter.rays = init_spotlight_rays(
-                    pos_x,
-                    pos_y,
-                    obj.orientation,
-                    obj.spotlight_beam_angle,
-                    ray_count,
-                );
-            }
+        if let Emitters::EmitterConvergent(obj) = self {
+            let base_object = &obj.base_emitter.base_object;
+            obj.target.0 += pos_x - base_object.pos_x;
+            obj.target.1 += pos_y - base_object.pos_y;
         }
+
+        let base_object = self.get_base_object_mut();
+        base_object.pos_x = pos_x;
+        base_object.pos_y = pos_y;
     }
 }
 
@@ -130,16 +375,44 @@ impl VariableSize for Emitters {
                     obj.base_emitter.base_object.radius += factor;
                 }
             }
+            Emitters::EmitterConvergent(obj) => {
+                if obj.base_emitter.base_object.radius >= 30. && factor < 0. {
+                    obj.base_emitter.base_object.radius += factor
+                } else if factor > 0. {
+                    obj.base_emitter.base_object.radius += factor;
+                }
+            }
+            Emitters::EmitterTargeted(obj) => {
+                if obj.base_emitter.base_object.radius >= 30. && factor < 0. {
+                    obj.base_emitter.base_object.radius += factor
+                } else if factor > 0. {
+                    obj.base_emitter.base_object.radius += factor;
+                }
+            }
         }
     }
 
     fn get_radius(&self) -> f32 {
         match self {
-            Emitters::EmitterIsotropic(obj) => return obj.base_object.radius,
+            Emitters::EmitterIsotropic(obj) => obj.base_object.radius,
+
+            Emitters::EmitterCollimated(obj) => obj.base_emitter.base_object.radius,
 
-            Emitters::EmitterCollimated(obj) => return obj.base_emitter.base_object.radius,
+            Emitters::EmitterSpotlight(obj) => obj.base_emitter.base_object.radius,
 
-            Emitters::EmitterSpotlight(obj) => return obj.base_emitter.base_object.radius,
+            Emitters::EmitterConvergent(obj) => obj.base_emitter.base_object.radius,
+
+            Emitters::EmitterTargeted(obj) => obj.base_emitter.base_object.radius,
+        }
+    }
+
+    fn set_radius(&mut self, radius: f32) {
+        match self {
+            Emitters::EmitterIsotropic(obj) => obj.base_object.set_radius(radius),
+            Emitters::EmitterCollimated(obj) => obj.base_emitter.base_object.set_radius(radius),
+            Emitters::EmitterSpotlight(obj) => obj.base_emitter.base_object.set_radius(radius),
+            Emitters::EmitterConvergent(obj) => obj.base_emitter.base_object.set_radius(radius),
+            Emitters::EmitterTargeted(obj) => obj.base_emitter.base_object.set_radius(radius),
         }
     }
 }
@@ -147,92 +420,94 @@ impl VariableSize for Emitters {
 impl VariableOrientation for Emitters {
     /// Changes the orientation of the emitter.
     ///
-    /// This method applies the orientation change only to `EmitterCollimated`
-    /// and `EmitterSpotlight` variants. Other variants are unaffected.
+    /// This method applies the orientation change only to `EmitterCollimated`,
+    /// `EmitterSpotlight`, and `EmitterConvergent` (whose target rotates
+    /// around it) variants. Other variants are unaffected.
     fn change_orientation(&mut self, factor: f32) {
         match self {
             Emitters::EmitterCollimated(obj) => obj.orientation += factor,
             Emitters::EmitterSpotlight(obj) => obj.orientation += factor,
+            Emitters::EmitterConvergent(obj) => obj.set_orientation(obj.orientation() + factor),
+            _ => {}
+        }
+    }
+
+    /// Resets the orientation of the emitter to its default.
+    ///
+    /// Like `change_orientation`, only `EmitterCollimated`, `EmitterSpotlight`,
+    /// and `EmitterConvergent` are affected. The caller is responsible for
+    /// reinitializing the rays.
+    fn reset_orientation(&mut self) {
+        match self {
+            Emitters::EmitterCollimated(obj) => obj.orientation = OBJD_COLLIMATED_ORIENTATION,
+            Emitters::EmitterSpotlight(obj) => obj.orientation = OBJD_SPOTLIGHT_ORIENTATION,
+            Emitters::EmitterConvergent(obj) => obj.set_orientation(OBJD_CONVERGENT_ORIENTATION),
+            _ => {}
+        }
+    }
+
+    /// Sets the orientation (in radians) of the emitter.
+    ///
+    /// Like `change_orientation`, only `EmitterCollimated`, `EmitterSpotlight`,
+    /// and `EmitterConvergent` are affected. The caller is responsible for
+    /// reinitializing the rays.
+    fn set_orientation(&mut self, orientation: f32) {
+        match self {
+            Emitters::EmitterCollimated(obj) => obj.orientation = orientation,
+            Emitters::EmitterSpotlight(obj) => obj.orientation = orientation,
+            Emitters::EmitterConvergent(obj) => obj.set_orientation(orientation),
             _ => {}
         }
     }
 }
 
 impl VariableRays for Emitters {
-    fn change_rays_count(&mut self, change_rays: i32) {
-        fn check_rays_range(ray_count: i32, change_rays: i32) {
-            if ray_count + change_rays > OBJC_MAX_RAY_COUNT {
-                eprintln!(
-                    "Raytracer ~Err. Added too many rays, more than OBJC_MAX_RAY_COUNT. Program will still run, but may become unstable since there are too many rays."
-                );
-            } else if ray_count + change_rays < OBJC_MIN_RAY_COUNT {
-                eprintln!(
-                    "Raytracer ~Err. Cannot reduce below minimum ray count of {}. Operation ignored.",
-                    OBJC_MIN_RAY_COUNT
-                );
-            }
+    /// Changes the number of rays of the emitter.
+    ///
+    /// Only the ray count is changed, the rays are rebuilt by
+    /// `init_all_rays()` like after any other edit.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the rays were changed
+    /// * `Err(RaytracerError::TooManyRays)` - If adding rays would push the total
+    ///   number of rays in the scene, `scene_total_rays`, over `OBJC_MAX_TOTAL_RAYS`
+    /// * `Err(RaytracerError::TooFewRays)` - If the emitter would have fewer than
+    ///   `OBJC_MIN_RAY_COUNT` rays
+    /// * `Err(RaytracerError::FixedRays)` - If the emitter is an
+    ///   `EmitterTargeted`, whose rays follow its targets
+    fn change_rays_count(
+        &mut self,
+        change_rays: i32,
+        scene_total_rays: i32,
+    ) -> Result<(), RaytracerError> {
+        if let Emitters::EmitterTargeted(_) = self {
+            return Err(RaytracerError::FixedRays);
         }
 
-        match self {
-            Emitters::EmitterIsotropic(obj) => {
-                let ray_count = obj.rays.len() as i32;
-
-                // Only proceed if we won't go below the minimum
-                if ray_count + change_rays >= OBJC_MIN_RAY_COUNT {
-                    obj.rays = init_isotropic_rays(
-                        obj.base_object.pos_x,
-                        obj.base_object.pos_y,
-                        ray_count + change_rays,
-                    );
-                    check_rays_range(ray_count, change_rays);
-                } else {
-                    eprintln!(
-                        "Raytracer ~Err. Cannot reduce below minimum ray count of {}.",
-                        OBJC_MIN_RAY_COUNT
-                    );
-                }
-            }
-            Emitters::EmitterCollimated(obj) => {
-                let ray_count = obj.base_emitter.rays.len() as i32;
-
-                // Only proceed if we won't go below the minimum
-                if ray_count + change_rays >= OBJC_MIN_RAY_COUNT {
-                    obj.base_emitter.rays = init_collimated_rays(
-                        obj.base_emitter.base_object.pos_x,
-                        obj.base_emitter.base_object.pos_y,
-                        obj.orientation,
-                        obj.collimated_beam_diameter,
-                        ray_count + change_rays,
-                    );
-                    check_rays_range(ray_count, change_rays);
-                } else {
-                    eprintln!(
-                        "Raytracer ~Err. Cannot reduce below minimum ray count of {}.",
-                        OBJC_MIN_RAY_COUNT
-                    );
-                }
-            }
-            Emitters::EmitterSpotlight(obj) => {
-                let ray_count = obj.base_emitter.rays.len() as i32;
-
-                // Only proceed if we won't go below the minimum
-                if ray_count + change_rays >= OBJC_MIN_RAY_COUNT {
-                    obj.base_emitter.rays = init_spotlight_rays(
-                        obj.base_emitter.base_object.pos_x,
-                        obj.base_emitter.base_object.pos_y,
-                        obj.orientation,
-                        obj.spotlight_beam_angle,
-                        ray_count + change_rays,
-                    );
-                    check_rays_range(ray_count, change_rays);
-                } else {
-                    eprintln!(
-                        "Raytracer ~Err. Cannot reduce below minimum ray count of {}.",
-                        OBJC_MIN_RAY_COUNT
-                    );
-                }
-            }
+        if change_rays > 0 && scene_total_rays + change_rays > OBJC_MAX_TOTAL_RAYS {
+            return Err(RaytracerError::TooManyRays(OBJC_MAX_TOTAL_RAYS));
+        }
+
+        let new_count = self.get_rays_count() + change_rays;
+        if new_count < OBJC_MIN_RAY_COUNT {
+            return Err(RaytracerError::TooFewRays(OBJC_MIN_RAY_COUNT));
+        }
+
+        if new_count > OBJC_MAX_RAY_COUNT {
+            eprintln!(
+                "Raytracer ~Err. Added too many rays, more than OBJC_MAX_RAY_COUNT. Program will still run, but may become unstable since there are too many rays."
+            );
         }
+
+        // the rays follow the count in the next init_all_rays()
+        self.get_base_emitter_mut().ray_count = new_count;
+
+        Ok(())
+    }
+
+    fn get_rays_count(&self) -> i32 {
+        self.get_base_emitter().ray_count
     }
 }
 
@@ -246,6 +521,28 @@ pub struct EmitterIsotropic {
     pub base_object: ObjectCircle,
     /// Collection of light rays emanating from this emitter
     pub rays: Vec<ObjectRay>,
+    /// The number of rays the emitter should have, the rays are always
+    /// rebuilt with this many instead of however many `rays` holds
+    pub ray_count: i32,
+    /// Thickness of every ray of this emitter when drawn
+    pub ray_width: f32,
+    /// Color of every ray of this emitter when drawn
+    pub ray_color: Color,
+    /// Wavelength (in nanometers) of every ray of this emitter, which then
+    /// overrides `ray_color`, `None` for light without a wavelength
+    pub wavelength_nm: Option<f32>,
+    /// The farthest distance a ray of this emitter reaches, `None` if the rays
+    /// are not limited (finite-range lights like a weak flashlight)
+    pub max_length: Option<f32>,
+    /// Segments of the rays after they reflect off mirrors, rebuilt by
+    /// `check_for_occlusion()` and drawn with the rays
+    pub reflections: Vec<ObjectRay>,
+    /// How many of the rays ended on an absorber (after any reflections),
+    /// counted by `check_for_occlusion()`
+    pub blocked_rays: usize,
+    /// Whether the emitter is switched on, a disabled emitter keeps its rays
+    /// and settings but its rays are not drawn, occluded, or counted as light
+    pub enabled: bool,
 }
 
 impl EmitterIsotropic {
@@ -261,9 +558,63 @@ impl EmitterIsotropic {
     ///
     /// # Returns
     ///
-    /// A new `EmitterIsotropic` instance with the specified parameters
+    /// A new `EmitterIsotropic` instance with the specified parameters, its
+    /// rays are `OBJD_RAY_WIDTH` thick, the current theme's ray color, and have
+    /// no wavelength or range limit. Its ray count is the number of `rays`, or
+    /// `OBJD_RAY_COUNT` if there are none.
     pub fn new(base_object: ObjectCircle, rays: Vec<ObjectRay>) -> Self {
-        EmitterIsotropic { base_object, rays }
+        EmitterIsotropic {
+            base_object,
+            ray_count: if rays.is_empty() {
+                OBJD_RAY_COUNT
+            } else {
+                rays.len() as i32
+            },
+            rays,
+            ray_width: OBJD_RAY_WIDTH,
+            ray_color: THEME.read().unwrap().ray_color,
+            wavelength_nm: None,
+            max_length: None,
+            reflections: Vec::new(),
+            blocked_rays: 0,
+            enabled: true,
+        }
+    }
+
+    /// Draws the emitter's rays, batched if `MACROQUAD_BATCH_RAYS` is set
+    ///
+    /// Nothing is drawn if the emitter is disabled. If `RAY_DRAW_STRIDE` is
+    /// more than 1, only every Nth ray (and every Nth reflection) is drawn.
+    pub fn draw_rays(&self) {
+        if !self.enabled {
+            return;
+        }
+
+        let stride = (*RAY_DRAW_STRIDE.read().unwrap()).max(1);
+        if MACROQUAD_BATCH_RAYS {
+            if stride == 1 {
+                draw_rays_batched(&self.rays);
+                draw_rays_batched(&self.reflections);
+            } else {
+                let rays: Vec<ObjectRay> = self
+                    .rays
+                    .iter()
+                    .step_by(stride)
+                    .chain(self.reflections.iter().step_by(stride))
+                    .cloned()
+                    .collect();
+                draw_rays_batched(&rays);
+            }
+        } else {
+            for ray in self
+                .rays
+                .iter()
+                .step_by(stride)
+                .chain(self.reflections.iter().step_by(stride))
+            {
+                ray.draw_object();
+            }
+        }
     }
 }
 
@@ -273,17 +624,23 @@ impl Drawable for EmitterIsotropic {
     /// Renders the emitter as a colored circle and draws all of its
     /// associated light rays emanating from it.
     fn draw_object(&self) {
-        // Draw the emitter's physical representation (a circle)
+        // Draw the emitter's physical representation (a circle), faded out
+        // if the emitter is disabled
+        let mut color_fill = self.base_object.color_fill;
+        if !self.enabled {
+            color_fill.a *= OBJD_DISABLED_ALPHA;
+        }
         draw_circle(
             self.base_object.pos_x,
             self.base_object.pos_y,
             self.base_object.radius,
-            self.base_object.color_fill,
+            color_fill,
         );
 
-        // Draw all the light rays associated with this emitter
-        for ray in &self.rays {
-            ray.draw_object();
+        // Draw all the light rays associated with this emitter, unless the
+        // main loop draws them in its additive pass
+        if !*ADDITIVE_RAYS.read().unwrap() {
+            self.draw_rays();
         }
     }
 }
@@ -325,7 +682,7 @@ impl EmitterCollimated {
         collimated_beam_diameter: f32,
     ) -> Self {
         EmitterCollimated {
-            base_emitter: EmitterIsotropic { base_object, rays },
+            base_emitter: EmitterIsotropic::new(base_object, rays),
             orientation,
             collimated_beam_diameter,
         }
@@ -372,9 +729,264 @@ impl EmitterSpotlight {
         spotlight_beam_angle: f32,
     ) -> Self {
         EmitterSpotlight {
-            base_emitter: EmitterIsotropic { base_object, rays },
+            base_emitter: EmitterIsotropic::new(base_object, rays),
             orientation,
             spotlight_beam_angle,
         }
     }
 }
+
+/// Represents a convergent (focusing) emitter.
+///
+/// This emitter produces rays that start across the emitter's body and converge
+/// toward a single `target` point before continuing past it, like the light
+/// behind a converging lens. The target keeps its offset from the emitter when
+/// the emitter is moved, and rotating the emitter rotates the target around it.
+#[derive(Clone, Debug)]
+pub struct EmitterConvergent {
+    /// The underlying emitter providing basic functionality
+    pub base_emitter: EmitterIsotropic,
+    /// The point (x, y) that the rays converge toward
+    pub target: (f32, f32),
+}
+
+impl EmitterConvergent {
+    /// Creates a new convergent emitter with the specified properties.
+    ///
+    /// # Arguments
+    ///
+    /// * `base_object` - The physical properties of the emitter
+    /// * `rays` - Collection of rays to be emitted from this source
+    /// * `target` - The point (x, y) that the rays converge toward
+    ///
+    /// # Returns
+    ///
+    /// A new `EmitterConvergent` instance with the specified parameters
+    pub fn new(base_object: ObjectCircle, rays: Vec<ObjectRay>, target: (f32, f32)) -> Self {
+        EmitterConvergent {
+            base_emitter: EmitterIsotropic::new(base_object, rays),
+            target,
+        }
+    }
+
+    /// Gets the default target of an emitter centered at (`pos_x`, `pos_y`)
+    ///
+    /// The target is `OBJD_CONVERGENT_DISTANCE` away from the center in the
+    /// direction of `OBJD_CONVERGENT_ORIENTATION`.
+    pub fn default_target(pos_x: f32, pos_y: f32) -> (f32, f32) {
+        (
+            pos_x + OBJD_CONVERGENT_DISTANCE * OBJD_CONVERGENT_ORIENTATION.cos(),
+            pos_y + OBJD_CONVERGENT_DISTANCE * OBJD_CONVERGENT_ORIENTATION.sin(),
+        )
+    }
+
+    /// Gets the distance from the emitter's center to its target
+    pub fn focus_distance(&self) -> f32 {
+        let base_object = &self.base_emitter.base_object;
+        ((self.target.0 - base_object.pos_x).powi(2) + (self.target.1 - base_object.pos_y).powi(2))
+            .sqrt()
+    }
+
+    /// Gets the angle (in radians) from the emitter's center to its target
+    pub fn orientation(&self) -> f32 {
+        let base_object = &self.base_emitter.base_object;
+        (self.target.1 - base_object.pos_y).atan2(self.target.0 - base_object.pos_x)
+    }
+
+    /// Moves the target around the emitter to the given angle (in radians),
+    /// keeping its distance
+    pub fn set_orientation(&mut self, orientation: f32) {
+        let base_object = &self.base_emitter.base_object;
+        let distance = self.focus_distance();
+        self.target = (
+            base_object.pos_x + distance * orientation.cos(),
+            base_object.pos_y + distance * orientation.sin(),
+        );
+    }
+}
+
+/// Represents an emitter aimed at a list of target points.
+///
+/// This emitter produces exactly one ray per target, starting at the emitter's
+/// center, passing through the target, and continuing past it. The targets
+/// are points in the scene: they stay in place when the emitter is moved, so
+/// every ray is aimed again. This gives precise control over individual rays,
+/// like an optical bench.
+#[derive(Clone, Debug)]
+pub struct EmitterTargeted {
+    /// The underlying emitter providing basic functionality
+    pub base_emitter: EmitterIsotropic,
+    /// The points (x, y) that the rays are aimed at, one ray each
+    pub targets: Vec<(f32, f32)>,
+}
+
+impl EmitterTargeted {
+    /// Creates a new targeted emitter with the specified properties.
+    ///
+    /// # Arguments
+    ///
+    /// * `base_object` - The physical properties of the emitter
+    /// * `rays` - Collection of rays to be emitted from this source
+    /// * `targets` - The points (x, y) that the rays are aimed at
+    ///
+    /// # Returns
+    ///
+    /// A new `EmitterTargeted` instance with the specified parameters, with one
+    /// ray per target
+    pub fn new(base_object: ObjectCircle, rays: Vec<ObjectRay>, targets: Vec<(f32, f32)>) -> Self {
+        let mut emitter = EmitterTargeted {
+            base_emitter: EmitterIsotropic::new(base_object, rays),
+            targets: Vec::new(),
+        };
+        emitter.set_targets(targets);
+        emitter
+    }
+
+    /// Replaces the targets, the ray count follows the number of targets
+    ///
+    /// The rays are rebuilt by `init_all_rays()`.
+    pub fn set_targets(&mut self, targets: Vec<(f32, f32)>) {
+        self.base_emitter.ray_count = targets.len() as i32;
+        self.targets = targets;
+    }
+
+    /// Aims one more ray at a point
+    ///
+    /// # Arguments
+    ///
+    /// * `target` - The point (x, y) to aim at
+    /// * `scene_total_rays` - The number of rays in the scene
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the target was added
+    /// * `Err(RaytracerError::TooManyRays)` - If another ray would push the
+    ///   scene over `OBJC_MAX_TOTAL_RAYS`
+    pub fn add_target(
+        &mut self,
+        target: (f32, f32),
+        scene_total_rays: i32,
+    ) -> Result<(), RaytracerError> {
+        if scene_total_rays >= OBJC_MAX_TOTAL_RAYS {
+            return Err(RaytracerError::TooManyRays(OBJC_MAX_TOTAL_RAYS));
+        }
+
+        self.targets.push(target);
+        self.base_emitter.ray_count = self.targets.len() as i32;
+        Ok(())
+    }
+
+    /// Gets the default targets of an emitter centered at (`pos_x`, `pos_y`)
+    ///
+    /// The targets are the `OBJD_TARGETED_OFFSETS` from the center.
+    pub fn default_targets(pos_x: f32, pos_y: f32) -> Vec<(f32, f32)> {
+        OBJD_TARGETED_OFFSETS
+            .iter()
+            .map(|(dx, dy)| (pos_x + dx, pos_y + dy))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use macroquad::color::WHITE;
+
+    fn emitter_with_rays(ray_count: usize) -> Emitters {
+        let ray = ObjectRay::new(0.0, 0.0, 10.0, 0.0, OBJD_RAY_WIDTH, WHITE);
+        Emitters::EmitterIsotropic(EmitterIsotropic::new(
+            ObjectCircle::new(0.0, 0.0, WHITE, 10.0),
+            vec![ray; ray_count],
+        ))
+    }
+
+    #[test]
+    fn invalid_ray_changes_are_rejected() {
+        let mut emitter = emitter_with_rays(OBJC_MIN_RAY_COUNT as usize);
+
+        assert_eq!(
+            emitter.change_rays_count(-1, OBJC_MIN_RAY_COUNT),
+            Err(RaytracerError::TooFewRays(OBJC_MIN_RAY_COUNT))
+        );
+        assert_eq!(
+            emitter.change_rays_count(1, OBJC_MAX_TOTAL_RAYS),
+            Err(RaytracerError::TooManyRays(OBJC_MAX_TOTAL_RAYS))
+        );
+        assert_eq!(emitter.get_rays_count(), OBJC_MIN_RAY_COUNT);
+    }
+
+    #[test]
+    fn targeted_ray_count_follows_targets() {
+        let ray = ObjectRay::new(0.0, 0.0, 10.0, 0.0, OBJD_RAY_WIDTH, WHITE);
+        let mut emitter = Emitters::EmitterTargeted(EmitterTargeted::new(
+            ObjectCircle::new(0.0, 0.0, WHITE, 10.0),
+            vec![ray; 2],
+            vec![(10.0, 0.0), (0.0, 10.0)],
+        ));
+
+        assert_eq!(
+            emitter.change_rays_count(1, 0),
+            Err(RaytracerError::FixedRays)
+        );
+        assert_eq!(emitter.get_rays_count(), 2);
+    }
+
+    #[test]
+    fn moving_leaves_the_rays_to_init_all_rays() {
+        let mut emitter = emitter_with_rays(4);
+        let ends: Vec<_> = emitter
+            .get_rays()
+            .iter()
+            .map(|r| (r.end_x, r.end_y))
+            .collect();
+        emitter.move_object(50.0, 60.0);
+
+        assert_eq!(emitter.get_base_object().pos_x, 50.0);
+        assert_eq!(emitter.get_base_object().pos_y, 60.0);
+        assert_eq!(
+            emitter
+                .get_rays()
+                .iter()
+                .map(|r| (r.end_x, r.end_y))
+                .collect::<Vec<_>>(),
+            ends
+        );
+
+        let mut convergent = Emitters::EmitterConvergent(EmitterConvergent::new(
+            ObjectCircle::new(0.0, 0.0, WHITE, 10.0),
+            vec![],
+            (100.0, 0.0),
+        ));
+        convergent.move_object(5.0, 5.0);
+        let Emitters::EmitterConvergent(o) = &convergent else {
+            unreachable!()
+        };
+        assert_eq!(o.target, (105.0, 5.0));
+    }
+
+    #[test]
+    fn ray_count_is_kept_apart_from_the_rays() {
+        let mut emitter = emitter_with_rays(5);
+        emitter.get_base_emitter_mut().rays.clear();
+        assert_eq!(emitter.get_rays_count(), 5);
+
+        // the rays are left for init_all_rays()
+        assert_eq!(emitter.change_rays_count(2, 0), Ok(()));
+        assert_eq!(emitter.get_rays_count(), 7);
+        assert!(emitter.get_rays().is_empty());
+
+        assert_eq!(emitter_with_rays(0).get_rays_count(), OBJD_RAY_COUNT);
+    }
+
+    #[test]
+    fn blocked_fraction_counts_rays_that_ended_on_an_absorber() {
+        let mut emitter = emitter_with_rays(4);
+        assert_eq!(emitter.blocked_fraction(), 0.0);
+
+        emitter.get_base_emitter_mut().blocked_rays = 1;
+        assert_eq!(emitter.blocked_fraction(), 0.25);
+
+        emitter.get_base_emitter_mut().rays.clear();
+        assert_eq!(emitter.blocked_fraction(), 0.0);
+    }
+}
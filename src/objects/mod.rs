@@ -9,6 +9,7 @@
 //! - `emitters`: Light emitter implementations (isotropic and collimated)
 //! - `occlusion`: The functions for occlusion
 //! - `ray`: Ray objects that represent light paths
+//!
 //! author:         Zhean Ganituen (zrygan)
 //! last updated:   April 16, 2025
 
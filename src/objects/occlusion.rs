@@ -3,10 +3,133 @@
 //! author:         Zhean Ganituen
 //! last updated:   April 18, 2025
 
-use super::{absorber::Absorbers, behavior::RaytracerObjects, emitters::*, ray::ObjectRay};
-use crate::OBJ_COLLECTION;
+use super::{
+    absorber::{AbsorberArc, Absorbers},
+    behavior::RaytracerObjects,
+    ray::ObjectRay,
+};
+use crate::{
+    OBJ_COLLECTION,
+    globals::{
+        ABSORBERS_GENERATION, OBJC_MAX_REFLECTIONS, OBJC_OCCLUSION_EARLY_EXIT_T,
+        OBJC_OCCLUSION_EPSILON,
+    },
+};
+use once_cell::sync::Lazy;
+use std::sync::RwLock;
 
-pub fn occlusion(occluder: &Absorbers, ray: &ObjectRay) -> Option<(f32, f32)> {
+/// The absorbers of the scene as of the last `check_for_occlusion()` pass,
+/// with the `ABSORBERS_GENERATION` they were copied at (`None` before the
+/// first pass)
+static OCCLUSION_ABSORBERS: Lazy<RwLock<(Option<u64>, Vec<Absorbers>)>> =
+    Lazy::new(|| RwLock::new((None, Vec::new())));
+
+/// Finds where a ray first enters (or exits) an absorber
+///
+/// The ray is written parametrically as `P(t) = start + t * (end - start)`,
+/// so `t = 0` is the start of the ray and `t = 1` is its end. Substituting
+/// `P(t)` into the circle equation gives a quadratic `a t² + b t + c = 0`
+/// whose roots are the points where the ray crosses the absorber's boundary.
+///
+/// Only roots with `t ∈ (EPS, 1]` are accepted, where `EPS` is
+/// `OBJC_OCCLUSION_EPSILON`. The smaller root is preferred, which is the
+/// nearest point along the ray. Since roots at the start are excluded, a ray
+/// that starts inside the absorber is cut where it exits, and a degenerate
+/// zero-length ray (`a == 0`) never hits.
+///
+/// A ray that starts on the boundary (a root within `EPS` of `t = 0`) is
+/// blocked at its start (`t = 0`) if it heads into the absorber (`b < 0`).
+/// Without this, floating point error can put that root just below 0 and let
+/// the ray leak through to the far side of the absorber.
+///
+/// For an `AbsorberArc` (or a `MirrorArc`), a root only counts as a hit if the
+/// point lies on the arc, i.e. its angle around the center is within the
+/// arc's angular range. A ray that crosses the circle off the arc passes
+/// through that point.
+///
+/// # Arguments
+///
+/// * `occluder` - The absorber that may block the ray
+/// * `ray` - The ray to test
+///
+/// # Returns
+///
+/// * `Some((t, (x, y)))` - The parametric distance `t` along the ray and the
+///   point where the ray hits the absorber
+/// * `None` - If the ray does not reach the absorber
+pub fn occlusion(occluder: &Absorbers, ray: &ObjectRay) -> Option<(f32, (f32, f32))> {
+    occlusion_from(occluder, ray, true)
+}
+
+/// Finds where a ray first hits an absorber, see `occlusion()`
+///
+/// If `block_at_start` is false, a ray starting on the boundary is not blocked
+/// at its start, which is needed for rays leaving the surface of a mirror.
+///
+/// Absorbers the ray cannot reach (see `could_reach()`) are skipped before
+/// solving the quadratic.
+fn occlusion_from(
+    occluder: &Absorbers,
+    ray: &ObjectRay,
+    block_at_start: bool,
+) -> Option<(f32, (f32, f32))> {
+    if !could_reach(occluder, ray) {
+        return None;
+    }
+
+    solve_occlusion(occluder, ray, block_at_start)
+}
+
+/// Checks if a ray could touch an absorber's circle at all
+///
+/// This is a cheap bound, so most absorbers away from a ray are ruled out
+/// without solving the quadratic in `solve_occlusion()`. The ray cannot reach
+/// the circle if the whole circle is behind its start (the center's
+/// projection onto the ray direction is below `-radius`), or if the circle is
+/// farther from the start than the ray is long (the center is farther than
+/// the ray length plus `radius`). A circle whose center is only slightly
+/// behind the start can still be crossed, e.g. by a ray leaving it from the
+/// inside.
+///
+/// Both bounds are widened by `OBJC_OCCLUSION_EPSILON` of the ray length, so
+/// the roots `solve_occlusion()` accepts near the ends of the ray are never
+/// ruled out.
+///
+/// # Arguments
+///
+/// * `occluder` - The absorber that may block the ray
+/// * `ray` - The ray to test
+///
+/// # Returns
+///
+/// `false` if the ray certainly misses the absorber, `true` if it may hit it
+fn could_reach(occluder: &Absorbers, ray: &ObjectRay) -> bool {
+    let base_object = occluder.get_base_object();
+    let direction = (ray.end_x - ray.start_x, ray.end_y - ray.start_y);
+    let to_center = (
+        base_object.pos_x - ray.start_x,
+        base_object.pos_y - ray.start_y,
+    );
+
+    let length = (direction.0.powi(2) + direction.1.powi(2)).sqrt();
+    let reach = base_object.radius + OBJC_OCCLUSION_EPSILON * length;
+
+    // the projection of the center onto the ray, scaled by the ray length
+    let projection = to_center.0 * direction.0 + to_center.1 * direction.1;
+    if projection < -reach * length {
+        return false;
+    }
+
+    to_center.0.powi(2) + to_center.1.powi(2) <= (length + reach).powi(2)
+}
+
+/// Finds where a ray first hits an absorber by solving the quadratic, see
+/// `occlusion_from()`
+fn solve_occlusion(
+    occluder: &Absorbers,
+    ray: &ObjectRay,
+    block_at_start: bool,
+) -> Option<(f32, (f32, f32))> {
     // get the slope of the ray
     let xs = ray.start_x;
     let xf = ray.end_x;
@@ -14,13 +137,8 @@ pub fn occlusion(occluder: &Absorbers, ray: &ObjectRay) -> Option<(f32, f32)> {
     let yf = ray.end_y;
     let slope = (xf - xs, yf - ys);
 
-    let (pos_x, pos_y, radius) = match occluder {
-        Absorbers::AbsorberPerfect(o) => (
-            o.base_object.pos_x,
-            o.base_object.pos_y,
-            o.base_object.radius,
-        ),
-    };
+    let base_object = occluder.get_base_object();
+    let (pos_x, pos_y, radius) = (base_object.pos_x, base_object.pos_y, base_object.radius);
 
     // coefficients of the quadratic
     let a: f32 = slope.0.powi(2) + slope.1.powi(2);
@@ -48,65 +166,525 @@ pub fn occlusion(occluder: &Absorbers, ray: &ObjectRay) -> Option<(f32, f32)> {
         0.0
     };
 
+    // a ray starting on the boundary and heading inside is blocked at its start
+    let enters_at_start = block_at_start
+        && b < 0.0
+        && (sol_1.abs() <= OBJC_OCCLUSION_EPSILON || sol_2.abs() <= OBJC_OCCLUSION_EPSILON);
+
     // check both solutions choose the one that is after the start of the ray
-    if (0.0 < sol_1) && (sol_1 <= 1.0) {
-        return Some((xs + sol_1 * slope.0, ys + sol_1 * slope.1));
-    } else if (0.0 < sol_2) && (sol_2 <= 1.0) {
-        return Some((xs + sol_2 * slope.0, ys + sol_2 * slope.1));
+    // (and, for arcs, on the arc)
+    enters_at_start
+        .then_some(0.0)
+        .into_iter()
+        .chain(
+            [sol_1, sol_2]
+                .into_iter()
+                .filter(|t| (OBJC_OCCLUSION_EPSILON < *t) && (*t <= 1.0)),
+        )
+        .map(|t| (t, (xs + t * slope.0, ys + t * slope.1)))
+        .find(|(_, (x, y))| match occluder {
+            Absorbers::AbsorberPerfect(_) => true,
+            Absorbers::AbsorberArc(o) | Absorbers::MirrorArc(o) => {
+                o.contains_angle((y - pos_y).atan2(x - pos_x))
+            }
+        })
+}
+
+/// Reflects the rest of a ray off a mirror at the point where it hits it
+///
+/// The part of the ray past the hit is mirrored about the normal of the
+/// circle at that point, `r = d - 2 (d · n) n`, so the reflected ray has the
+/// same length as the part of the ray it replaces. Both sides of the arc
+/// reflect.
+///
+/// # Arguments
+///
+/// * `mirror` - The mirror that was hit
+/// * `ray` - The ray before it is truncated at the hit
+/// * `t` - The parametric distance of the hit along the ray
+/// * `hit_point` - The point where the ray hits the mirror
+///
+/// # Returns
+///
+/// A ray starting at the hit point, with the same thickness, color, and
+/// wavelength
+pub fn reflect_off_mirror(
+    mirror: &AbsorberArc,
+    ray: &ObjectRay,
+    t: f32,
+    hit_point: (f32, f32),
+) -> ObjectRay {
+    let remaining = (
+        (ray.end_x - ray.start_x) * (1.0 - t),
+        (ray.end_y - ray.start_y) * (1.0 - t),
+    );
+
+    let base_object = &mirror.base_object;
+    let normal = (
+        (hit_point.0 - base_object.pos_x) / base_object.radius,
+        (hit_point.1 - base_object.pos_y) / base_object.radius,
+    );
+    let dot = remaining.0 * normal.0 + remaining.1 * normal.1;
+
+    ObjectRay {
+        wavelength_nm: ray.wavelength_nm,
+        ..ObjectRay::new(
+            hit_point.0,
+            hit_point.1,
+            hit_point.0 + remaining.0 - 2.0 * dot * normal.0,
+            hit_point.1 + remaining.1 - 2.0 * dot * normal.1,
+            ray.thickness,
+            ray.color,
+        )
     }
+}
+
+/// Finds the absorber a ray hits first
+///
+/// # Arguments
+///
+/// * `absorbers` - Every absorber in the scene
+/// * `ray` - The ray to test
+/// * `leaving` - The index of the mirror the ray starts on, if any, which is
+///   not allowed to block the ray at its start
+///
+/// # Returns
+///
+/// The index of the absorber, the parametric distance of the hit, and the
+/// point of the hit, or `None` if the ray hits nothing
+fn nearest_hit(
+    absorbers: &[Absorbers],
+    ray: &ObjectRay,
+    leaving: Option<usize>,
+) -> Option<(usize, f32, (f32, f32))> {
+    let mut nearest: Option<(usize, f32, (f32, f32))> = None;
+
+    for (index, absorber) in absorbers.iter().enumerate() {
+        let hit = if leaving == Some(index) {
+            occlusion_from(absorber, ray, false)
+        } else {
+            occlusion(absorber, ray)
+        };
+
+        if let Some((t, hit_point)) = hit
+            && nearest.is_none_or(|(_, nearest_t, _)| t < nearest_t)
+        {
+            nearest = Some((index, t, hit_point));
 
-    return None;
+            // nothing can be hit before the start of the ray
+            if t <= OBJC_OCCLUSION_EARLY_EXIT_T {
+                break;
+            }
+        }
+    }
+
+    nearest
+}
+
+/// Moves a ray's end point to the first absorber it hits
+///
+/// # Arguments
+///
+/// * `absorbers` - Every absorber in the scene
+/// * `ray` - The ray to truncate
+/// * `leaving` - The index of the mirror the ray starts on, if any
+///
+/// # Returns
+///
+/// The index of the absorber the ray hits and, if it is a mirror, the
+/// reflected ray, or `None` if the ray hits nothing
+fn truncate_at_hit(
+    absorbers: &[Absorbers],
+    ray: &mut ObjectRay,
+    leaving: Option<usize>,
+) -> Option<(usize, Option<ObjectRay>)> {
+    let (index, t, (x, y)) = nearest_hit(absorbers, ray, leaving)?;
+
+    let reflected = match &absorbers[index] {
+        Absorbers::MirrorArc(mirror) => Some(reflect_off_mirror(mirror, ray, t, (x, y))),
+        _ => None,
+    };
+
+    // Move the ray's end point to the hit
+    ray.end_x = x;
+    ray.end_y = y;
+
+    Some((index, reflected))
+}
+
+/// Traces a ray through the scene, truncating it at the first absorber
+///
+/// If that absorber is a mirror, the reflected ray is traced the same way,
+/// until it hits an absorber, misses everything, or `OBJC_MAX_REFLECTIONS`
+/// reflections were traced.
+///
+/// # Arguments
+///
+/// * `absorbers` - Every absorber in the scene
+/// * `ray` - The ray to trace, its end point is moved to the first hit
+///
+/// # Returns
+///
+/// The reflected segments, in the order the light travels along them, and
+/// whether the light ended on an (non-mirror) absorber
+pub fn trace_ray(absorbers: &[Absorbers], ray: &mut ObjectRay) -> (Vec<ObjectRay>, bool) {
+    let mut reflections = Vec::new();
+    let mut hit = truncate_at_hit(absorbers, ray, None);
+
+    while let Some((mirror, Some(mut segment))) = hit {
+        hit = truncate_at_hit(absorbers, &mut segment, Some(mirror));
+        reflections.push(segment);
+
+        if reflections.len() >= OBJC_MAX_REFLECTIONS {
+            break;
+        }
+    }
+
+    (reflections, matches!(hit, Some((_, None))))
 }
 
-pub fn check_for_occlusion() {
+/// Finds the perfect absorber a point is buried in
+///
+/// Arc absorbers and mirrors are only their thin arc, so nothing is ever
+/// inside them. A point exactly on the boundary of a perfect absorber is not
+/// inside it.
+///
+/// # Arguments
+///
+/// * `absorbers` - Every absorber in the scene
+/// * `pos_x` - X coordinate of the point
+/// * `pos_y` - Y coordinate of the point
+///
+/// # Returns
+///
+/// The index of the first perfect absorber containing the point, or `None`
+pub fn absorber_at_source(absorbers: &[Absorbers], pos_x: f32, pos_y: f32) -> Option<usize> {
+    absorbers.iter().position(|absorber| match absorber {
+        Absorbers::AbsorberPerfect(o) => {
+            let base_object = &o.base_object;
+            (pos_x - base_object.pos_x).powi(2) + (pos_y - base_object.pos_y).powi(2)
+                < base_object.radius.powi(2)
+        }
+        Absorbers::AbsorberArc(_) | Absorbers::MirrorArc(_) => false,
+    })
+}
+
+/// Truncates every emitter's rays at the nearest absorber they hit
+///
+/// Each ray is tested against every absorber, keeping the hit with the
+/// smallest parametric distance `t` (see `occlusion()`). Since `t` is measured
+/// along the same ray for every absorber, no lengths need to be computed. Once
+/// a hit with `t <= OBJC_OCCLUSION_EARLY_EXIT_T` is found, nothing can be
+/// nearer, so the remaining absorbers are skipped.
+/// Absorbers a ray points away from or cannot reach are ruled out without
+/// solving for the hit (see `could_reach()`).
+///
+/// Rays that hit a mirror are reflected (see `trace_ray()`), the reflected
+/// segments are stored in the emitter's `reflections`. The rays whose light
+/// ends on an absorber are counted in the emitter's `blocked_rays`.
+///
+/// An emitter whose center is inside a perfect absorber (see
+/// `absorber_at_source()`) is buried: the absorber takes all of its light, so
+/// every ray is cut to zero length at its start and nothing is reflected.
+/// Otherwise, rays would escape up to the absorber's edge, leaving a short
+/// stub that looks like a broken emitter. Those emitters are returned so the
+/// caller can point them out.
+///
+/// The absorbers are copied out of the collection so the emitters can be
+/// changed while testing against them. The copy is kept between passes and
+/// only made again when `ABSORBERS_GENERATION` has changed, so moving an
+/// emitter (the common case) neither copies nor compares the absorbers.
+///
+/// # Thread Safety
+///
+/// This function acquires a write lock on the `OBJ_COLLECTION` global.
+///
+/// # Returns
+///
+/// The indices of the enabled emitters buried in an absorber
+pub fn check_for_occlusion() -> Vec<usize> {
+    let mut collection = OBJ_COLLECTION.write().unwrap();
+    let mut cache = OCCLUSION_ABSORBERS.write().unwrap();
+    let generation = *ABSORBERS_GENERATION.read().unwrap();
+
     // Filter absorbers from the collection
-    let absorbers: Vec<_> = {
-        let collection = OBJ_COLLECTION.read().unwrap();
-        collection
-            .iter()
-            .filter_map(|obj| {
-                if let RaytracerObjects::Absorbers(absorber) = obj {
-                    Some(absorber.clone())
-                } else {
-                    None
+    if cache.0 != Some(generation) {
+        cache.0 = Some(generation);
+        cache.1.clear();
+        cache.1.extend(collection.iter().filter_map(|obj| {
+            if let RaytracerObjects::Absorbers(absorber) = obj {
+                Some(absorber.clone())
+            } else {
+                None
+            }
+        }));
+    }
+    let absorbers = &cache.1;
+
+    let mut buried = Vec::new();
+    for (index, obj) in collection.iter_mut().enumerate() {
+        // disabled emitters have no light to block
+        if let RaytracerObjects::Emitters(emitter) = obj
+            && emitter.is_enabled()
+        {
+            let base_emitter = emitter.get_base_emitter_mut();
+            base_emitter.reflections.clear();
+            base_emitter.blocked_rays = 0;
+
+            let center = &base_emitter.base_object;
+            if absorber_at_source(absorbers, center.pos_x, center.pos_y).is_some() {
+                for ray in base_emitter.rays.iter_mut() {
+                    (ray.end_x, ray.end_y) = (ray.start_x, ray.start_y);
                 }
-            })
-            .collect()
-    };
+                base_emitter.blocked_rays = base_emitter.rays.len();
+                buried.push(index);
+                continue;
+            }
 
-    {
-        let mut collection = OBJ_COLLECTION.write().unwrap();
-        for index in 0..collection.len() {
-            if let Some(obj) = collection.get_mut(index) {
-                if let RaytracerObjects::Emitters(emitter) = obj {
-                    // Get mutable reference to the rays depending on the type of emitter
-                    let rays = match emitter {
-                        Emitters::EmitterIsotropic(o) => &mut o.rays,
-                        Emitters::EmitterCollimated(o) => &mut o.base_emitter.rays,
-                        Emitters::EmitterSpotlight(o) => &mut o.base_emitter.rays,
-                    };
-
-                    // Check each ray against each absorber for occlusion
-                    for ray in rays.iter_mut() {
-                        for absorber in &absorbers {
-                            if let Some(hit_point) = occlusion(absorber, ray) {
-                                let current_length = ((ray.end_x - ray.start_x).powi(2)
-                                    + (ray.end_y - ray.start_y).powi(2))
-                                .sqrt();
-                                let new_length = ((hit_point.0 - ray.start_x).powi(2)
-                                    + (hit_point.1 - ray.start_y).powi(2))
-                                .sqrt();
-
-                                // If the new length is shorter, update the ray's end point
-                                if new_length < current_length {
-                                    ray.end_x = hit_point.0;
-                                    ray.end_y = hit_point.1;
-                                }
+            // Check each ray against each absorber for occlusion, the
+            // reflections are rebuilt in place
+            for ray in base_emitter.rays.iter_mut() {
+                let (reflections, absorbed) = trace_ray(absorbers, ray);
+                base_emitter.reflections.extend(reflections);
+                base_emitter.blocked_rays += usize::from(absorbed);
+            }
+        }
+    }
+
+    buried
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::objects::absorber::{AbsorberArc, AbsorberPerfect};
+    use crate::objects::circle::ObjectCircle;
+    use macroquad::color::WHITE;
+
+    /// An absorber of radius 50 centered at (100, 0)
+    fn absorber() -> Absorbers {
+        Absorbers::AbsorberPerfect(AbsorberPerfect::new(ObjectCircle::new(
+            100.0, 0.0, WHITE, 50.0,
+        )))
+    }
+
+    /// An arc of radius 50 centered at (100, 0), covering its left half
+    fn arc() -> Absorbers {
+        Absorbers::AbsorberArc(AbsorberArc::new(
+            ObjectCircle::new(100.0, 0.0, WHITE, 50.0),
+            std::f32::consts::FRAC_PI_2,
+            3.0 * std::f32::consts::FRAC_PI_2,
+        ))
+    }
+
+    fn ray(start_x: f32, start_y: f32, end_x: f32, end_y: f32) -> ObjectRay {
+        ObjectRay::new(start_x, start_y, end_x, end_y, 1.0, WHITE)
+    }
+
+    fn assert_hit(hit: Option<(f32, (f32, f32))>, expected: (f32, f32)) {
+        let (_, (x, y)) = hit.expect("expected the ray to hit the absorber");
+        assert!((x - expected.0).abs() < 1e-3, "x: {} != {}", x, expected.0);
+        assert!((y - expected.1).abs() < 1e-3, "y: {} != {}", y, expected.1);
+    }
+
+    #[test]
+    fn ray_through_center_hits_nearest_root() {
+        // roots at t = 0.25 and t = 0.75, the nearest one is chosen
+        assert_hit(
+            occlusion(&absorber(), &ray(0.0, 0.0, 200.0, 0.0)),
+            (50.0, 0.0),
+        );
+    }
+
+    #[test]
+    fn hit_returns_parametric_distance() {
+        let (t, _) = occlusion(&absorber(), &ray(0.0, 0.0, 200.0, 0.0)).unwrap();
+        assert!((t - 0.25).abs() < 1e-6, "t: {} != 0.25", t);
+    }
+
+    #[test]
+    fn tangent_ray_hits_once() {
+        // the discriminant is zero, so both roots are t = 0.5
+        assert_hit(
+            occlusion(&absorber(), &ray(0.0, 50.0, 200.0, 50.0)),
+            (100.0, 50.0),
+        );
+    }
+
+    #[test]
+    fn ray_that_misses_returns_none() {
+        assert_eq!(occlusion(&absorber(), &ray(0.0, 100.0, 200.0, 100.0)), None);
+    }
+
+    #[test]
+    fn ray_that_stops_short_returns_none() {
+        // both roots are past t = 1
+        assert_eq!(occlusion(&absorber(), &ray(0.0, 0.0, 25.0, 0.0)), None);
+    }
+
+    #[test]
+    fn ray_starting_inside_hits_exit_point() {
+        // roots at t = -0.25 and t = 0.25, only the exit point is in (0, 1]
+        assert_hit(
+            occlusion(&absorber(), &ray(100.0, 0.0, 300.0, 0.0)),
+            (150.0, 0.0),
+        );
+    }
+
+    #[test]
+    fn emitter_on_absorber_edge_is_blocked() {
+        // rays from (50, 0), exactly on the edge, heading into the absorber
+        for (end_x, end_y) in [(250.0, 0.0), (200.0, 100.0), (200.0, -100.0)] {
+            let (t, _) = occlusion(&absorber(), &ray(50.0, 0.0, end_x, end_y))
+                .expect("expected the ray to be blocked");
+            assert_eq!(t, 0.0);
+        }
+
+        // a point on the edge that is not exactly representable
+        let (x, y) = (100.0 + 50.0 * 2.0_f32.cos(), 50.0 * 2.0_f32.sin());
+        let (t, _) =
+            occlusion(&absorber(), &ray(x, y, 100.0, 0.0)).expect("expected the ray to be blocked");
+        assert_eq!(t, 0.0);
+
+        // rays heading away from the absorber are not blocked
+        assert_eq!(occlusion(&absorber(), &ray(50.0, 0.0, -150.0, 0.0)), None);
+        assert_eq!(occlusion(&absorber(), &ray(50.0, 0.0, 0.0, 100.0)), None);
+    }
+
+    #[test]
+    fn zero_length_ray_returns_none() {
+        // a == 0, inside and outside the absorber
+        assert_eq!(occlusion(&absorber(), &ray(100.0, 0.0, 100.0, 0.0)), None);
+        assert_eq!(occlusion(&absorber(), &ray(0.0, 0.0, 0.0, 0.0)), None);
+    }
+
+    #[test]
+    fn ray_hitting_arc_is_blocked() {
+        // enters the circle at (50, 0), which is on the left half
+        assert_hit(occlusion(&arc(), &ray(0.0, 0.0, 200.0, 0.0)), (50.0, 0.0));
+    }
+
+    #[test]
+    fn ray_passes_through_gap_in_arc() {
+        // enters at (150, 0) on the missing right half, exits at (50, 0) on the arc
+        assert_hit(occlusion(&arc(), &ray(200.0, 0.0, 0.0, 0.0)), (50.0, 0.0));
+        // only crosses the circle on the missing right half
+        assert_eq!(occlusion(&arc(), &ray(200.0, 0.0, 120.0, 0.0)), None);
+    }
+
+    #[test]
+    fn concave_mirror_focuses_parallel_rays() {
+        // a mirror of radius 100 centered at (0, 0), covering its right side,
+        // focuses rays parallel to its axis near (50, 0)
+        let mirror = Absorbers::MirrorArc(AbsorberArc::new(
+            ObjectCircle::new(0.0, 0.0, WHITE, 100.0),
+            -std::f32::consts::FRAC_PI_4,
+            std::f32::consts::FRAC_PI_4,
+        ));
+
+        for offset in [-10.0, 10.0] {
+            let mut incoming = ray(-200.0, offset, 400.0, offset);
+            let (reflections, absorbed) = trace_ray(std::slice::from_ref(&mirror), &mut incoming);
+            assert!(!absorbed);
+
+            // stopped at the mirror and sent back towards the focal point
+            assert!(incoming.end_x > 99.0);
+            assert_eq!(reflections.len(), 1);
+            let reflected = &reflections[0];
+            let t = (50.0 - reflected.start_x) / (reflected.end_x - reflected.start_x);
+            let y = reflected.start_y + t * (reflected.end_y - reflected.start_y);
+            assert!(y.abs() < 1.0, "crosses the axis at y = {}", y);
+        }
+    }
+
+    #[test]
+    fn traced_rays_report_whether_an_absorber_took_their_light() {
+        let mirror = Absorbers::MirrorArc(AbsorberArc::new(
+            ObjectCircle::new(300.0, 0.0, WHITE, 50.0),
+            std::f32::consts::FRAC_PI_2,
+            3.0 * std::f32::consts::FRAC_PI_2,
+        ));
+
+        // stopped by the absorber
+        assert!(trace_ray(&[absorber()], &mut ray(0.0, 0.0, 400.0, 0.0)).1);
+        // misses it
+        assert!(!trace_ray(&[absorber()], &mut ray(0.0, 100.0, 400.0, 100.0)).1);
+        // reflected back into the absorber by the mirror behind it
+        let (reflections, absorbed) = trace_ray(
+            std::slice::from_ref(&mirror),
+            &mut ray(200.0, 0.0, 400.0, 0.0),
+        );
+        assert_eq!((reflections.len(), absorbed), (1, false));
+        let (_, absorbed) = trace_ray(&[absorber(), mirror], &mut ray(200.0, 0.0, 400.0, 0.0));
+        assert!(absorbed);
+    }
+
+    #[test]
+    fn source_inside_perfect_absorber_is_found() {
+        let absorbers = [arc(), absorber()];
+
+        // inside both circles, but only the perfect absorber is solid
+        assert_eq!(absorber_at_source(&absorbers, 90.0, 10.0), Some(1));
+        // on the boundary, or outside
+        assert_eq!(absorber_at_source(&absorbers, 50.0, 0.0), None);
+        assert_eq!(absorber_at_source(&absorbers, 0.0, 0.0), None);
+        assert_eq!(absorber_at_source(&[arc()], 100.0, 0.0), None);
+    }
+
+    #[test]
+    fn arc_range_wraps_past_zero() {
+        let arc = AbsorberArc::new(ObjectCircle::new(0.0, 0.0, WHITE, 1.0), 5.5, 0.5);
+        assert!(arc.contains_angle(0.0));
+        assert!(arc.contains_angle(6.0));
+        assert!(!arc.contains_angle(std::f32::consts::PI));
+    }
+
+    #[test]
+    fn reach_filter_does_not_change_hits() {
+        // rays from a grid of starts (inside, on, and outside the circle) in
+        // every direction, short and long
+        let mut filtered = 0;
+        for occluder in [absorber(), arc()] {
+            for start_x in (-50..=250).step_by(25) {
+                for start_y in (-100..=100).step_by(25) {
+                    for step in 0..24 {
+                        let angle = step as f32 * std::f32::consts::TAU / 24.0;
+                        for length in [10.0, 60.0, 400.0] {
+                            let (x, y) = (start_x as f32, start_y as f32);
+                            let ray = ray(x, y, x + length * angle.cos(), y + length * angle.sin());
+
+                            for block_at_start in [true, false] {
+                                assert_eq!(
+                                    occlusion_from(&occluder, &ray, block_at_start),
+                                    solve_occlusion(&occluder, &ray, block_at_start),
+                                    "ray from {}, {} at {} rad, length {}",
+                                    x,
+                                    y,
+                                    angle,
+                                    length
+                                );
+                            }
+                            if !could_reach(&occluder, &ray) {
+                                filtered += 1;
                             }
                         }
                     }
                 }
             }
         }
+
+        // the filter is actually exercised
+        assert!(filtered > 0);
+    }
+
+    #[test]
+    fn ray_leaving_from_inside_is_not_filtered() {
+        // the center is behind the start, but the ray still crosses the circle
+        let leaving = ray(90.0, 0.0, 0.0, 0.0);
+        assert!(could_reach(&absorber(), &leaving));
+        assert_hit(occlusion(&absorber(), &leaving), (50.0, 0.0));
+
+        // the whole circle is behind the start
+        assert!(!could_reach(&absorber(), &ray(0.0, 0.0, -100.0, 0.0)));
     }
 }
@@ -2,7 +2,8 @@
 //!
 //! This module contains the definition of light rays used in the raytracer,
 //! including both the base `ObjectRay` struct and helper functions to create
-//! different ray patterns.
+//! different ray patterns. Rays may carry a wavelength, which gives them the
+//! color of that wavelength of light (see `wavelength_to_color()`).
 //!
 //! author:         Zhean Ganituen (zrygan)
 //! last updated:   April 17, 2025
@@ -10,12 +11,21 @@
 use std::f32::consts::PI;
 
 use super::behavior::Drawable;
-use crate::globals::{OBJD_RAY_COLOR, OBJD_RAY_WIDTH};
-use crate::helpers::object_utils::linspace;
+use crate::globals::{
+    FLOWING_RAYS, MACROQUAD_SMOOTH_RAYS, OBJD_CONVERGENT_SPREAD, OBJD_GOLDEN_ANGLE_RAYS,
+    OBJD_RAY_ENDPOINT_COLOR, OBJD_RAY_ENDPOINT_RADIUS, OBJD_RAY_FEATHER, OBJD_RAY_FLOW_DASH,
+    OBJD_RAY_FLOW_GAP, OBJD_RAY_WIDTH, OBJD_VISIBLE_WAVELENGTHS, RAY_FLOW_SPEED, RAY_MAX_LENGTH,
+    SHOW_RAY_ENDPOINTS, THEME,
+};
+use crate::helpers::object_utils::spread_points;
 
 use macroquad::{
     color::Color,
-    shapes::draw_line,
+    material::{Material, MaterialParams, load_material},
+    miniquad::{BlendFactor, BlendState, BlendValue, Equation, PipelineParams, ShaderSource},
+    models::{Mesh, Vertex, draw_mesh},
+    shapes::{draw_circle, draw_line},
+    time::get_time,
     window::{screen_height, screen_width},
 };
 
@@ -37,6 +47,9 @@ pub struct ObjectRay {
     pub thickness: f32,
     /// Color of the ray when drawn
     pub color: Color,
+    /// Wavelength of the ray's light in nanometers, `None` for rays that
+    /// only have an explicit `color`
+    pub wavelength_nm: Option<f32>,
 }
 
 impl ObjectRay {
@@ -56,7 +69,8 @@ impl ObjectRay {
     ///
     /// # Returns
     ///
-    /// A new `ObjectRay` instance with the specified parameters
+    /// A new `ObjectRay` instance with the specified parameters and no
+    /// wavelength
     pub fn new(
         start_x: f32,
         start_y: f32,
@@ -72,53 +86,513 @@ impl ObjectRay {
             end_y,
             thickness,
             color,
+            wavelength_nm: None,
         }
     }
-}
 
-impl Drawable for ObjectRay {
-    fn draw_object(&self) {
-        draw_line(
+    /// Sets the wavelength of the ray's light
+    ///
+    /// A ray with a wavelength is drawn in the color of that wavelength (see
+    /// `wavelength_to_color()`), keeping the alpha of its current color.
+    /// Clearing the wavelength leaves the color as it is, so the caller
+    /// restores the explicit color if needed.
+    ///
+    /// # Arguments
+    ///
+    /// * `wavelength_nm` - The wavelength in nanometers, or `None` to clear it
+    pub fn set_wavelength(&mut self, wavelength_nm: Option<f32>) {
+        self.wavelength_nm = wavelength_nm;
+
+        if let Some(wavelength_nm) = wavelength_nm {
+            self.color = Color {
+                a: self.color.a,
+                ..wavelength_to_color(wavelength_nm)
+            };
+        }
+    }
+
+    /// Shortens the ray so that it is no longer than `max_length`
+    ///
+    /// The start of the ray stays in place. Rays that are already shorter are
+    /// left unchanged.
+    pub fn cap_length(&mut self, max_length: f32) {
+        (self.end_x, self.end_y) = cap_to_length(
             self.start_x,
             self.start_y,
             self.end_x,
             self.end_y,
-            self.thickness,
-            self.color,
+            max_length,
         );
     }
 }
 
+impl Drawable for ObjectRay {
+    /// Draws the ray as a line
+    ///
+    /// If `FLOWING_RAYS` is set, the ray is drawn as moving dashes by
+    /// `draw_flowing_ray()`. Otherwise, if `MACROQUAD_SMOOTH_RAYS` is set, the
+    /// ray is drawn with soft edges by `draw_smooth_ray()` instead of
+    /// `draw_line()`. If `SHOW_RAY_ENDPOINTS` is set, a dot marks where the
+    /// ray ends.
+    fn draw_object(&self) {
+        if *FLOWING_RAYS.read().unwrap() {
+            draw_flowing_ray(self);
+        } else if MACROQUAD_SMOOTH_RAYS {
+            draw_smooth_ray(self);
+        } else {
+            draw_line(
+                self.start_x,
+                self.start_y,
+                self.end_x,
+                self.end_y,
+                self.thickness,
+                self.color,
+            );
+        }
+
+        if *SHOW_RAY_ENDPOINTS.read().unwrap() {
+            draw_ray_endpoint(self);
+        }
+    }
+}
+
+/// Draws a dot where a ray ends, for the `SHOW_RAY_ENDPOINTS` debug view
+fn draw_ray_endpoint(ray: &ObjectRay) {
+    draw_circle(
+        ray.end_x,
+        ray.end_y,
+        OBJD_RAY_ENDPOINT_RADIUS,
+        OBJD_RAY_ENDPOINT_COLOR,
+    );
+}
+
+/// Draws a ray as dashes that move from its start to its end
+///
+/// The dashes are `OBJD_RAY_FLOW_DASH` long with `OBJD_RAY_FLOW_GAP` between
+/// them, and move along the ray at `RAY_FLOW_SPEED`.
+fn draw_flowing_ray(ray: &ObjectRay) {
+    let (dx, dy) = (ray.end_x - ray.start_x, ray.end_y - ray.start_y);
+    let length = (dx.powi(2) + dy.powi(2)).sqrt();
+    if length == 0.0 {
+        return;
+    }
+
+    let phase = (get_time() * *RAY_FLOW_SPEED.read().unwrap() as f64)
+        % (OBJD_RAY_FLOW_DASH + OBJD_RAY_FLOW_GAP) as f64;
+    for (from, to) in flow_dashes(length, phase as f32) {
+        draw_line(
+            ray.start_x + dx * from / length,
+            ray.start_y + dy * from / length,
+            ray.start_x + dx * to / length,
+            ray.start_y + dy * to / length,
+            ray.thickness,
+            ray.color,
+        );
+    }
+}
+
+/// Gets the dashes along a flowing ray
+///
+/// The dashes repeat every `OBJD_RAY_FLOW_DASH + OBJD_RAY_FLOW_GAP`, shifted
+/// towards the end of the ray by `phase`. Dashes cut by either end of the ray
+/// are shortened to fit.
+///
+/// # Arguments
+///
+/// * `length` - The length of the ray
+/// * `phase` - How far the dashes have moved along the ray
+///
+/// # Returns
+///
+/// The start and end of each dash, as distances from the start of the ray
+fn flow_dashes(length: f32, phase: f32) -> Vec<(f32, f32)> {
+    let period = OBJD_RAY_FLOW_DASH + OBJD_RAY_FLOW_GAP;
+
+    // start one period early so a dash entering at the start is drawn
+    let mut dash_start = phase.rem_euclid(period) - period;
+    let mut dashes = Vec::new();
+    while dash_start < length {
+        let (from, to) = (
+            dash_start.max(0.0),
+            (dash_start + OBJD_RAY_FLOW_DASH).min(length),
+        );
+        if to > from {
+            dashes.push((from, to));
+        }
+        dash_start += period;
+    }
+
+    dashes
+}
+
+/// Gets the approximate color of light of a single wavelength
+///
+/// This is a piecewise linear fit of the visible spectrum (violet, blue, cyan,
+/// green, yellow, red), dimmed towards both ends of `OBJD_VISIBLE_WAVELENGTHS`
+/// where the eye is less sensitive, with a gamma of 0.8. Wavelengths outside
+/// the visible range are black.
+///
+/// # Arguments
+///
+/// * `wavelength_nm` - The wavelength in nanometers
+///
+/// # Returns
+///
+/// The opaque color of the wavelength
+pub fn wavelength_to_color(wavelength_nm: f32) -> Color {
+    let (min, max) = OBJD_VISIBLE_WAVELENGTHS;
+    let w = wavelength_nm;
+
+    let (r, g, b) = match w {
+        w if !(min..=max).contains(&w) => (0.0, 0.0, 0.0),
+        w if w < 440.0 => ((440.0 - w) / (440.0 - min), 0.0, 1.0),
+        w if w < 490.0 => (0.0, (w - 440.0) / 50.0, 1.0),
+        w if w < 510.0 => (0.0, 1.0, (510.0 - w) / 20.0),
+        w if w < 580.0 => ((w - 510.0) / 70.0, 1.0, 0.0),
+        w if w < 645.0 => (1.0, (645.0 - w) / 65.0, 0.0),
+        _ => (1.0, 0.0, 0.0),
+    };
+
+    // the eye's sensitivity falls off near the ends of the visible range
+    let intensity = if w < 420.0 {
+        0.3 + 0.7 * (w - min) / (420.0 - min)
+    } else if w > 700.0 {
+        0.3 + 0.7 * (max - w) / (max - 700.0)
+    } else {
+        1.0
+    };
+    let adjust = |channel: f32| (channel * intensity).powf(0.8);
+
+    Color::new(adjust(r), adjust(g), adjust(b), 1.0)
+}
+
+/// Vertex shader of the additive ray material, the same as macroquad's default
+const ADDITIVE_VERTEX_SHADER: &str = r#"#version 100
+attribute vec3 position;
+attribute vec2 texcoord;
+attribute vec4 color0;
+
+varying lowp vec2 uv;
+varying lowp vec4 color;
+
+uniform mat4 Model;
+uniform mat4 Projection;
+
+void main() {
+    gl_Position = Projection * Model * vec4(position, 1);
+    color = color0 / 255.0;
+    uv = texcoord;
+}
+"#;
+
+/// Fragment shader of the additive ray material, the same as macroquad's default
+const ADDITIVE_FRAGMENT_SHADER: &str = r#"#version 100
+varying lowp vec4 color;
+varying lowp vec2 uv;
+
+uniform sampler2D Texture;
+
+void main() {
+    gl_FragColor = color * texture2D(Texture, uv);
+}
+"#;
+
+/// Creates the material used to draw rays with additive blending
+///
+/// The shaders are macroquad's defaults, only the blend state differs: each
+/// ray's color (scaled by its alpha) is added to what is already on screen
+/// instead of being mixed with it, so overlapping rays get brighter.
+///
+/// Must be called after the window is created.
+///
+/// # Returns
+///
+/// The material, or the error from macroquad if the shaders fail to compile
+pub fn additive_ray_material() -> Result<Material, macroquad::Error> {
+    load_material(
+        ShaderSource::Glsl {
+            vertex: ADDITIVE_VERTEX_SHADER,
+            fragment: ADDITIVE_FRAGMENT_SHADER,
+        },
+        MaterialParams {
+            pipeline_params: PipelineParams {
+                color_blend: Some(BlendState::new(
+                    Equation::Add,
+                    BlendFactor::Value(BlendValue::SourceAlpha),
+                    BlendFactor::One,
+                )),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    )
+}
+
+/// Draws many rays in a few draw calls.
+///
+/// Instead of one `draw_line()` call per ray, every ray is added as a quad to
+/// a shared mesh which is drawn with a single `draw_mesh()` call. macroquad
+/// drops the part of a mesh that doesn't fit in one draw call, so the rays
+/// are split into chunks of at most `RAYS_PER_MESH` rays, one mesh per
+/// chunk. Whether this is faster than drawing the rays one at a time hasn't
+/// been measured, which is why `MACROQUAD_BATCH_RAYS` is off by default.
+///
+/// The rays are drawn with hard edges, `MACROQUAD_SMOOTH_RAYS` only applies
+/// to rays drawn one at a time. The `SHOW_RAY_ENDPOINTS` dots are drawn one
+/// at a time after the meshes. While `FLOWING_RAYS` is set, every ray is
+/// drawn one at a time instead, dash by dash.
+///
+/// # Arguments
+///
+/// * `rays` - The rays to draw
+pub fn draw_rays_batched(rays: &[ObjectRay]) {
+    if *FLOWING_RAYS.read().unwrap() {
+        rays.iter().for_each(ObjectRay::draw_object);
+        return;
+    }
+
+    // each ray takes 6 indices, and a draw call holds at most 5000 of them
+    // (macroquad's default `draw_call_index_capacity`)
+    const RAYS_PER_MESH: usize = 5000 / 6;
+
+    for chunk in rays.chunks(RAYS_PER_MESH) {
+        let mut vertices = Vec::with_capacity(chunk.len() * 4);
+        let mut indices = Vec::with_capacity(chunk.len() * 6);
+
+        for ray in chunk {
+            let (dx, dy) = (ray.end_x - ray.start_x, ray.end_y - ray.start_y);
+            let length = (dx.powi(2) + dy.powi(2)).sqrt();
+            if length == 0.0 {
+                continue;
+            }
+
+            // half the thickness, perpendicular to the ray
+            let (offset_x, offset_y) = (
+                -dy / length * ray.thickness / 2.0,
+                dx / length * ray.thickness / 2.0,
+            );
+
+            let first = vertices.len() as u16;
+            for (x, y) in [(ray.start_x, ray.start_y), (ray.end_x, ray.end_y)] {
+                for side in [-1.0, 1.0] {
+                    vertices.push(Vertex::new(
+                        x + side * offset_x,
+                        y + side * offset_y,
+                        0.0,
+                        0.0,
+                        0.0,
+                        ray.color,
+                    ));
+                }
+            }
+            indices.extend([0, 1, 2, 1, 3, 2].map(|i| first + i));
+        }
+
+        draw_mesh(&Mesh {
+            vertices,
+            indices,
+            texture: None,
+        });
+    }
+
+    if *SHOW_RAY_ENDPOINTS.read().unwrap() {
+        rays.iter().for_each(draw_ray_endpoint);
+    }
+}
+
+/// Draws a ray as a quad whose alpha fades out towards its edges.
+///
+/// The quad has a solid core `thickness` pixels wide, with a band of
+/// `OBJD_RAY_FEATHER` pixels on each side whose alpha falls off to 0
+/// perpendicular to the ray's direction. This is more expensive than
+/// `draw_line()` but avoids jagged edges, especially on thin rays.
+fn draw_smooth_ray(ray: &ObjectRay) {
+    let (dx, dy) = (ray.end_x - ray.start_x, ray.end_y - ray.start_y);
+    let length = (dx.powi(2) + dy.powi(2)).sqrt();
+    if length == 0.0 {
+        return;
+    }
+
+    // unit vector perpendicular to the ray
+    let (perp_x, perp_y) = (-dy / length, dx / length);
+    let core = ray.thickness / 2.0;
+    let edge = core + OBJD_RAY_FEATHER;
+    let transparent = Color {
+        a: 0.0,
+        ..ray.color
+    };
+
+    // four vertices across the ray (edge, core, core, edge) at each end
+    let mut vertices = Vec::with_capacity(8);
+    for (x, y) in [(ray.start_x, ray.start_y), (ray.end_x, ray.end_y)] {
+        for (offset, color) in [
+            (-edge, transparent),
+            (-core, ray.color),
+            (core, ray.color),
+            (edge, transparent),
+        ] {
+            vertices.push(Vertex::new(
+                x + perp_x * offset,
+                y + perp_y * offset,
+                0.0,
+                0.0,
+                0.0,
+                color,
+            ));
+        }
+    }
+
+    // two triangles for each of the three bands
+    let indices = (0..3u16)
+        .flat_map(|band| [band, band + 1, band + 4, band + 1, band + 5, band + 4])
+        .collect();
+
+    draw_mesh(&Mesh {
+        vertices,
+        indices,
+        texture: None,
+    });
+}
+
+/// Shortens a ray's end point so the ray is no longer than `RAY_MAX_LENGTH`.
+///
+/// If `RAY_MAX_LENGTH` is 0, or the ray is already shorter than it, the end
+/// point is returned unchanged.
+///
+/// # Returns
+///
+/// The (possibly shortened) end point of the ray as `(end_x, end_y)`
+fn cap_ray_length(start_x: f32, start_y: f32, end_x: f32, end_y: f32) -> (f32, f32) {
+    cap_to_length(
+        start_x,
+        start_y,
+        end_x,
+        end_y,
+        *RAY_MAX_LENGTH.read().unwrap(),
+    )
+}
+
+/// Shortens a segment's end point so the segment is no longer than `max_length`.
+///
+/// If `max_length` is 0 (or less), or the segment is already shorter than it,
+/// the end point is returned unchanged.
+fn cap_to_length(
+    start_x: f32,
+    start_y: f32,
+    end_x: f32,
+    end_y: f32,
+    max_length: f32,
+) -> (f32, f32) {
+    let (dx, dy) = (end_x - start_x, end_y - start_y);
+    let length = (dx.powi(2) + dy.powi(2)).sqrt();
+
+    if max_length <= 0.0 || length <= max_length {
+        (end_x, end_y)
+    } else {
+        let scale = max_length / length;
+        (start_x + dx * scale, start_y + dy * scale)
+    }
+}
+
+/// Creates one ray from a point towards each target.
+///
+/// Each ray passes through its target and continues until it is long enough
+/// to cross the screen past it (or until `RAY_MAX_LENGTH`).
+///
+/// # Arguments
+///
+/// * `rays` - The rays to replace, their allocation is reused
+/// * `start_x` - X coordinate of the emitter's center point
+/// * `start_y` - Y coordinate of the emitter's center point
+/// * `targets` - The points (x, y) the rays are aimed at
+///
+/// Afterwards, `rays` holds the new `ObjectRay`s in the same order as `targets`
+pub fn fill_targeted_rays(
+    rays: &mut Vec<ObjectRay>,
+    start_x: f32,
+    start_y: f32,
+    targets: &[(f32, f32)],
+) {
+    let ray_color = THEME.read().unwrap().ray_color;
+    let diagonal = (screen_width().powi(2) + screen_height().powi(2)).sqrt();
+
+    rays.clear();
+    rays.extend(targets.iter().map(|&(target_x, target_y)| {
+        let angle = (target_y - start_y).atan2(target_x - start_x);
+        let length =
+            ((target_x - start_x).powi(2) + (target_y - start_y).powi(2)).sqrt() + diagonal;
+        let (end_x, end_y) = cap_ray_length(
+            start_x,
+            start_y,
+            start_x + length * angle.cos(),
+            start_y + length * angle.sin(),
+        );
+
+        ObjectRay::new(start_x, start_y, end_x, end_y, OBJD_RAY_WIDTH, ray_color)
+    }));
+}
+
+/// Creates the rays of `fill_targeted_rays()` in a new vector
+pub fn init_targeted_rays(start_x: f32, start_y: f32, targets: &[(f32, f32)]) -> Vec<ObjectRay> {
+    let mut rays = Vec::with_capacity(targets.len());
+    fill_targeted_rays(&mut rays, start_x, start_y, targets);
+    rays
+}
+
 /// Creates a collection of rays arranged in an isotropic (point source) pattern.
 ///
 /// This function generates rays that emanate from a central point in all directions,
 /// similar to a point light source. The rays are evenly distributed around 360 degrees.
 ///
+/// If `OBJD_GOLDEN_ANGLE_RAYS` is set, each ray is instead turned by the golden
+/// angle (about 137.5 degrees) from the previous one. The rays still cover the
+/// circle uniformly, but without the regular spacing that can alias against
+/// regular geometry.
+///
 /// # Arguments
 ///
+/// * `rays` - The rays to replace, their allocation is reused
 /// * `start_x` - X coordinate of the emitter's center point
 /// * `start_y` - Y coordinate of the emitter's center point
 ///
-/// # Returns
-///
-/// A vector of `ObjectRay`s arranged in a circular pattern from the given point
-pub fn init_isotropic_rays(start_x: f32, start_y: f32, ray_count: i32) -> Vec<ObjectRay> {
-    let mut rays: Vec<ObjectRay> = Vec::with_capacity(ray_count as usize);
+/// Afterwards, `rays` holds the new `ObjectRay`s arranged in a circular pattern
+/// from the given point
+pub fn fill_isotropic_rays(rays: &mut Vec<ObjectRay>, start_x: f32, start_y: f32, ray_count: i32) {
+    rays.clear();
+    let ray_color = THEME.read().unwrap().ray_color;
+
+    // PI * (3 - sqrt(5)), the full circle divided by the golden ratio squared
+    let golden_angle = PI * (3.0 - 5.0_f32.sqrt());
 
     for index in 0..ray_count {
         // Calculate angle for each ray to distribute them evenly in a circle
-        let angle = (index as f32 / ray_count as f32) * 2.0 * PI;
-
-        rays.push(ObjectRay::new(
+        let angle = if OBJD_GOLDEN_ANGLE_RAYS {
+            (index as f32 * golden_angle) % (2.0 * PI)
+        } else {
+            (index as f32 / ray_count as f32) * 2.0 * PI
+        };
+        let (end_x, end_y) = cap_ray_length(
             start_x,
             start_y,
             start_x + angle.cos() * screen_width(),
             start_y + angle.sin() * screen_height(),
+        );
+
+        rays.push(ObjectRay::new(
+            start_x,
+            start_y,
+            end_x,
+            end_y,
             OBJD_RAY_WIDTH,
-            OBJD_RAY_COLOR,
+            ray_color,
         ));
     }
+}
 
+/// Creates the rays of `fill_isotropic_rays()` in a new vector
+pub fn init_isotropic_rays(start_x: f32, start_y: f32, ray_count: i32) -> Vec<ObjectRay> {
+    let mut rays = Vec::with_capacity(ray_count as usize);
+    fill_isotropic_rays(&mut rays, start_x, start_y, ray_count);
     rays
 }
 
@@ -129,22 +603,24 @@ pub fn init_isotropic_rays(start_x: f32, start_y: f32, ray_count: i32) -> Vec<Ob
 ///
 /// # Arguments
 ///
+/// * `rays` - The rays to replace, their allocation is reused
 /// * `start_x` - X coordinate of the emitter's center point
 /// * `start_y` - Y coordinate of the emitter's center point
 /// * `orientation` - The angle (in radians) at which the rays should point
 /// * `collimated_beam_diameter` - Width of the beam (perpendicular to ray direction)
 ///
-/// # Returns
-///
-/// A vector of `ObjectRay`s arranged in a parallel pattern from the given point
-pub fn init_collimated_rays(
+/// Afterwards, `rays` holds the new `ObjectRay`s arranged in a parallel pattern
+/// from the given point
+pub fn fill_collimated_rays(
+    rays: &mut Vec<ObjectRay>,
     start_x: f32,
     start_y: f32,
     orientation: f32,
     collimated_beam_diameter: f32,
     ray_count: i32,
-) -> Vec<ObjectRay> {
-    let mut rays: Vec<ObjectRay> = Vec::with_capacity(ray_count as usize);
+) {
+    rays.clear();
+    let ray_color = THEME.read().unwrap().ray_color;
 
     // Calculate the direction vector components using the orientation angle
     let cos_x = orientation.cos();
@@ -164,18 +640,43 @@ pub fn init_collimated_rays(
         let offset_x = offset * perp.0;
         let offset_y = offset * perp.1;
 
-        rays.push(ObjectRay::new(
-            // Apply offset to create parallel rays
+        // Extend ray to screen edge in the direction of orientation
+        let (end_x, end_y) = cap_ray_length(
             start_x + offset_x,
             start_y + offset_y,
-            // Extend ray to screen edge in the direction of orientation
             start_x + offset_x + cos_x * screen_width(),
             start_y + offset_y + sin_y * screen_height(),
+        );
+
+        rays.push(ObjectRay::new(
+            // Apply offset to create parallel rays
+            start_x + offset_x,
+            start_y + offset_y,
+            end_x,
+            end_y,
             OBJD_RAY_WIDTH,
-            OBJD_RAY_COLOR,
+            ray_color,
         ));
     }
+}
 
+/// Creates the rays of `fill_collimated_rays()` in a new vector
+pub fn init_collimated_rays(
+    start_x: f32,
+    start_y: f32,
+    orientation: f32,
+    collimated_beam_diameter: f32,
+    ray_count: i32,
+) -> Vec<ObjectRay> {
+    let mut rays = Vec::with_capacity(ray_count as usize);
+    fill_collimated_rays(
+        &mut rays,
+        start_x,
+        start_y,
+        orientation,
+        collimated_beam_diameter,
+        ray_count,
+    );
     rays
 }
 
@@ -187,53 +688,234 @@ pub fn init_collimated_rays(
 ///
 /// # Arguments
 ///
+/// * `rays` - The rays to replace, their allocation is reused
 /// * `start_x` - X coordinate of the emitter's center point
 /// * `start_y` - Y coordinate of the emitter's center point
 /// * `orientation` - The central angle (in radians) at which the spotlight is pointing
 /// * `spotlight_beam_angle` - The total angular spread of the spotlight cone (in radians)
 ///
-/// # Returns
-///
-/// A vector of `ObjectRay`s arranged in a cone pattern from the given point
+/// Afterwards, `rays` holds the new `ObjectRay`s arranged in a cone pattern
+/// from the given point
 ///
-/// # Panics
-///
-/// Panics if `OBJC_MAX_RAY_COUNT` is less than 2, as at least two rays are needed to
-/// define a spotlight beam (one at each edge of the cone).
-pub fn init_spotlight_rays(
+/// A spotlight needs at least two rays to reach both edges of the cone, with a
+/// single ray it points straight along `orientation` and with none it is empty.
+pub fn fill_spotlight_rays(
+    rays: &mut Vec<ObjectRay>,
     start_x: f32,
     start_y: f32,
     orientation: f32,
     spotlight_beam_angle: f32,
     ray_count: i32,
-) -> Vec<ObjectRay> {
-    let mut rays: Vec<ObjectRay> = Vec::with_capacity(ray_count as usize);
+) {
+    rays.clear();
+    let ray_color = THEME.read().unwrap().ray_color;
 
     // Calculate the half-angle to evenly distribute rays on both sides of central orientation
     let half_angle = spotlight_beam_angle / 2.0;
 
     // Generate a linear range of angles from (orientation - half_angle) to (orientation + half_angle)
     // These will represent the directions of each ray in the spotlight cone
-    let angles = linspace(
+    let angles = spread_points(
         orientation - half_angle,
         orientation + half_angle,
         ray_count,
-    )
-    .expect("Number of rays for spotlight must be at least 2.");
+    );
 
     // Create a ray for each angle in the spotlight cone
     for angle in angles {
-        rays.push(ObjectRay::new(
+        // Extend ray to screen edge in the direction of the angle
+        // Note: Cosine gives x-component, and negative sine gives y-component (due to y-axis orientation)
+        let (end_x, end_y) = cap_ray_length(
             start_x,
             start_y,
-            // Extend ray to screen edge in the direction of the angle
-            // Note: Cosine gives x-component, and negative sine gives y-component (due to y-axis orientation)
             start_x + screen_width() * angle.cos(),
-            start_y + screen_height() * (-1.0 * angle.sin()),
+            start_y + screen_height() * (-angle.sin()),
+        );
+
+        rays.push(ObjectRay::new(
+            start_x,
+            start_y,
+            end_x,
+            end_y,
             OBJD_RAY_WIDTH,
-            OBJD_RAY_COLOR,
+            ray_color,
         ));
     }
+}
 
+/// Creates the rays of `fill_spotlight_rays()` in a new vector
+pub fn init_spotlight_rays(
+    start_x: f32,
+    start_y: f32,
+    orientation: f32,
+    spotlight_beam_angle: f32,
+    ray_count: i32,
+) -> Vec<ObjectRay> {
+    let mut rays = Vec::with_capacity(ray_count as usize);
+    fill_spotlight_rays(
+        &mut rays,
+        start_x,
+        start_y,
+        orientation,
+        spotlight_beam_angle,
+        ray_count,
+    );
     rays
 }
+
+/// Creates a collection of rays that converge toward a target point.
+///
+/// The rays start evenly spaced across the emitter's diameter (perpendicular
+/// to the direction of the target) and are aimed at the target, so they cross
+/// there before continuing past it. Each ray is then tilted by up to half of
+/// `OBJD_CONVERGENT_SPREAD` in proportion to its distance from the center, which
+/// slightly blurs the focus instead of making it a perfect point.
+///
+/// # Arguments
+///
+/// * `rays` - The rays to replace, their allocation is reused
+/// * `start_x` - X coordinate of the emitter's center point
+/// * `start_y` - Y coordinate of the emitter's center point
+/// * `radius` - Radius of the emitter, the rays start across its diameter
+/// * `target` - The point (x, y) that the rays converge toward
+/// * `ray_count` - The number of rays to create
+///
+/// Afterwards, `rays` holds the new `ObjectRay`s converging toward the target
+/// point
+pub fn fill_convergent_rays(
+    rays: &mut Vec<ObjectRay>,
+    start_x: f32,
+    start_y: f32,
+    radius: f32,
+    target: (f32, f32),
+    ray_count: i32,
+) {
+    rays.clear();
+    let ray_color = THEME.read().unwrap().ray_color;
+
+    // Direction to the target, and the perpendicular used to spread the starts
+    let orientation = (target.1 - start_y).atan2(target.0 - start_x);
+    let perp = (-orientation.sin(), orientation.cos());
+
+    // Rays are long enough to reach the target and then cross the screen
+    let length = ((target.0 - start_x).powi(2) + (target.1 - start_y).powi(2)).sqrt()
+        + (screen_width().powi(2) + screen_height().powi(2)).sqrt();
+
+    let offsets = spread_points(-radius, radius, ray_count);
+
+    for offset in offsets {
+        let ray_start_x = start_x + offset * perp.0;
+        let ray_start_y = start_y + offset * perp.1;
+
+        // Aim at the target, tilted slightly by the ray's offset
+        let tilt = if radius > 0.0 {
+            OBJD_CONVERGENT_SPREAD / 2.0 * (offset / radius)
+        } else {
+            0.0
+        };
+        let angle = (target.1 - ray_start_y).atan2(target.0 - ray_start_x) + tilt;
+
+        let (end_x, end_y) = cap_ray_length(
+            ray_start_x,
+            ray_start_y,
+            ray_start_x + length * angle.cos(),
+            ray_start_y + length * angle.sin(),
+        );
+
+        rays.push(ObjectRay::new(
+            ray_start_x,
+            ray_start_y,
+            end_x,
+            end_y,
+            OBJD_RAY_WIDTH,
+            ray_color,
+        ));
+    }
+}
+
+/// Creates the rays of `fill_convergent_rays()` in a new vector
+pub fn init_convergent_rays(
+    start_x: f32,
+    start_y: f32,
+    radius: f32,
+    target: (f32, f32),
+    ray_count: i32,
+) -> Vec<ObjectRay> {
+    let mut rays = Vec::with_capacity(ray_count as usize);
+    fill_convergent_rays(&mut rays, start_x, start_y, radius, target, ray_count);
+    rays
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_color(color: Color, expected: (f32, f32, f32)) {
+        let actual = (color.r, color.g, color.b);
+        assert!(
+            (actual.0 - expected.0).abs() < 1e-3
+                && (actual.1 - expected.1).abs() < 1e-3
+                && (actual.2 - expected.2).abs() < 1e-3,
+            "{:?} != {:?}",
+            actual,
+            expected
+        );
+        assert_eq!(color.a, 1.0);
+    }
+
+    #[test]
+    fn wavelengths_map_to_spectrum_colors() {
+        assert_color(wavelength_to_color(440.0), (0.0, 0.0, 1.0));
+        assert_color(wavelength_to_color(500.0), (0.0, 1.0, 0.5_f32.powf(0.8)));
+        assert_color(wavelength_to_color(580.0), (1.0, 1.0, 0.0));
+        assert_color(wavelength_to_color(650.0), (1.0, 0.0, 0.0));
+
+        // violet has some red in it, but is dimmed near the end of the range
+        let violet = wavelength_to_color(400.0);
+        assert!(violet.r > 0.0 && violet.b > violet.r && violet.b < 1.0);
+    }
+
+    #[test]
+    fn invisible_wavelengths_are_black() {
+        assert_color(wavelength_to_color(300.0), (0.0, 0.0, 0.0));
+        assert_color(wavelength_to_color(900.0), (0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn wavelength_keeps_the_ray_alpha() {
+        let mut ray = ObjectRay::new(0.0, 0.0, 1.0, 0.0, 1.0, Color::new(1.0, 1.0, 1.0, 0.5));
+        ray.set_wavelength(Some(650.0));
+        assert_eq!(ray.wavelength_nm, Some(650.0));
+        assert_color(
+            Color {
+                a: 1.0,
+                ..ray.color
+            },
+            (1.0, 0.0, 0.0),
+        );
+        assert_eq!(ray.color.a, 0.5);
+
+        ray.set_wavelength(None);
+        assert_eq!(ray.wavelength_nm, None);
+    }
+
+    #[test]
+    fn flow_dashes_move_towards_the_end() {
+        assert_eq!(OBJD_RAY_FLOW_DASH + OBJD_RAY_FLOW_GAP, 20.0);
+
+        assert_eq!(
+            flow_dashes(50.0, 0.0),
+            vec![(0.0, 12.0), (20.0, 32.0), (40.0, 50.0)]
+        );
+        assert_eq!(
+            flow_dashes(50.0, 5.0),
+            vec![(5.0, 17.0), (25.0, 37.0), (45.0, 50.0)]
+        );
+        // a dash entering at the start is cut to fit
+        assert_eq!(
+            flow_dashes(50.0, 15.0),
+            vec![(0.0, 7.0), (15.0, 27.0), (35.0, 47.0)]
+        );
+        assert_eq!(flow_dashes(50.0, 25.0), flow_dashes(50.0, 5.0));
+    }
+}
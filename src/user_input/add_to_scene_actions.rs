@@ -1,29 +1,40 @@
 //! User input action handlers
 //!
 //! This module contains functions that respond to user interactions by creating
-//! and manipulating objects in the raytracer scene.
+//! and manipulating objects in the raytracer scene. Presets build common setups
+//! of one or more configured objects in a single step.
 //!
 //! author:         Zhean Ganituen (zrygan)
 //! last updated:   April 17, 2025
 
+use crate::errors::RaytracerError;
 use crate::globals::{
-    OBJD_CIRCLE_FILL, OBJD_CIRCLE_RADIUS, OBJD_COLLIMATED_BEAM_DIAMETER,
-    OBJD_COLLIMATED_ORIENTATION, OBJD_RAY_COUNT, OBJD_SPOTLIGHT_BEAM_ANGLE,
-    OBJD_SPOTLIGHT_ORIENTATION,
+    OBJ_COLLECTION, OBJC_CREATION_ANGLE_PER_PIXEL, OBJC_MAX_TOTAL_RAYS, OBJD_ABSORBER_FILL,
+    OBJD_ARC_END_ANGLE, OBJD_ARC_START_ANGLE, OBJD_CIRCLE_RADIUS, OBJD_COLLIMATED_BEAM_DIAMETER,
+    OBJD_COLLIMATED_ORIENTATION, OBJD_ECLIPSE_DISTANCE, OBJD_ECLIPSE_RADIUS, OBJD_MIRROR_COLOR,
+    OBJD_MIRROR_END_ANGLE, OBJD_MIRROR_RADIUS, OBJD_MIRROR_START_ANGLE, OBJD_PREVIEW_ALPHA,
+    OBJD_RAY_COUNT, OBJD_SPOTLIGHT_BEAM_ANGLE, OBJD_SPOTLIGHT_ORIENTATION, OBJD_SUN_COLOR,
+    OBJD_SUN_RADIUS, OBJD_SUN_RAY_COLOR, OBJD_SUN_RAY_COUNT, THEME,
 };
-use crate::helpers::object_utils::add_object_to_collection;
-use crate::objects::absorber::{AbsorberPerfect, Absorbers};
-use crate::objects::behavior::RaytracerObjects;
+use crate::helpers::object_utils::{add_object_to_collection, total_ray_count};
+use crate::objects::absorber::{AbsorberArc, AbsorberPerfect, Absorbers};
+use crate::objects::behavior::{RaytracerObjects, VariableSize};
 use crate::objects::circle::ObjectCircle;
-use crate::objects::emitters::{EmitterCollimated, EmitterIsotropic, EmitterSpotlight, Emitters};
-use crate::objects::ray::{init_collimated_rays, init_isotropic_rays, init_spotlight_rays};
+use crate::objects::emitters::{
+    EmitterCollimated, EmitterConvergent, EmitterIsotropic, EmitterSpotlight, EmitterTargeted,
+    Emitters,
+};
+use crate::objects::ray::{
+    init_collimated_rays, init_convergent_rays, init_isotropic_rays, init_spotlight_rays,
+    init_targeted_rays,
+};
 use macroquad::input::mouse_position;
+use std::f32::consts::TAU;
 
-/// Creates and adds a new object to the scene at the current mouse position.
+/// Creates a new object of the given type with default parameters.
 ///
 /// This function handles the creation of different types of objects based on the
-/// provided object type string. It uses the current mouse cursor position as the
-/// placement point for the new object.
+/// provided object type string. The object is not added to the scene.
 ///
 /// # Arguments
 ///
@@ -31,79 +42,325 @@ use macroquad::input::mouse_position;
 ///   - "circle_none": Creates a simple circle object
 ///   - "emitter_isotropic": Creates an isotropic emitter (rays in all directions)
 ///   - "emitter_collimated": Creates a collimated emitter (parallel rays)
+///   - "emitter_spotlight": Creates a spotlight emitter (cone of rays)
+///   - "emitter_convergent": Creates a convergent emitter (rays focused on a point)
+///   - "emitter_targeted": Creates a targeted emitter (one ray per target point)
+///   - "absorber_perfect": Creates a perfect absorber
+///   - "absorber_arc": Creates an arc absorber
+///   - "mirror_arc": Creates an arc mirror, `OBJD_MIRROR_RADIUS` in radius
+/// * `pos_x` - X-coordinate of the new object
+/// * `pos_y` - Y-coordinate of the new object
 ///
-/// # Example
+/// # Returns
 ///
-/// ```
-/// // Create a new isotropic emitter at the current mouse position
-/// add_object_to_scene("emitter_isotropic");
-/// ```
-pub fn add_object_to_scene(object_type: &str) {
-    // Get the current mouse cursor position
-    let (mouse_x, mouse_y) = mouse_position();
-
-    if let "circle_none" = object_type {
-        // Create a basic circle object at the mouse position
-        let new_object = ObjectCircle::new(mouse_x, mouse_y, OBJD_CIRCLE_FILL, OBJD_CIRCLE_RADIUS);
+/// * `Some(RaytracerObjects)` - The new object
+/// * `None` - If the object type is not recognized
+pub fn new_object_at(object_type: &str, pos_x: f32, pos_y: f32) -> Option<RaytracerObjects> {
+    let circle_fill = THEME.read().unwrap().circle_fill;
+    let mut base_object = ObjectCircle::new(pos_x, pos_y, circle_fill, OBJD_CIRCLE_RADIUS);
+    // absorbers are darker so they don't look like plain circles
+    if object_type.starts_with("absorber") {
+        base_object.set_color(OBJD_ABSORBER_FILL);
+    }
 
-        add_object_to_collection(RaytracerObjects::ObjectCircle(new_object));
-    } else if let "emitter_isotropic" = object_type {
+    match object_type {
+        // Create a basic circle object
+        "circle_none" => Some(RaytracerObjects::ObjectCircle(base_object)),
         // Create an isotropic emitter (radiating in all directions)
-        let new_object = EmitterIsotropic::new(
-            ObjectCircle::new(mouse_x, mouse_y, OBJD_CIRCLE_FILL, OBJD_CIRCLE_RADIUS),
-            init_isotropic_rays(mouse_x, mouse_y, OBJD_RAY_COUNT),
-        );
-
-        add_object_to_collection(RaytracerObjects::Emitters(Emitters::EmitterIsotropic(
-            new_object,
-        )));
-    } else if let "emitter_collimated" = object_type {
+        "emitter_isotropic" => Some(RaytracerObjects::Emitters(Emitters::EmitterIsotropic(
+            EmitterIsotropic::new(
+                base_object,
+                init_isotropic_rays(pos_x, pos_y, OBJD_RAY_COUNT),
+            ),
+        ))),
         // Create a collimated emitter (parallel rays, like a laser)
-        let new_object = EmitterCollimated::new(
-            ObjectCircle::new(mouse_x, mouse_y, OBJD_CIRCLE_FILL, OBJD_CIRCLE_RADIUS),
-            init_collimated_rays(
-                mouse_x,
-                mouse_y,
+        "emitter_collimated" => Some(RaytracerObjects::Emitters(Emitters::EmitterCollimated(
+            EmitterCollimated::new(
+                base_object,
+                init_collimated_rays(
+                    pos_x,
+                    pos_y,
+                    OBJD_COLLIMATED_ORIENTATION,
+                    OBJD_COLLIMATED_BEAM_DIAMETER,
+                    OBJD_RAY_COUNT,
+                ),
                 OBJD_COLLIMATED_ORIENTATION,
                 OBJD_COLLIMATED_BEAM_DIAMETER,
-                OBJD_RAY_COUNT,
             ),
-            OBJD_COLLIMATED_ORIENTATION,
-            OBJD_COLLIMATED_BEAM_DIAMETER,
-        );
-
-        add_object_to_collection(RaytracerObjects::Emitters(Emitters::EmitterCollimated(
-            new_object,
-        )));
-    } else if let "emitter_spotlight" = object_type {
+        ))),
         // Create a spotlight emitter (like a flashlight)
-        let new_object = EmitterSpotlight::new(
-            ObjectCircle::new(mouse_x, mouse_y, OBJD_CIRCLE_FILL, OBJD_CIRCLE_RADIUS),
-            init_spotlight_rays(
-                mouse_x,
-                mouse_y,
+        "emitter_spotlight" => Some(RaytracerObjects::Emitters(Emitters::EmitterSpotlight(
+            EmitterSpotlight::new(
+                base_object,
+                init_spotlight_rays(
+                    pos_x,
+                    pos_y,
+                    OBJD_SPOTLIGHT_ORIENTATION,
+                    OBJD_SPOTLIGHT_BEAM_ANGLE,
+                    OBJD_RAY_COUNT,
+                ),
                 OBJD_SPOTLIGHT_ORIENTATION,
                 OBJD_SPOTLIGHT_BEAM_ANGLE,
-                OBJD_RAY_COUNT,
             ),
-            OBJD_SPOTLIGHT_ORIENTATION,
-            OBJD_SPOTLIGHT_BEAM_ANGLE,
-        );
-
-        add_object_to_collection(RaytracerObjects::Emitters(Emitters::EmitterSpotlight(
-            new_object,
-        )));
-    } else if let "absorber_perfect" = object_type {
+        ))),
+        // Create a convergent emitter (like the light behind a lens)
+        "emitter_convergent" => {
+            let target = EmitterConvergent::default_target(pos_x, pos_y);
+            Some(RaytracerObjects::Emitters(Emitters::EmitterConvergent(
+                EmitterConvergent::new(
+                    base_object,
+                    init_convergent_rays(pos_x, pos_y, OBJD_CIRCLE_RADIUS, target, OBJD_RAY_COUNT),
+                    target,
+                ),
+            )))
+        }
+        // Create a targeted emitter (one ray through each target point)
+        "emitter_targeted" => {
+            let targets = EmitterTargeted::default_targets(pos_x, pos_y);
+            Some(RaytracerObjects::Emitters(Emitters::EmitterTargeted(
+                EmitterTargeted::new(
+                    base_object,
+                    init_targeted_rays(pos_x, pos_y, &targets),
+                    targets,
+                ),
+            )))
+        }
         // Create a perfect absorber (full opaque)
-        let new_object = AbsorberPerfect::new(ObjectCircle::new(
-            mouse_x,
-            mouse_y,
-            OBJD_CIRCLE_FILL,
-            OBJD_CIRCLE_RADIUS,
+        "absorber_perfect" => Some(RaytracerObjects::Absorbers(Absorbers::AbsorberPerfect(
+            AbsorberPerfect::new(base_object),
+        ))),
+        // Create an arc absorber (only blocks along the arc)
+        "absorber_arc" => Some(RaytracerObjects::Absorbers(Absorbers::AbsorberArc(
+            AbsorberArc::new(base_object, OBJD_ARC_START_ANGLE, OBJD_ARC_END_ANGLE),
+        ))),
+        // Create an arc mirror (a concave mirror facing left)
+        "mirror_arc" => Some(RaytracerObjects::Absorbers(Absorbers::MirrorArc(
+            AbsorberArc::new(
+                ObjectCircle::new(pos_x, pos_y, OBJD_MIRROR_COLOR, OBJD_MIRROR_RADIUS),
+                OBJD_MIRROR_START_ANGLE,
+                OBJD_MIRROR_END_ANGLE,
+            ),
+        ))),
+        _ => None,
+    }
+}
+
+/// Creates a new object of the given type, sized by a drag.
+///
+/// The drag distance sets the radius of circles, absorbers, mirrors, and
+/// most emitters. A collimated emitter's beam is as wide as the drag on each
+/// side, and a spotlight's beam angle grows by `OBJC_CREATION_ANGLE_PER_PIXEL`
+/// per pixel dragged (up to a full circle). The object is not added to the
+/// scene.
+///
+/// # Arguments
+///
+/// * `object_type` - The type of object, see `new_object_at` for the
+///   accepted values
+/// * `pos_x` - X-coordinate of the new object
+/// * `pos_y` - Y-coordinate of the new object
+/// * `size` - The drag distance in pixels, or `None` for the default size
+///
+/// # Returns
+///
+/// * `Some(RaytracerObjects)` - The new object
+/// * `None` - If the object type is not recognized
+pub fn new_sized_object_at(
+    object_type: &str,
+    pos_x: f32,
+    pos_y: f32,
+    size: Option<f32>,
+) -> Option<RaytracerObjects> {
+    let mut object = new_object_at(object_type, pos_x, pos_y)?;
+    let Some(size) = size else {
+        return Some(object);
+    };
+
+    // the rays are built for the default size, so the emitters whose rays
+    // depend on it get new ones
+    match &mut object {
+        RaytracerObjects::ObjectCircle(o) => o.set_radius(size),
+        RaytracerObjects::Absorbers(o) => o.set_radius(size),
+        RaytracerObjects::Emitters(Emitters::EmitterCollimated(o)) => {
+            o.collimated_beam_diameter = 2.0 * size;
+            o.base_emitter.rays = init_collimated_rays(
+                pos_x,
+                pos_y,
+                o.orientation,
+                o.collimated_beam_diameter,
+                o.base_emitter.ray_count,
+            );
+        }
+        RaytracerObjects::Emitters(Emitters::EmitterSpotlight(o)) => {
+            o.spotlight_beam_angle = (size * OBJC_CREATION_ANGLE_PER_PIXEL).min(TAU);
+            o.base_emitter.rays = init_spotlight_rays(
+                pos_x,
+                pos_y,
+                o.orientation,
+                o.spotlight_beam_angle,
+                o.base_emitter.ray_count,
+            );
+        }
+        RaytracerObjects::Emitters(Emitters::EmitterConvergent(o)) => {
+            o.base_emitter.base_object.set_radius(size);
+            o.base_emitter.rays =
+                init_convergent_rays(pos_x, pos_y, size, o.target, o.base_emitter.ray_count);
+        }
+        RaytracerObjects::Emitters(o) => o.set_radius(size),
+    }
+
+    Some(object)
+}
+
+/// Creates the objects of a preset centered on the given position.
+///
+/// # Arguments
+///
+/// * `preset` - A string identifier for the preset to create:
+///   - "sun": A large isotropic emitter with `OBJD_SUN_RAY_COUNT` warm rays
+///   - "eclipse": A sun with a perfect absorber to its right casting a shadow
+/// * `pos_x` - X-coordinate of the preset's main object
+/// * `pos_y` - Y-coordinate of the preset's main object
+///
+/// # Returns
+///
+/// * `Some(Vec<RaytracerObjects>)` - The new objects, in the order they should be added
+/// * `None` - If the preset is not recognized
+pub fn new_preset_at(preset: &str, pos_x: f32, pos_y: f32) -> Option<Vec<RaytracerObjects>> {
+    let sun = || {
+        let mut emitter = Emitters::EmitterIsotropic(EmitterIsotropic::new(
+            ObjectCircle::new(pos_x, pos_y, OBJD_SUN_COLOR, OBJD_SUN_RADIUS),
+            init_isotropic_rays(pos_x, pos_y, OBJD_SUN_RAY_COUNT),
         ));
+        emitter.set_ray_color(OBJD_SUN_RAY_COLOR);
+        RaytracerObjects::Emitters(emitter)
+    };
 
-        add_object_to_collection(RaytracerObjects::Absorbers(Absorbers::AbsorberPerfect(
-            new_object,
-        )));
+    match preset {
+        "sun" => Some(vec![sun()]),
+        "eclipse" => {
+            let mut absorber =
+                new_object_at("absorber_perfect", pos_x + OBJD_ECLIPSE_DISTANCE, pos_y)?;
+            if let RaytracerObjects::Absorbers(o) = &mut absorber {
+                o.set_radius(OBJD_ECLIPSE_RADIUS);
+            }
+            Some(vec![sun(), absorber])
+        }
+        _ => None,
+    }
+}
+
+/// Creates and adds a preset to the scene at the current mouse position.
+///
+/// The objects are added one by one with `add_new_object`, so an object that
+/// would exceed the ray budget is skipped. No more objects are added once the
+/// scene has `OBJC_MAX_OBJ_COUNT` objects.
+///
+/// # Arguments
+///
+/// * `preset` - A string identifier for the preset, see `new_preset_at` for
+///   the accepted values
+///
+/// # Returns
+///
+/// The number of objects added to the scene
+pub fn add_preset_to_scene(preset: &str) -> usize {
+    let (mouse_x, mouse_y) = mouse_position();
+    let Some(objects) = new_preset_at(preset, mouse_x, mouse_y) else {
+        return 0;
+    };
+
+    let mut added = 0;
+    for object in objects {
+        match add_new_object(object) {
+            Ok(()) => added += 1,
+            Err(e @ RaytracerError::TooManyObjects(_)) => {
+                eprintln!("Raytracer ~Err: Cannot add the rest of the preset, {}", e);
+                break;
+            }
+            Err(e) => eprintln!("Raytracer ~Err: Skipped an object of the preset, {}", e),
+        }
+    }
+
+    added
+}
+
+/// Creates and adds a new object to the scene.
+///
+/// New emitters are rejected if their rays would push the total number of rays
+/// in the scene over `OBJC_MAX_TOTAL_RAYS`.
+///
+/// # Arguments
+///
+/// * `object_type` - A string identifier for the type of object to create,
+///   see `new_object_at` for the accepted values
+/// * `position` - Where to create the object, usually where the cursor was
+///   when the creation key was pressed
+/// * `size` - The drag distance that sizes the object, see
+///   `new_sized_object_at`, or `None` for the default size
+///
+/// # Returns
+///
+/// `true` if the object was added to the scene, `false` otherwise
+///
+/// # Example
+///
+/// ```
+/// // Create a new isotropic emitter with a radius of 80 pixels
+/// add_object_to_scene("emitter_isotropic", (200.0, 300.0), Some(80.0));
+/// ```
+pub fn add_object_to_scene(object_type: &str, position: (f32, f32), size: Option<f32>) -> bool {
+    let Some(new_object) = new_sized_object_at(object_type, position.0, position.1, size) else {
+        return false;
+    };
+
+    match add_new_object(new_object) {
+        Ok(()) => true,
+        Err(e) => {
+            eprintln!("Raytracer ~Err: Cannot add object, {}", e);
+            false
+        }
+    }
+}
+
+/// Adds an already created object to the scene.
+///
+/// New emitters are rejected if their rays would push the total number of rays
+/// in the scene over `OBJC_MAX_TOTAL_RAYS`.
+///
+/// # Arguments
+///
+/// * `new_object` - The object to add, e.g. from `new_object_at`
+///
+/// # Returns
+///
+/// * `Ok(())` - If the object was added to the scene
+/// * `Err(RaytracerError)` - If the object would exceed the ray budget, or
+///   any error from `add_object_to_collection`
+pub fn add_new_object(new_object: RaytracerObjects) -> Result<(), RaytracerError> {
+    let scene_total_rays = total_ray_count(&OBJ_COLLECTION.read().unwrap());
+    let new_rays = total_ray_count(std::slice::from_ref(&new_object));
+    if scene_total_rays + new_rays > OBJC_MAX_TOTAL_RAYS {
+        return Err(RaytracerError::TooManyRays(OBJC_MAX_TOTAL_RAYS));
+    }
+
+    add_object_to_collection(new_object)
+}
+
+/// Draws a translucent "ghost" preview of an object being created.
+///
+/// The preview is drawn with `OBJD_PREVIEW_ALPHA` applied to the object and its
+/// rays. It is never added to the scene, so it does not take part in occlusion.
+///
+/// # Arguments
+///
+/// * `object_type` - A string identifier for the type of object to preview,
+///   see `new_object_at` for the accepted values
+/// * `position` - Where the object will be created
+/// * `size` - The drag distance that sizes the object, see
+///   `new_sized_object_at`, or `None` for the default size
+pub fn draw_object_preview(object_type: &str, position: (f32, f32), size: Option<f32>) {
+    if let Some(object) = new_sized_object_at(object_type, position.0, position.1, size) {
+        object.draw_with_alpha(OBJD_PREVIEW_ALPHA);
     }
 }
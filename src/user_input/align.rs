@@ -0,0 +1,382 @@
+//! Alignment and distribution of the selected objects
+//!
+//! These actions line up the centers of the selected objects, or space them
+//! evenly, to make neat layouts, or mirror them to make symmetric ones. They
+//! are run from the command console with the `align`, `distribute`, and `flip`
+//! commands. Locked objects are left in place.
+//!
+//! author:         Zhean Ganituen (zrygan)
+//! last updated:   April 18, 2025
+
+use std::f32::consts::{PI, TAU};
+
+use crate::globals::{OBJ_COLLECTION, collection::mark_absorbers_changed};
+use crate::helpers::action_utils::unlocked_targets;
+use crate::objects::absorber::Absorbers;
+use crate::objects::behavior::{Movable, RaytracerObjects, VariableOrientation};
+use crate::objects::emitters::Emitters;
+
+/// The direction to align or distribute objects along
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Axis {
+    /// Along the x-axis, aligned objects share the same y-coordinate
+    Horizontal,
+    /// Along the y-axis, aligned objects share the same x-coordinate
+    Vertical,
+}
+
+impl Axis {
+    /// Parses an axis typed in the console, `h`/`horizontal` or `v`/`vertical`
+    pub fn parse(name: &str) -> Option<Axis> {
+        match name {
+            "h" | "horizontal" => Some(Axis::Horizontal),
+            "v" | "vertical" => Some(Axis::Vertical),
+            _ => None,
+        }
+    }
+
+    /// Gets the axis as an adverb for messages, e.g. "horizontally"
+    pub fn adverb(&self) -> &'static str {
+        match self {
+            Axis::Horizontal => "horizontally",
+            Axis::Vertical => "vertically",
+        }
+    }
+}
+
+/// Gets the positions of objects with their centers lined up along an axis
+///
+/// The centers are moved onto the line through their average position, so
+/// a horizontal alignment keeps each x-coordinate and sets the y-coordinates
+/// to their average.
+pub fn aligned_positions(positions: &[(f32, f32)], axis: Axis) -> Vec<(f32, f32)> {
+    if positions.is_empty() {
+        return Vec::new();
+    }
+
+    let (mean_x, mean_y) = centroid(positions);
+
+    positions
+        .iter()
+        .map(|&(x, y)| match axis {
+            Axis::Horizontal => (x, mean_y),
+            Axis::Vertical => (mean_x, y),
+        })
+        .collect()
+}
+
+/// Gets the average of some positions, which must not be empty
+fn centroid(positions: &[(f32, f32)]) -> (f32, f32) {
+    let count = positions.len() as f32;
+    (
+        positions.iter().map(|p| p.0).sum::<f32>() / count,
+        positions.iter().map(|p| p.1).sum::<f32>() / count,
+    )
+}
+
+/// Mirrors a point across the line through `center`, see `flip_selection()`
+fn flipped_point(point: (f32, f32), center: (f32, f32), axis: Axis) -> (f32, f32) {
+    match axis {
+        Axis::Horizontal => (2.0 * center.0 - point.0, point.1),
+        Axis::Vertical => (point.0, 2.0 * center.1 - point.1),
+    }
+}
+
+/// Mirrors a direction (in radians, from 0 to 2π) the same way as
+/// `flipped_point()`
+///
+/// This holds for the upward y-axis of spotlight orientations too, since
+/// flipping the y-axis only changes which of the two flips negates the angle.
+pub fn flipped_angle(angle: f32, axis: Axis) -> f32 {
+    match axis {
+        Axis::Horizontal => (PI - angle).rem_euclid(TAU),
+        Axis::Vertical => (-angle).rem_euclid(TAU),
+    }
+}
+
+/// Gets the positions of objects mirrored along an axis about their centroid
+///
+/// A horizontal flip mirrors the objects left to right, negating their x
+/// offsets from the centroid, and a vertical flip mirrors them top to bottom.
+pub fn flipped_positions(positions: &[(f32, f32)], axis: Axis) -> Vec<(f32, f32)> {
+    if positions.is_empty() {
+        return Vec::new();
+    }
+
+    let center = centroid(positions);
+    positions
+        .iter()
+        .map(|&p| flipped_point(p, center, axis))
+        .collect()
+}
+
+/// Mirrors the directions of an object that was flipped about `center`
+///
+/// Oriented emitters point the mirrored way, the targets of a targeted
+/// emitter (which stay in place when it moves) are mirrored about `center`,
+/// and arcs cover the mirrored part of their circle. The object itself must
+/// already be moved.
+fn flip_directions(object: &mut RaytracerObjects, center: (f32, f32), axis: Axis) {
+    let orientation = object.params().orientation;
+    match object {
+        RaytracerObjects::Emitters(Emitters::EmitterTargeted(o)) => {
+            for target in o.targets.iter_mut() {
+                *target = flipped_point(*target, center, axis);
+            }
+        }
+        RaytracerObjects::Emitters(o) => {
+            if let Some(orientation) = orientation {
+                o.set_orientation(flipped_angle(orientation, axis));
+            }
+        }
+        RaytracerObjects::Absorbers(Absorbers::AbsorberArc(o) | Absorbers::MirrorArc(o)) => {
+            // mirroring reverses the direction the arc goes around the circle
+            (o.start_angle, o.end_angle) = (
+                flipped_angle(o.end_angle, axis),
+                flipped_angle(o.start_angle, axis),
+            );
+        }
+        RaytracerObjects::ObjectCircle(_) | RaytracerObjects::Absorbers(_) => {}
+    }
+}
+
+/// Gets the positions of objects spaced evenly along an axis
+///
+/// The two outermost objects stay where they are, and the others are moved
+/// along the axis (keeping their order) so the gaps between centers are equal.
+/// The other coordinate is not changed.
+pub fn distributed_positions(positions: &[(f32, f32)], axis: Axis) -> Vec<(f32, f32)> {
+    let coordinate = |p: (f32, f32)| match axis {
+        Axis::Horizontal => p.0,
+        Axis::Vertical => p.1,
+    };
+
+    let mut order: Vec<usize> = (0..positions.len()).collect();
+    order.sort_by(|a, b| coordinate(positions[*a]).total_cmp(&coordinate(positions[*b])));
+
+    let mut result = positions.to_vec();
+    if positions.len() < 3 {
+        return result;
+    }
+
+    let first = coordinate(positions[order[0]]);
+    let last = coordinate(positions[order[order.len() - 1]]);
+    let step = (last - first) / (positions.len() - 1) as f32;
+
+    for (rank, index) in order.into_iter().enumerate() {
+        let value = first + step * rank as f32;
+        match axis {
+            Axis::Horizontal => result[index].0 = value,
+            Axis::Vertical => result[index].1 = value,
+        }
+    }
+
+    result
+}
+
+/// Moves the unlocked selected objects to new positions
+///
+/// # Arguments
+///
+/// * `selected` - The indices of the selected objects
+/// * `minimum` - The fewest unlocked objects the arrangement needs
+/// * `arrange` - Gets the new positions from the current ones
+///
+/// # Returns
+///
+/// * `Ok(usize)` - The number of objects that were arranged
+/// * `Err(String)` - If fewer than `minimum` unlocked objects are selected
+fn arrange_selection(
+    selected: &[usize],
+    minimum: usize,
+    arrange: impl Fn(&[(f32, f32)]) -> Vec<(f32, f32)>,
+) -> Result<usize, String> {
+    let mut collection = OBJ_COLLECTION.write().unwrap();
+    let targets = arrangement_targets(&collection, selected, minimum)?;
+
+    let positions: Vec<(f32, f32)> = targets.iter().map(|i| collection[*i].get_pos()).collect();
+    for (index, (x, y)) in targets.iter().zip(arrange(&positions)) {
+        move_to(&mut collection[*index], x, y);
+    }
+    mark_absorbers_changed();
+
+    Ok(targets.len())
+}
+
+/// Gets the indices of the selected objects that can be arranged, the ones
+/// that are not locked (see `unlocked_targets()`)
+///
+/// # Returns
+///
+/// * `Ok(Vec<usize>)` - The indices of the unlocked selected objects
+/// * `Err(String)` - If there are fewer than `minimum` of them
+fn arrangement_targets(
+    collection: &[RaytracerObjects],
+    selected: &[usize],
+    minimum: usize,
+) -> Result<Vec<usize>, String> {
+    let (targets, _) = unlocked_targets(collection, selected.to_vec());
+
+    if targets.len() < minimum {
+        return Err(format!("select at least {} unlocked objects", minimum));
+    }
+    Ok(targets)
+}
+
+/// Moves an object of any type to a position
+fn move_to(object: &mut RaytracerObjects, pos_x: f32, pos_y: f32) {
+    match object {
+        RaytracerObjects::ObjectCircle(o) => o.move_object(pos_x, pos_y),
+        RaytracerObjects::Emitters(o) => o.move_object(pos_x, pos_y),
+        RaytracerObjects::Absorbers(o) => o.move_object(pos_x, pos_y),
+    }
+}
+
+/// Lines up the centers of the selected objects along an axis
+///
+/// See `aligned_positions()`. The caller is responsible for reinitializing
+/// the rays.
+///
+/// # Returns
+///
+/// * `Ok(usize)` - The number of objects that were aligned
+/// * `Err(String)` - If fewer than two unlocked objects are selected
+pub fn align_selection(selected: &[usize], axis: Axis) -> Result<usize, String> {
+    arrange_selection(selected, 2, |positions| aligned_positions(positions, axis))
+}
+
+/// Spaces the selected objects evenly along an axis
+///
+/// See `distributed_positions()`. The caller is responsible for reinitializing
+/// the rays.
+///
+/// # Returns
+///
+/// * `Ok(usize)` - The number of objects that were distributed
+/// * `Err(String)` - If fewer than three unlocked objects are selected
+pub fn distribute_selection(selected: &[usize], axis: Axis) -> Result<usize, String> {
+    arrange_selection(selected, 3, |positions| {
+        distributed_positions(positions, axis)
+    })
+}
+
+/// Mirrors the selected objects along an axis about their centroid
+///
+/// See `flipped_positions()`, the directions of the objects are mirrored too
+/// (see `flip_directions()`). A single object stays in place and is only
+/// turned around. The caller is responsible for reinitializing the rays.
+///
+/// # Returns
+///
+/// * `Ok(usize)` - The number of objects that were flipped
+/// * `Err(String)` - If no unlocked objects are selected
+pub fn flip_selection(selected: &[usize], axis: Axis) -> Result<usize, String> {
+    let mut collection = OBJ_COLLECTION.write().unwrap();
+    let targets = arrangement_targets(&collection, selected, 1)?;
+
+    let positions: Vec<(f32, f32)> = targets.iter().map(|i| collection[*i].get_pos()).collect();
+    let center = centroid(&positions);
+    for (index, (x, y)) in targets.iter().zip(flipped_positions(&positions, axis)) {
+        let object = &mut collection[*index];
+        move_to(object, x, y);
+        flip_directions(object, center, axis);
+    }
+    mark_absorbers_changed();
+
+    Ok(targets.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::objects::absorber::AbsorberArc;
+    use crate::objects::circle::ObjectCircle;
+    use crate::objects::emitters::{EmitterCollimated, EmitterTargeted};
+    use macroquad::color::WHITE;
+    use std::f32::consts::FRAC_PI_2;
+
+    #[test]
+    fn centers_are_aligned_on_their_average() {
+        let positions = [(0.0, 10.0), (50.0, 30.0), (100.0, 20.0)];
+
+        assert_eq!(
+            aligned_positions(&positions, Axis::Horizontal),
+            vec![(0.0, 20.0), (50.0, 20.0), (100.0, 20.0)]
+        );
+        assert_eq!(
+            aligned_positions(&positions, Axis::Vertical),
+            vec![(50.0, 10.0), (50.0, 30.0), (50.0, 20.0)]
+        );
+    }
+
+    #[test]
+    fn distribution_keeps_the_ends_and_order() {
+        // out of order on purpose, the middle object is listed last
+        let positions = [(0.0, 5.0), (90.0, 5.0), (20.0, 7.0)];
+
+        assert_eq!(
+            distributed_positions(&positions, Axis::Horizontal),
+            vec![(0.0, 5.0), (90.0, 5.0), (45.0, 7.0)]
+        );
+        // fewer than three objects are left alone
+        assert_eq!(
+            distributed_positions(&positions[..2], Axis::Vertical),
+            positions[..2].to_vec()
+        );
+    }
+
+    #[test]
+    fn flips_mirror_positions_about_the_centroid() {
+        let positions = [(0.0, 10.0), (30.0, 0.0), (90.0, 50.0)];
+
+        assert_eq!(
+            flipped_positions(&positions, Axis::Horizontal),
+            vec![(80.0, 10.0), (50.0, 0.0), (-10.0, 50.0)]
+        );
+        assert_eq!(
+            flipped_positions(&positions, Axis::Vertical),
+            vec![(0.0, 30.0), (30.0, 40.0), (90.0, -10.0)]
+        );
+    }
+
+    #[test]
+    fn flips_mirror_directions() {
+        let close = |a: f32, b: f32| (a - b).abs() < 1e-5;
+        assert!(close(flipped_angle(0.0, Axis::Horizontal), PI));
+        assert!(close(flipped_angle(FRAC_PI_2, Axis::Horizontal), FRAC_PI_2));
+        assert!(close(
+            flipped_angle(FRAC_PI_2, Axis::Vertical),
+            3.0 * FRAC_PI_2
+        ));
+
+        let circle = || ObjectCircle::new(0.0, 0.0, WHITE, 10.0);
+        let mut collimated = RaytracerObjects::Emitters(Emitters::EmitterCollimated(
+            EmitterCollimated::new(circle(), Vec::new(), 0.0, 20.0),
+        ));
+        flip_directions(&mut collimated, (0.0, 0.0), Axis::Horizontal);
+        assert!(close(collimated.params().orientation.unwrap(), PI));
+
+        let mut targeted = RaytracerObjects::Emitters(Emitters::EmitterTargeted(
+            EmitterTargeted::new(circle(), Vec::new(), vec![(40.0, 5.0)]),
+        ));
+        flip_directions(&mut targeted, (10.0, 0.0), Axis::Horizontal);
+        let RaytracerObjects::Emitters(Emitters::EmitterTargeted(o)) = &targeted else {
+            unreachable!()
+        };
+        assert_eq!(o.targets, vec![(-20.0, 5.0)]);
+
+        // an arc facing right faces left once flipped horizontally
+        let mut arc = RaytracerObjects::Absorbers(Absorbers::MirrorArc(AbsorberArc::new(
+            circle(),
+            -FRAC_PI_2 / 2.0,
+            FRAC_PI_2 / 2.0,
+        )));
+        flip_directions(&mut arc, (0.0, 0.0), Axis::Horizontal);
+        let RaytracerObjects::Absorbers(Absorbers::MirrorArc(o)) = &arc else {
+            unreachable!()
+        };
+        assert!(close(o.start_angle, 3.0 * PI / 4.0));
+        assert!(close(o.end_angle, 5.0 * PI / 4.0));
+        assert!(o.contains_angle(PI) && !o.contains_angle(0.0));
+    }
+}
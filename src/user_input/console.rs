@@ -0,0 +1,638 @@
+//! Command console for scripted actions
+//!
+//! This module provides a one-line text console, opened with the backtick key,
+//! where commands such as `add spotlight 100 200` are typed and run against the
+//! scene. Commands are parsed into a `ConsoleCommand` and dispatched to the
+//! same functions the keybinds use.
+//!
+//! author:         Zhean Ganituen (zrygan)
+//! last updated:   April 18, 2025
+
+use crate::errors::RaytracerError;
+use crate::globals::{
+    CONSOLE_BG_COLOR, CONSOLE_FONT_SIZE, CONSOLE_TEXT_COLOR, FLOWING_RAYS, OBJ_COLLECTION,
+    OBJC_MAX_RAY_COUNT, OBJC_MIN_RAY_COUNT, OBJD_VISIBLE_WAVELENGTHS, RAY_DRAW_STRIDE,
+    RAY_FLOW_SPEED, collection::mark_absorbers_changed,
+};
+use crate::helpers::action_utils::unlocked_targets;
+use crate::helpers::export_utils::export_settings_json;
+use crate::helpers::object_utils::total_ray_count;
+use crate::helpers::scene_io::{load_csv, save_csv};
+use crate::objects::behavior::{Drawable, ObjectParam, RaytracerObjects};
+use crate::objects::emitters::{Emitters, VariableRays};
+use crate::user_input::add_to_scene_actions::{add_new_object, new_object_at};
+use crate::user_input::align::{Axis, align_selection, distribute_selection, flip_selection};
+use macroquad::input::{KeyCode, clear_input_queue, get_char_pressed, is_key_pressed};
+use macroquad::shapes::draw_rectangle;
+use macroquad::text::draw_text;
+use macroquad::window::{screen_height, screen_width};
+
+/// The usage printed for unknown or malformed commands
+pub const CONSOLE_USAGE: &str = "commands: add <type> <x> <y>, clear, save <path>, load <path>, rays <count>, stride <n>, flow <speed|off>, set <radius|orientation|angle|diameter> <value>, settings <path>, wavelength <nm|off>, target <x> <y>, target clear, align <h|v>, distribute <h|v>, flip <h|v>";
+
+/// A command typed into the console
+#[derive(Clone, Debug, PartialEq)]
+pub enum ConsoleCommand {
+    /// Adds an object of a type accepted by `new_object_at()` at a position
+    Add {
+        object_type: &'static str,
+        pos_x: f32,
+        pos_y: f32,
+    },
+    /// Removes every object from the scene
+    Clear,
+    /// Saves the scene as a CSV layout
+    Save(String),
+    /// Loads a CSV layout into the scene
+    Load(String),
+    /// Sets the ray count of the selected emitters, or of every emitter if
+    /// nothing is selected
+    Rays(i32),
+    /// Draws only every Nth ray of each emitter, see `RAY_DRAW_STRIDE`
+    Stride(usize),
+    /// Makes the rays flow at a speed (in pixels per second), see
+    /// `FLOWING_RAYS`, `None` stops them
+    Flow(Option<f32>),
+    /// Sets a parameter of the selected objects, see
+    /// `RaytracerObjects::set_param()`, angles are typed in degrees
+    Set { param: ObjectParam, value: f32 },
+    /// Saves the keybinds and runtime settings as JSON, see
+    /// `export_settings_json()`
+    Settings(String),
+    /// Sets the wavelength (in nanometers) of the selected emitters, or of
+    /// every emitter if nothing is selected, `None` clears it
+    Wavelength(Option<f32>),
+    /// Adds a target point to the selected targeted emitters
+    Target { pos_x: f32, pos_y: f32 },
+    /// Removes every target of the selected targeted emitters
+    ClearTargets,
+    /// Lines up the centers of the selected objects along an axis
+    Align(Axis),
+    /// Spaces the selected objects evenly along an axis
+    Distribute(Axis),
+    /// Mirrors the selected objects along an axis about their centroid
+    Flip(Axis),
+}
+
+impl ConsoleCommand {
+    /// Parses a line typed into the console
+    ///
+    /// Object types for `add` may be given by their full name (e.g.
+    /// `emitter_spotlight`) or a short name: `circle`, `isotropic`,
+    /// `collimated`, `spotlight`, `convergent`, `targeted`, `absorber`, `arc`,
+    /// or `mirror`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(ConsoleCommand)` - The parsed command
+    /// * `Err(String)` - A message describing why the line is invalid
+    pub fn parse(line: &str) -> Result<ConsoleCommand, String> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let number = |word: &str| {
+            word.parse::<f32>()
+                .map_err(|_| format!("'{}' is not a number", word))
+        };
+
+        match words.as_slice() {
+            ["add", object_type, x, y] => Ok(ConsoleCommand::Add {
+                object_type: object_type_name(object_type)
+                    .ok_or(format!("unknown object type '{}'", object_type))?,
+                pos_x: number(x)?,
+                pos_y: number(y)?,
+            }),
+            ["clear"] => Ok(ConsoleCommand::Clear),
+            ["save", path] => Ok(ConsoleCommand::Save(path.to_string())),
+            ["load", path] => Ok(ConsoleCommand::Load(path.to_string())),
+            ["rays", count] => count
+                .parse::<i32>()
+                .map(ConsoleCommand::Rays)
+                .map_err(|_| format!("'{}' is not a whole number", count)),
+            ["stride", stride] => match stride.parse::<usize>() {
+                Ok(stride) if stride >= 1 => Ok(ConsoleCommand::Stride(stride)),
+                _ => Err(format!("'{}' is not a whole number of at least 1", stride)),
+            },
+            ["flow", "off"] => Ok(ConsoleCommand::Flow(None)),
+            ["flow", speed] => match number(speed)? {
+                speed if speed > 0.0 => Ok(ConsoleCommand::Flow(Some(speed))),
+                _ => Err(format!("'{}' is not a speed above 0", speed)),
+            },
+            ["set", param, value] => {
+                let param = match *param {
+                    "radius" => ObjectParam::Radius,
+                    "orientation" => ObjectParam::Orientation,
+                    "angle" => ObjectParam::BeamAngle,
+                    "diameter" => ObjectParam::BeamDiameter,
+                    _ => return Err(format!("unknown parameter '{}'", param)),
+                };
+                let value = match param {
+                    ObjectParam::Orientation | ObjectParam::BeamAngle => {
+                        number(value)?.to_radians()
+                    }
+                    ObjectParam::Radius | ObjectParam::BeamDiameter => number(value)?,
+                };
+                Ok(ConsoleCommand::Set { param, value })
+            }
+            ["settings", path] => Ok(ConsoleCommand::Settings(path.to_string())),
+            ["wavelength", "off"] => Ok(ConsoleCommand::Wavelength(None)),
+            ["wavelength", nm] => Ok(ConsoleCommand::Wavelength(Some(number(nm)?))),
+            ["target", "clear"] => Ok(ConsoleCommand::ClearTargets),
+            ["target", x, y] => Ok(ConsoleCommand::Target {
+                pos_x: number(x)?,
+                pos_y: number(y)?,
+            }),
+            ["align", axis] => Axis::parse(axis)
+                .map(ConsoleCommand::Align)
+                .ok_or(format!("unknown axis '{}', use h or v", axis)),
+            ["distribute", axis] => Axis::parse(axis)
+                .map(ConsoleCommand::Distribute)
+                .ok_or(format!("unknown axis '{}', use h or v", axis)),
+            ["flip", axis] => Axis::parse(axis)
+                .map(ConsoleCommand::Flip)
+                .ok_or(format!("unknown axis '{}', use h or v", axis)),
+            _ => Err(CONSOLE_USAGE.to_string()),
+        }
+    }
+
+    /// Runs the command against the scene
+    ///
+    /// # Arguments
+    ///
+    /// * `selected` - The indices of the selected objects, used by `rays`,
+    ///   `set`, `wavelength`, `target`, `align`, and `distribute`
+    ///
+    /// Locked objects among them are skipped, and the message says how many.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(String)` - A message describing what was done, the rays must then
+    ///   be reinitialized
+    /// * `Err(String)` - A message describing why the command failed
+    pub fn execute(&self, selected: &[usize]) -> Result<String, String> {
+        match self {
+            ConsoleCommand::Add {
+                object_type,
+                pos_x,
+                pos_y,
+            } => {
+                let object = new_object_at(object_type, *pos_x, *pos_y)
+                    .ok_or(format!("unknown object type '{}'", object_type))?;
+                add_new_object(object).map_err(|e| e.to_string())?;
+                Ok(format!("Added {} at {}, {}", object_type, pos_x, pos_y))
+            }
+            ConsoleCommand::Clear => {
+                OBJ_COLLECTION.write().unwrap().clear();
+                mark_absorbers_changed();
+                Ok("Removed every object".to_string())
+            }
+            ConsoleCommand::Save(path) => save_csv(path)
+                .map(|count| format!("Saved {} object(s) to {}", count, path))
+                .map_err(|e| format!("failed to save {}: {}", path, e)),
+            ConsoleCommand::Load(path) => load_csv(path)
+                .map(|count| format!("Loaded {} object(s) from {}", count, path))
+                .map_err(|e| format!("failed to load {}: {}", path, e)),
+            ConsoleCommand::Rays(count) => {
+                if !(OBJC_MIN_RAY_COUNT..=OBJC_MAX_RAY_COUNT).contains(count) {
+                    return Err(format!(
+                        "ray count must be between {} and {}",
+                        OBJC_MIN_RAY_COUNT, OBJC_MAX_RAY_COUNT
+                    ));
+                }
+
+                let mut collection = OBJ_COLLECTION.write().unwrap();
+                let (targets, locked) =
+                    unlocked_targets(&collection, all_if_empty(selected, collection.len()));
+
+                let mut changed = 0;
+                for index in targets {
+                    let scene_total_rays = total_ray_count(&collection);
+                    if let Some(RaytracerObjects::Emitters(o)) = collection.get_mut(index) {
+                        match o.change_rays_count(count - o.get_rays_count(), scene_total_rays) {
+                            Ok(()) => changed += 1,
+                            // targeted emitters keep one ray per target
+                            Err(RaytracerError::FixedRays) => {}
+                            Err(e) => {
+                                return Err(format!(
+                                    "{} (after setting {} emitter(s))",
+                                    e, changed
+                                ));
+                            }
+                        }
+                    }
+                }
+
+                Ok(with_locked(
+                    format!("Set {} emitter(s) to {} rays", changed, count),
+                    locked,
+                ))
+            }
+            ConsoleCommand::Stride(stride) => {
+                *RAY_DRAW_STRIDE.write().unwrap() = *stride;
+                Ok(if *stride == 1 {
+                    "Drawing every ray".to_string()
+                } else {
+                    format!("Drawing 1 in every {} rays", stride)
+                })
+            }
+            ConsoleCommand::Flow(speed) => {
+                *FLOWING_RAYS.write().unwrap() = speed.is_some();
+                Ok(match speed {
+                    Some(speed) => {
+                        *RAY_FLOW_SPEED.write().unwrap() = *speed;
+                        format!("Rays flow at {} px/s", speed)
+                    }
+                    None => "Rays are drawn as solid lines".to_string(),
+                })
+            }
+            ConsoleCommand::Set { param, value } => {
+                // the bounds are the same for every object, so a bad value is
+                // rejected before any object is changed
+                let (range, bounds) = param.bounds();
+                if !range.contains(value) {
+                    return Err(RaytracerError::ParamOutOfRange {
+                        param: param.name(),
+                        bounds,
+                    }
+                    .to_string());
+                }
+
+                let mut collection = OBJ_COLLECTION.write().unwrap();
+                let (targets, locked) = unlocked_targets(&collection, selected.to_vec());
+
+                let mut changed = 0;
+                for index in targets {
+                    // objects without the parameter are skipped
+                    if collection[index].set_param(*param, *value).is_ok() {
+                        changed += 1;
+                    }
+                }
+                if changed > 0 {
+                    mark_absorbers_changed();
+                }
+
+                if changed == 0 {
+                    return Err(with_locked(
+                        format!("select an unlocked object with a {} first", param),
+                        locked,
+                    ));
+                }
+                Ok(with_locked(
+                    format!("Set the {} of {} object(s)", param, changed),
+                    locked,
+                ))
+            }
+            ConsoleCommand::Settings(path) => export_settings_json(path)
+                .map(|()| format!("Saved the keybinds and settings to {}", path))
+                .map_err(|e| format!("failed to save {}: {}", path, e)),
+            ConsoleCommand::Wavelength(wavelength_nm) => {
+                let (min, max) = OBJD_VISIBLE_WAVELENGTHS;
+                if let Some(nm) = wavelength_nm
+                    && !(min..=max).contains(nm)
+                {
+                    return Err(format!("wavelength must be between {} and {} nm", min, max));
+                }
+
+                let mut collection = OBJ_COLLECTION.write().unwrap();
+                let (targets, locked) =
+                    unlocked_targets(&collection, all_if_empty(selected, collection.len()));
+
+                let mut changed = 0;
+                for index in targets {
+                    if let RaytracerObjects::Emitters(o) = &mut collection[index] {
+                        o.set_wavelength(*wavelength_nm);
+                        changed += 1;
+                    }
+                }
+
+                Ok(with_locked(
+                    match wavelength_nm {
+                        Some(nm) => format!("Set {} emitter(s) to {} nm", changed, nm),
+                        None => format!("Cleared the wavelength of {} emitter(s)", changed),
+                    },
+                    locked,
+                ))
+            }
+            ConsoleCommand::Target { pos_x, pos_y } => {
+                let mut collection = OBJ_COLLECTION.write().unwrap();
+                let (targets, locked) = unlocked_targets(&collection, selected.to_vec());
+
+                let mut changed = 0;
+                for index in targets {
+                    let scene_total_rays = total_ray_count(&collection);
+                    if let RaytracerObjects::Emitters(Emitters::EmitterTargeted(o)) =
+                        &mut collection[index]
+                    {
+                        if let Err(e) = o.add_target((*pos_x, *pos_y), scene_total_rays) {
+                            return Err(format!("{} (after aiming {} emitter(s))", e, changed));
+                        }
+                        changed += 1;
+                    }
+                }
+
+                if changed == 0 {
+                    return Err(with_locked(
+                        "select an unlocked targeted emitter first".to_string(),
+                        locked,
+                    ));
+                }
+                Ok(with_locked(
+                    format!("Aimed {} emitter(s) at {}, {}", changed, pos_x, pos_y),
+                    locked,
+                ))
+            }
+            ConsoleCommand::ClearTargets => {
+                let mut collection = OBJ_COLLECTION.write().unwrap();
+                let (targets, locked) = unlocked_targets(&collection, selected.to_vec());
+
+                let mut changed = 0;
+                for index in targets {
+                    if let RaytracerObjects::Emitters(Emitters::EmitterTargeted(o)) =
+                        &mut collection[index]
+                    {
+                        o.set_targets(Vec::new());
+                        changed += 1;
+                    }
+                }
+
+                if changed == 0 {
+                    return Err(with_locked(
+                        "select an unlocked targeted emitter first".to_string(),
+                        locked,
+                    ));
+                }
+                Ok(with_locked(
+                    format!("Cleared the targets of {} emitter(s)", changed),
+                    locked,
+                ))
+            }
+            ConsoleCommand::Align(axis) => align_selection(selected, *axis)
+                .map(|count| format!("Aligned {} object(s) {}", count, axis.adverb())),
+            ConsoleCommand::Distribute(axis) => distribute_selection(selected, *axis)
+                .map(|count| format!("Distributed {} object(s) {}", count, axis.adverb())),
+            ConsoleCommand::Flip(axis) => flip_selection(selected, *axis)
+                .map(|count| format!("Flipped {} object(s) {}", count, axis.adverb())),
+        }
+    }
+}
+
+/// Gets the objects a command acts on, every object if none are selected
+fn all_if_empty(selected: &[usize], len: usize) -> Vec<usize> {
+    if selected.is_empty() {
+        (0..len).collect()
+    } else {
+        selected.to_vec()
+    }
+}
+
+/// Adds how many locked objects were skipped to the message of a command
+fn with_locked(message: String, locked: usize) -> String {
+    if locked == 0 {
+        message
+    } else {
+        format!("{}, skipped {} locked object(s)", message, locked)
+    }
+}
+
+/// Gets the `new_object_at()` name of an object type typed in the console
+fn object_type_name(name: &str) -> Option<&'static str> {
+    match name {
+        "circle" | "circle_none" => Some("circle_none"),
+        "isotropic" | "emitter_isotropic" => Some("emitter_isotropic"),
+        "collimated" | "emitter_collimated" => Some("emitter_collimated"),
+        "spotlight" | "emitter_spotlight" => Some("emitter_spotlight"),
+        "convergent" | "emitter_convergent" => Some("emitter_convergent"),
+        "targeted" | "emitter_targeted" => Some("emitter_targeted"),
+        "absorber" | "absorber_perfect" => Some("absorber_perfect"),
+        "arc" | "absorber_arc" => Some("absorber_arc"),
+        "mirror" | "mirror_arc" => Some("mirror_arc"),
+        _ => None,
+    }
+}
+
+/// The result of capturing a frame of user input for the console
+#[derive(Clone, Debug, PartialEq)]
+pub enum ConsoleState {
+    /// The user is still typing
+    Editing,
+    /// The user pressed Enter, with the typed line
+    Submitted(String),
+    /// The user pressed Escape or the backtick to close the console
+    Closed,
+}
+
+/// A one-line text console for typing commands
+#[derive(Clone, Debug, Default)]
+pub struct Console {
+    /// The text typed so far
+    pub buffer: String,
+    /// The result of the last command
+    pub output: String,
+}
+
+impl Console {
+    /// Opens an empty console
+    ///
+    /// Any characters typed before the console was opened (such as the
+    /// backtick used to open it) are discarded.
+    pub fn open() -> Console {
+        clear_input_queue();
+        Console::default()
+    }
+
+    /// Processes the user's input for this frame
+    ///
+    /// Printable characters are appended to the buffer, Backspace removes the
+    /// last character, Enter submits the line, and Escape or the backtick
+    /// closes the console.
+    pub fn capture_input(&mut self) -> ConsoleState {
+        // the char queue is a stack, so reverse it to get the typed order
+        let mut typed = Vec::new();
+        while let Some(c) = get_char_pressed() {
+            typed.push(c);
+        }
+
+        for c in typed.into_iter().rev() {
+            if (c.is_ascii_graphic() || c == ' ') && c != '`' {
+                self.buffer.push(c);
+            }
+        }
+
+        if is_key_pressed(KeyCode::Backspace) {
+            self.buffer.pop();
+        }
+
+        if is_key_pressed(KeyCode::Escape) || is_key_pressed(KeyCode::GraveAccent) {
+            ConsoleState::Closed
+        } else if is_key_pressed(KeyCode::Enter) || is_key_pressed(KeyCode::KpEnter) {
+            ConsoleState::Submitted(std::mem::take(&mut self.buffer))
+        } else {
+            ConsoleState::Editing
+        }
+    }
+}
+
+impl Drawable for Console {
+    /// Draws the console along the bottom of the window
+    fn draw_object(&self) {
+        let height = CONSOLE_FONT_SIZE * 2.5;
+        let top = screen_height() - height;
+
+        draw_rectangle(0.0, top, screen_width(), height, CONSOLE_BG_COLOR);
+        draw_text(
+            &self.output,
+            4.0,
+            top + CONSOLE_FONT_SIZE,
+            CONSOLE_FONT_SIZE * 0.75,
+            CONSOLE_TEXT_COLOR,
+        );
+        draw_text(
+            &format!("> {}_", self.buffer),
+            4.0,
+            top + CONSOLE_FONT_SIZE * 2.0,
+            CONSOLE_FONT_SIZE,
+            CONSOLE_TEXT_COLOR,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn commands_are_parsed() {
+        assert_eq!(
+            ConsoleCommand::parse("add spotlight 100 200"),
+            Ok(ConsoleCommand::Add {
+                object_type: "emitter_spotlight",
+                pos_x: 100.0,
+                pos_y: 200.0,
+            })
+        );
+        assert_eq!(ConsoleCommand::parse("  clear "), Ok(ConsoleCommand::Clear));
+        assert_eq!(
+            ConsoleCommand::parse("save scene.csv"),
+            Ok(ConsoleCommand::Save("scene.csv".to_string()))
+        );
+        assert_eq!(
+            ConsoleCommand::parse("rays 64"),
+            Ok(ConsoleCommand::Rays(64))
+        );
+        assert_eq!(
+            ConsoleCommand::parse("stride 4"),
+            Ok(ConsoleCommand::Stride(4))
+        );
+        assert_eq!(
+            ConsoleCommand::parse("flow 120"),
+            Ok(ConsoleCommand::Flow(Some(120.0)))
+        );
+        assert_eq!(
+            ConsoleCommand::parse("flow off"),
+            Ok(ConsoleCommand::Flow(None))
+        );
+        assert_eq!(
+            ConsoleCommand::parse("set radius 40"),
+            Ok(ConsoleCommand::Set {
+                param: ObjectParam::Radius,
+                value: 40.0,
+            })
+        );
+        assert_eq!(
+            ConsoleCommand::parse("set angle 180"),
+            Ok(ConsoleCommand::Set {
+                param: ObjectParam::BeamAngle,
+                value: std::f32::consts::PI,
+            })
+        );
+        assert_eq!(
+            ConsoleCommand::parse("settings config.json"),
+            Ok(ConsoleCommand::Settings("config.json".to_string()))
+        );
+        assert_eq!(
+            ConsoleCommand::parse("wavelength 532"),
+            Ok(ConsoleCommand::Wavelength(Some(532.0)))
+        );
+        assert_eq!(
+            ConsoleCommand::parse("wavelength off"),
+            Ok(ConsoleCommand::Wavelength(None))
+        );
+        assert_eq!(
+            ConsoleCommand::parse("target 10 -20"),
+            Ok(ConsoleCommand::Target {
+                pos_x: 10.0,
+                pos_y: -20.0,
+            })
+        );
+        assert_eq!(
+            ConsoleCommand::parse("target clear"),
+            Ok(ConsoleCommand::ClearTargets)
+        );
+        assert_eq!(
+            ConsoleCommand::parse("distribute v"),
+            Ok(ConsoleCommand::Distribute(Axis::Vertical))
+        );
+        assert_eq!(
+            ConsoleCommand::parse("flip h"),
+            Ok(ConsoleCommand::Flip(Axis::Horizontal))
+        );
+    }
+
+    #[test]
+    fn invalid_commands_are_rejected() {
+        assert_eq!(
+            ConsoleCommand::parse("jump"),
+            Err(CONSOLE_USAGE.to_string())
+        );
+        assert!(ConsoleCommand::parse("add laser 1 2").is_err());
+        assert!(ConsoleCommand::parse("add circle one 2").is_err());
+        assert!(ConsoleCommand::parse("rays 6.5").is_err());
+        assert!(ConsoleCommand::parse("stride 0").is_err());
+        assert!(ConsoleCommand::parse("flow 0").is_err());
+        assert!(ConsoleCommand::parse("set color 1").is_err());
+    }
+
+    #[test]
+    fn targets_stay_within_the_ray_budget() {
+        use crate::globals::OBJC_MAX_TOTAL_RAYS;
+        use crate::objects::circle::ObjectCircle;
+        use crate::objects::emitters::EmitterTargeted;
+        use macroquad::color::WHITE;
+
+        let mut scene = vec![RaytracerObjects::Emitters(Emitters::EmitterTargeted(
+            EmitterTargeted::new(ObjectCircle::new(0.0, 0.0, WHITE, 10.0), vec![], vec![]),
+        ))];
+        let RaytracerObjects::Emitters(Emitters::EmitterTargeted(o)) = &mut scene[0] else {
+            unreachable!()
+        };
+
+        // every target counts toward the budget as soon as it is added
+        for i in 0..OBJC_MAX_TOTAL_RAYS {
+            assert_eq!(o.add_target((i as f32, 0.0), i), Ok(()));
+        }
+        assert_eq!(total_ray_count(&scene), OBJC_MAX_TOTAL_RAYS);
+
+        let RaytracerObjects::Emitters(Emitters::EmitterTargeted(o)) = &mut scene[0] else {
+            unreachable!()
+        };
+        assert_eq!(
+            o.add_target((0.0, 1.0), OBJC_MAX_TOTAL_RAYS),
+            Err(RaytracerError::TooManyRays(OBJC_MAX_TOTAL_RAYS))
+        );
+
+        o.set_targets(Vec::new());
+        assert_eq!(total_ray_count(&scene), 0);
+    }
+
+    #[test]
+    fn skipped_locked_objects_are_reported() {
+        assert_eq!(
+            with_locked("Set 2 emitter(s)".to_string(), 0),
+            "Set 2 emitter(s)"
+        );
+        assert_eq!(
+            with_locked("Set 2 emitter(s)".to_string(), 3),
+            "Set 2 emitter(s), skipped 3 locked object(s)"
+        );
+        assert_eq!(all_if_empty(&[], 3), vec![0, 1, 2]);
+        assert_eq!(all_if_empty(&[4], 3), vec![4]);
+    }
+}
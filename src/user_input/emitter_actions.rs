@@ -1,30 +1,378 @@
 use crate::{
-    globals::OBJ_COLLECTION,
-    helpers::action_utils::object_at_cursor_index,
-    objects::behavior::{RaytracerObjects, VariableOrientation, VariableSize},
+    errors::RaytracerError,
+    globals::{
+        OBJ_COLLECTION, OBJC_MAX_RAY_COUNT, OBJC_MIN_RAY_COUNT, OBJD_COLLIMATED_BEAM_DIAMETER,
+        OBJD_COLLIMATED_ORIENTATION, OBJD_SPOTLIGHT_BEAM_ANGLE, OBJD_SPOTLIGHT_ORIENTATION,
+        WINDOW_AUTOTUNE_DEADBAND, WINDOW_AUTOTUNE_GAIN, collection::mark_absorbers_changed,
+    },
+    helpers::object_utils::total_ray_count,
+    objects::{
+        behavior::{RaytracerObjects, VariableOrientation, VariableSize},
+        emitters::{
+            EmitterCollimated, EmitterConvergent, EmitterIsotropic, EmitterSpotlight,
+            EmitterTargeted, Emitters, VariableRays,
+        },
+        ray::{
+            init_collimated_rays, init_convergent_rays, init_isotropic_rays, init_spotlight_rays,
+            init_targeted_rays,
+        },
+    },
 };
 
-pub fn object_change_size(mouse_x: f32, mouse_y: f32, change_factor: f32) {
-    if let Some(object_index) = object_at_cursor_index(mouse_x, mouse_y) {
-        let mut collection = OBJ_COLLECTION.write().unwrap();
-        if let Some(object) = collection.get_mut(object_index) {
-            match object {
-                RaytracerObjects::ObjectCircle(o) => o.change_radius(change_factor),
-                RaytracerObjects::Absorbers(o) => o.change_radius(change_factor),
-                RaytracerObjects::Emitters(o) => o.change_radius(change_factor),
+pub fn object_change_size(object_index: usize, change_factor: f32) {
+    let mut collection = OBJ_COLLECTION.write().unwrap();
+    if let Some(object) = collection.get_mut(object_index) {
+        match object {
+            RaytracerObjects::ObjectCircle(o) => o.change_radius(change_factor),
+            RaytracerObjects::Absorbers(o) => {
+                o.change_radius(change_factor);
+                mark_absorbers_changed();
             }
+            RaytracerObjects::Emitters(o) => o.change_radius(change_factor),
         }
     }
 }
 
-pub fn object_change_orientation(mouse_x: f32, mouse_y: f32, change_factor: f32) {
-    if let Some(object_index) = object_at_cursor_index(mouse_x, mouse_y) {
-        let mut collection = OBJ_COLLECTION.write().unwrap();
-        if let Some(object) = collection.get_mut(object_index) {
-            match object {
-                RaytracerObjects::Emitters(o) => o.change_orientation(change_factor),
-                _ => {}
+/// Gets the ray count that keeps an emitter's ray density the same after resizing
+///
+/// The number of rays is scaled by the same ratio as the radius, so the
+/// spacing between rays at the emitter's surface stays roughly constant. The
+/// result is clamped between `OBJC_MIN_RAY_COUNT` and `OBJC_MAX_RAY_COUNT`.
+pub fn scaled_ray_count(ray_count: i32, old_radius: f32, new_radius: f32) -> i32 {
+    if old_radius <= 0.0 {
+        return ray_count;
+    }
+
+    let scaled = (ray_count as f32 * new_radius / old_radius).round() as i32;
+    scaled.clamp(OBJC_MIN_RAY_COUNT, OBJC_MAX_RAY_COUNT)
+}
+
+/// Gets the ray count that moves the frame time towards its budget
+///
+/// This is a proportional controller: the relative error
+/// `(budget - frame_time) / budget` is positive when there is headroom and
+/// negative when the frame took too long, and the ray count changes by
+/// `WINDOW_AUTOTUNE_GAIN` times that fraction of itself (by at least one ray).
+/// Errors within `WINDOW_AUTOTUNE_DEADBAND` leave the count unchanged. The
+/// result is clamped between `OBJC_MIN_RAY_COUNT` and `OBJC_MAX_RAY_COUNT`.
+///
+/// # Arguments
+///
+/// * `ray_count` - The current ray count of an emitter
+/// * `frame_time` - The time the last frame took, in seconds
+/// * `budget` - The time a frame may take, in seconds
+pub fn tuned_ray_count(ray_count: i32, frame_time: f32, budget: f32) -> i32 {
+    let error = (budget - frame_time) / budget;
+    if error.abs() <= WINDOW_AUTOTUNE_DEADBAND {
+        return ray_count;
+    }
+
+    let change = (ray_count as f32 * WINDOW_AUTOTUNE_GAIN * error).round() as i32;
+    let change = if change == 0 {
+        error.signum() as i32
+    } else {
+        change
+    };
+
+    (ray_count + change).clamp(OBJC_MIN_RAY_COUNT, OBJC_MAX_RAY_COUNT)
+}
+
+/// Adjusts the ray count of every enabled emitter towards the frame budget
+///
+/// See `tuned_ray_count()`. Emitters that can't get more rays because of the
+/// scene's ray budget keep their count.
+///
+/// # Arguments
+///
+/// * `frame_time` - The time the last frame took, in seconds
+/// * `budget` - The time a frame may take, in seconds
+///
+/// # Returns
+///
+/// `true` if any ray count changed, so the rays need to be reinitialized
+pub fn autotune_ray_counts(frame_time: f32, budget: f32) -> bool {
+    let mut collection = OBJ_COLLECTION.write().unwrap();
+    let mut changed = false;
+
+    for index in 0..collection.len() {
+        let scene_total_rays = total_ray_count(&collection);
+        if let RaytracerObjects::Emitters(o) = &mut collection[index]
+            && o.is_enabled()
+        {
+            let ray_count = o.get_rays_count();
+            let target = tuned_ray_count(ray_count, frame_time, budget);
+            if target != ray_count
+                && o.change_rays_count(target - ray_count, scene_total_rays)
+                    .is_ok()
+            {
+                changed = true;
             }
         }
     }
+
+    changed
+}
+
+/// Changes the size of the object at `object_index` and, for emitters, scales
+/// the number of rays by the same ratio (see `scaled_ray_count()`)
+///
+/// # Returns
+///
+/// * `Ok(())` - If the object was resized, and its rays scaled if it is an emitter
+/// * `Err(RaytracerError)` - If the object was resized but its rays could not
+///   be changed, e.g. because of the scene's ray budget
+pub fn object_change_size_scaling_rays(
+    object_index: usize,
+    change_factor: f32,
+) -> Result<(), RaytracerError> {
+    let mut collection = OBJ_COLLECTION.write().unwrap();
+    let scene_total_rays = total_ray_count(&collection);
+    match collection.get_mut(object_index) {
+        Some(RaytracerObjects::Emitters(o)) => {
+            let old_radius = o.get_radius();
+            o.change_radius(change_factor);
+
+            let ray_count = o.get_rays_count();
+            let target = scaled_ray_count(ray_count, old_radius, o.get_radius());
+            if target != ray_count {
+                o.change_rays_count(target - ray_count, scene_total_rays)?;
+            }
+        }
+        Some(RaytracerObjects::ObjectCircle(o)) => o.change_radius(change_factor),
+        Some(RaytracerObjects::Absorbers(o)) => {
+            o.change_radius(change_factor);
+            mark_absorbers_changed();
+        }
+        None => {}
+    }
+
+    Ok(())
+}
+
+pub fn object_change_orientation(object_index: usize, change_factor: f32) {
+    let mut collection = OBJ_COLLECTION.write().unwrap();
+    if let Some(RaytracerObjects::Emitters(o)) = collection.get_mut(object_index) {
+        o.change_orientation(change_factor);
+    }
+}
+
+/// Resets the orientation of the object at `object_index` to its default
+///
+/// # Returns
+///
+/// `true` if the object is a collimated, spotlight, or convergent emitter (the
+/// only objects with an orientation), `false` otherwise
+pub fn object_reset_orientation(object_index: usize) -> bool {
+    let mut collection = OBJ_COLLECTION.write().unwrap();
+    match collection.get_mut(object_index) {
+        Some(RaytracerObjects::Emitters(o @ Emitters::EmitterCollimated(_)))
+        | Some(RaytracerObjects::Emitters(o @ Emitters::EmitterSpotlight(_)))
+        | Some(RaytracerObjects::Emitters(o @ Emitters::EmitterConvergent(_))) => {
+            o.reset_orientation();
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Points the emitter at `object_index` towards a point
+///
+/// Spotlight orientations use an upward y-axis, so the angle is measured with
+/// the y difference flipped for them. The caller is responsible for
+/// reinitializing the rays.
+///
+/// # Arguments
+///
+/// * `object_index` - The index of the emitter in `OBJ_COLLECTION`
+/// * `target_x` - X coordinate of the point to aim at
+/// * `target_y` - Y coordinate of the point to aim at
+///
+/// # Returns
+///
+/// * `Some(f32)` - The emitter's new orientation in radians
+/// * `None` - If the object is not a collimated, spotlight, or convergent
+///   emitter (the only objects with an orientation)
+pub fn object_aim_at(object_index: usize, target_x: f32, target_y: f32) -> Option<f32> {
+    let mut collection = OBJ_COLLECTION.write().unwrap();
+    match collection.get_mut(object_index) {
+        Some(RaytracerObjects::Emitters(o)) => {
+            let orientation = aim_orientation(o, target_x, target_y)?;
+            o.set_orientation(orientation);
+            Some(orientation)
+        }
+        _ => None,
+    }
+}
+
+/// Gets the orientation that points `emitter` towards a point
+///
+/// # Returns
+///
+/// The orientation in radians, or `None` for emitters without an orientation
+fn aim_orientation(emitter: &Emitters, target_x: f32, target_y: f32) -> Option<f32> {
+    let base_object = emitter.get_base_object();
+    let (dx, dy) = (target_x - base_object.pos_x, target_y - base_object.pos_y);
+    match emitter {
+        Emitters::EmitterCollimated(_) | Emitters::EmitterConvergent(_) => Some(dy.atan2(dx)),
+        Emitters::EmitterSpotlight(_) => Some((-dy).atan2(dx)),
+        Emitters::EmitterIsotropic(_) | Emitters::EmitterTargeted(_) => None,
+    }
+}
+
+/// Gets the emitter type that follows `emitter_type` in the cycle
+/// Isotropic -> Collimated -> Spotlight -> Convergent -> Targeted -> Isotropic
+pub fn next_emitter_type(emitter_type: &str) -> &'static str {
+    match emitter_type {
+        "Isotropic" => "Collimated",
+        "Collimated" => "Spotlight",
+        "Spotlight" => "Convergent",
+        "Convergent" => "Targeted",
+        _ => "Isotropic",
+    }
+}
+
+/// Converts the emitter at `index` to another emitter type in place
+///
+/// The position, radius, color, ray count, ray width, range, and whether the
+/// emitter is enabled are preserved, while the parameters specific to the new
+/// type (orientation, beam diameter, beam angle, targets) are set to their
+/// defaults. The rays are reinitialized.
+/// A targeted emitter has one ray per target, so its ray count is not kept.
+///
+/// # Arguments
+///
+/// * `index` - The index of the emitter in `OBJ_COLLECTION`
+/// * `target` - The type to convert to, one of "Isotropic", "Collimated",
+///   "Spotlight", "Convergent", or "Targeted" (see `Emitters::type_name`)
+///
+/// # Returns
+///
+/// `true` if the emitter was converted, `false` if the object at `index` is
+/// not an emitter or the target type is not recognized
+pub fn convert_emitter(index: usize, target: &str) -> bool {
+    let mut collection = OBJ_COLLECTION.write().unwrap();
+    let Some(RaytracerObjects::Emitters(emitter)) = collection.get_mut(index) else {
+        return false;
+    };
+
+    let base_object = emitter.get_base_object().clone();
+    let ray_count = emitter.get_rays_count();
+    let ray_width = emitter.get_ray_width();
+    let ray_color = emitter.get_ray_color();
+    let wavelength_nm = emitter.get_wavelength();
+    let max_length = emitter.get_max_length();
+    let enabled = emitter.is_enabled();
+    let (pos_x, pos_y) = (base_object.pos_x, base_object.pos_y);
+
+    *emitter = match target {
+        "Isotropic" => Emitters::EmitterIsotropic(EmitterIsotropic::new(
+            base_object,
+            init_isotropic_rays(pos_x, pos_y, ray_count),
+        )),
+        "Collimated" => Emitters::EmitterCollimated(EmitterCollimated::new(
+            base_object,
+            init_collimated_rays(
+                pos_x,
+                pos_y,
+                OBJD_COLLIMATED_ORIENTATION,
+                OBJD_COLLIMATED_BEAM_DIAMETER,
+                ray_count,
+            ),
+            OBJD_COLLIMATED_ORIENTATION,
+            OBJD_COLLIMATED_BEAM_DIAMETER,
+        )),
+        "Spotlight" => Emitters::EmitterSpotlight(EmitterSpotlight::new(
+            base_object,
+            init_spotlight_rays(
+                pos_x,
+                pos_y,
+                OBJD_SPOTLIGHT_ORIENTATION,
+                OBJD_SPOTLIGHT_BEAM_ANGLE,
+                ray_count,
+            ),
+            OBJD_SPOTLIGHT_ORIENTATION,
+            OBJD_SPOTLIGHT_BEAM_ANGLE,
+        )),
+        "Convergent" => {
+            let target = EmitterConvergent::default_target(pos_x, pos_y);
+            let radius = base_object.radius;
+            Emitters::EmitterConvergent(EmitterConvergent::new(
+                base_object,
+                init_convergent_rays(pos_x, pos_y, radius, target, ray_count),
+                target,
+            ))
+        }
+        "Targeted" => {
+            let targets = EmitterTargeted::default_targets(pos_x, pos_y);
+            Emitters::EmitterTargeted(EmitterTargeted::new(
+                base_object,
+                init_targeted_rays(pos_x, pos_y, &targets),
+                targets,
+            ))
+        }
+        _ => return false,
+    };
+    emitter.set_ray_width(ray_width);
+    emitter.set_ray_color(ray_color);
+    emitter.set_wavelength(wavelength_nm);
+    emitter.set_max_length(max_length);
+    emitter.set_enabled(enabled);
+
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::objects::circle::ObjectCircle;
+    use macroquad::color::WHITE;
+    use std::f32::consts::FRAC_PI_2;
+
+    #[test]
+    fn ray_count_scales_with_radius() {
+        assert_eq!(scaled_ray_count(32, 50.0, 55.0), 35);
+        assert_eq!(scaled_ray_count(32, 50.0, 45.0), 29);
+        // clamped to the limits
+        assert_eq!(scaled_ray_count(80, 50.0, 100.0), OBJC_MAX_RAY_COUNT);
+        assert_eq!(scaled_ray_count(4, 50.0, 10.0), OBJC_MIN_RAY_COUNT);
+    }
+
+    #[test]
+    fn ray_count_follows_frame_budget() {
+        // half the budget: 50% headroom, a quarter more rays
+        assert_eq!(tuned_ray_count(40, 0.01, 0.02), 50);
+        // twice the budget: half as many rays as the error, clamped
+        assert_eq!(tuned_ray_count(40, 0.04, 0.02), 20);
+        assert_eq!(tuned_ray_count(4, 0.04, 0.02), OBJC_MIN_RAY_COUNT);
+        // within the deadband
+        assert_eq!(tuned_ray_count(40, 0.021, 0.02), 40);
+        // small counts still move by one ray
+        assert_eq!(tuned_ray_count(3, 0.015, 0.02), 4);
+    }
+
+    #[test]
+    fn aim_flips_y_for_spotlights() {
+        let circle = ObjectCircle::new(0.0, 0.0, WHITE, 10.0);
+        let collimated = Emitters::EmitterCollimated(EmitterCollimated::new(
+            circle.clone(),
+            Vec::new(),
+            0.0,
+            OBJD_COLLIMATED_BEAM_DIAMETER,
+        ));
+        let spotlight = Emitters::EmitterSpotlight(EmitterSpotlight::new(
+            circle.clone(),
+            Vec::new(),
+            0.0,
+            OBJD_SPOTLIGHT_BEAM_ANGLE,
+        ));
+
+        // straight down the screen
+        let down = aim_orientation(&collimated, 0.0, 50.0).unwrap();
+        assert!((down - FRAC_PI_2).abs() < 1e-6);
+        let down = aim_orientation(&spotlight, 0.0, 50.0).unwrap();
+        assert!((down + FRAC_PI_2).abs() < 1e-6);
+
+        let isotropic = Emitters::EmitterIsotropic(EmitterIsotropic::new(circle, Vec::new()));
+        assert_eq!(aim_orientation(&isotropic, 0.0, 50.0), None);
+    }
 }
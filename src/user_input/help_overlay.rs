@@ -0,0 +1,332 @@
+//! Keyboard shortcut help overlay
+//!
+//! This module lists every action and the key bound to it. The keys are read
+//! from the `KEYB_` constants in `globals/mod.rs` rather than written out by hand,
+//! so the overlay stays accurate if the keys are remapped.
+//!
+//! author:         Zhean Ganituen (zrygan)
+//! last updated:   April 18, 2025
+
+use crate::globals::*;
+use macroquad::input::{KeyCode, is_key_down, is_key_pressed};
+use macroquad::shapes::draw_rectangle;
+use macroquad::text::draw_text;
+use macroquad::window::{screen_height, screen_width};
+
+/// Gets the display name of a key
+///
+/// # Example
+///
+/// ```
+/// assert_eq!(key_name(KeyCode::LeftBracket), "LeftBracket");
+/// ```
+pub fn key_name(key: KeyCode) -> String {
+    format!("{:?}", key)
+}
+
+/// Gets every action and the key (or keys) bound to it
+///
+/// # Returns
+///
+/// A list of `(action, keys)` pairs in the order they are shown in the overlay
+pub fn keybind_help() -> Vec<(&'static str, String)> {
+    let pair = |a: KeyCode, b: KeyCode| format!("{} / {}", key_name(a), key_name(b));
+
+    vec![
+        ("Create a simple circle", key_name(KEYB_SIMPLE_CIRCLE)),
+        (
+            "Create an isotropic emitter",
+            key_name(KEYB_EMITTER_ISOTROPIC),
+        ),
+        (
+            "Create a collimated emitter",
+            key_name(KEYB_EMITTER_COLLIMATED),
+        ),
+        (
+            "Create a spotlight emitter",
+            key_name(KEYB_EMITTER_SPOTLIGHT),
+        ),
+        (
+            "Create a convergent emitter",
+            key_name(KEYB_EMITTER_CONVERGENT),
+        ),
+        ("Create a targeted emitter", key_name(KEYB_EMITTER_TARGETED)),
+        ("Create a perfect absorber", key_name(KEYB_ABSORBER_PERFECT)),
+        ("Create an arc absorber", key_name(KEYB_ABSORBER_ARC)),
+        ("Create an arc mirror", key_name(KEYB_MIRROR_ARC)),
+        ("Delete object", key_name(KEYB_DELETE)),
+        (
+            "Restore deleted object or replaced scene",
+            format!(
+                "{} + {}",
+                key_name(KEYB_RESTORE_MODIFIER),
+                key_name(KEYB_RESTORE_DELETED)
+            ),
+        ),
+        (
+            "Bring to front / send to back",
+            pair(KEYB_BRING_TO_FRONT, KEYB_SEND_TO_BACK),
+        ),
+        ("Select / deselect object", key_name(KEYB_SELECT_TOGGLE)),
+        ("Clear the selection", key_name(KEYB_SELECT_CLEAR)),
+        (
+            "Select the next / previous object",
+            format!(
+                "{} / Shift+{}",
+                key_name(KEYB_SELECT_NEXT),
+                key_name(KEYB_SELECT_NEXT)
+            ),
+        ),
+        ("Enlarge / shrink", pair(KEYB_RTC_ENLARGE, KEYB_RTC_SHRINK)),
+        (
+            "Rotate",
+            pair(KEYB_RTC_INC_ORIENTATION, KEYB_RTC_DEC_ORIENTATION),
+        ),
+        ("Reset orientation", key_name(KEYB_RTC_RESET_ORIENTATION)),
+        (
+            "Aim at cursor",
+            format!(
+                "{} + {}",
+                key_name(KEYB_RTC_AIM_MODIFIER),
+                key_name(KEYB_RTC_AIM_AT_CURSOR)
+            ),
+        ),
+        (
+            "Aim emitter at cursor",
+            format!("{}+drag", key_name(KEYB_RTC_ROTATE_DRAG)),
+        ),
+        ("Lock / unlock", key_name(KEYB_TOGGLE_LOCK)),
+        ("Group the selection", key_name(KEYB_GROUP)),
+        ("Ungroup", key_name(KEYB_UNGROUP)),
+        (
+            "Add / remove a ray",
+            pair(KEYB_EMM_INC_RAYS, KEYB_EMM_DEC_RAYS),
+        ),
+        (
+            "Beam diameter or angle",
+            pair(KEYB_EMM_SEC_INC, KEYB_EMM_SEC_DEC),
+        ),
+        ("Convert emitter type", key_name(KEYB_EMM_CONVERT)),
+        ("Switch emitter on / off", key_name(KEYB_EMM_TOGGLE_ENABLED)),
+        ("Freeze emitter rays", key_name(KEYB_EMM_SNAPSHOT_RAYS)),
+        ("Clear frozen rays", key_name(KEYB_CLEAR_SNAPSHOTS)),
+        ("Show mirrored image source", key_name(KEYB_IMAGE_SOURCE)),
+        (
+            "Measure an angle",
+            format!(
+                "{} + {}",
+                key_name(KEYB_MEASURE_ANGLE_MODIFIER),
+                key_name(KEYB_MEASURE_ANGLE)
+            ),
+        ),
+        ("Type an exact value", key_name(KEYB_NUMERIC_ENTRY)),
+        ("Open the command console", key_name(KEYB_CONSOLE)),
+        ("Toggle ray crossings", key_name(KEYB_TOGGLE_INTERSECTIONS)),
+        (
+            "Toggle ray endpoints",
+            format!(
+                "{} + {}",
+                key_name(KEYB_RAY_ENDPOINTS_MODIFIER),
+                key_name(KEYB_TOGGLE_RAY_ENDPOINTS)
+            ),
+        ),
+        ("Toggle occlusion", key_name(KEYB_TOGGLE_OCCLUSION)),
+        ("Toggle absorber shadows", key_name(KEYB_TOGGLE_SHADOWS)),
+        ("Toggle additive rays", key_name(KEYB_TOGGLE_ADDITIVE_RAYS)),
+        ("Toggle long exposure", key_name(KEYB_TOGGLE_LIGHTMAP)),
+        ("Switch dark / light theme", key_name(KEYB_TOGGLE_THEME)),
+        ("Toggle ray count tuning", key_name(KEYB_TOGGLE_AUTOTUNE)),
+        ("Clear long exposure", key_name(KEYB_CLEAR_LIGHTMAP)),
+        ("Export long exposure", key_name(KEYB_EXPORT_LIGHTMAP)),
+        (
+            "Toggle dragging stops at objects",
+            key_name(KEYB_TOGGLE_DRAG_COLLISION),
+        ),
+        (
+            "Toggle wrap around when dragging",
+            format!(
+                "{} + {}",
+                key_name(KEYB_DRAG_WRAP_MODIFIER),
+                key_name(KEYB_TOGGLE_DRAG_WRAP)
+            ),
+        ),
+        (
+            "Toggle keeping dragged objects on screen",
+            format!(
+                "{} + {}",
+                key_name(KEYB_DRAG_CLAMP_MODIFIER),
+                key_name(KEYB_TOGGLE_DRAG_CLAMP)
+            ),
+        ),
+        (
+            "Toggle orientation arrows",
+            key_name(KEYB_TOGGLE_ORIENTATION_ARROWS),
+        ),
+        (
+            "Toggle flowing rays",
+            format!(
+                "{} + {}",
+                key_name(KEYB_RAY_FLOW_MODIFIER),
+                key_name(KEYB_TOGGLE_RAY_FLOW)
+            ),
+        ),
+        ("Report lit area", key_name(KEYB_REPORT_COVERAGE)),
+        ("Export rays", key_name(KEYB_EXPORT_RAYS)),
+        ("Export scene as SVG", key_name(KEYB_EXPORT_SVG)),
+        ("Export high quality PNG", key_name(KEYB_EXPORT_RENDER)),
+        ("Load objects from CSV", key_name(KEYB_IMPORT_CSV)),
+        ("Load preset slot", "1 - 9".to_string()),
+        (
+            "Save preset slot",
+            format!("{}+1 - 9", key_name(KEYB_SAVE_PRESET_MODIFIER)),
+        ),
+        ("Create a sun", key_name(KEYB_PRESET_SUN)),
+        (
+            "Create a sun and an absorber",
+            key_name(KEYB_PRESET_ECLIPSE),
+        ),
+        ("Reset everything", key_name(KEYB_RESET_ALL)),
+        ("Center objects in window", key_name(KEYB_FIT_TO_WINDOW)),
+        (
+            "Longer / shorter rays",
+            pair(KEYB_RAY_LENGTH_INC, KEYB_RAY_LENGTH_DEC),
+        ),
+        (
+            "Show all objects (debug)",
+            key_name(KEYB_DEBUG_SHOW_ALL_OBJ),
+        ),
+        (
+            "Show changes since the last check",
+            format!(
+                "{} + {}",
+                key_name(KEYB_PRINT_CHANGES_MODIFIER),
+                key_name(KEYB_PRINT_CHANGES)
+            ),
+        ),
+        (
+            "Show / hide this help",
+            format!(
+                "{} / Shift+{}",
+                key_name(KEYB_HELP),
+                key_name(KEYB_HELP_ALT)
+            ),
+        ),
+    ]
+}
+
+/// Checks if the key that toggles the help overlay was pressed
+///
+/// The overlay is toggled with `KEYB_HELP` or with `?` (`KEYB_HELP_ALT` while
+/// holding Shift).
+pub fn help_key_pressed() -> bool {
+    is_key_pressed(KEYB_HELP)
+        || (is_key_pressed(KEYB_HELP_ALT)
+            && (is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift)))
+}
+
+/// Gets how many keybinds fit in the window
+///
+/// The keybinds are laid out in columns of `HELP_COLUMN_WIDTH`, as many as
+/// fit side by side, and the ones that don't fit go on the next page.
+///
+/// # Arguments
+///
+/// * `width` - The width of the window
+/// * `height` - The height of the window
+///
+/// # Returns
+///
+/// The number of rows in a column and of columns on a page, at least 1 each
+pub fn help_layout(width: f32, height: f32) -> (usize, usize) {
+    let line_height = HELP_FONT_SIZE * 1.2;
+    // the title takes two lines and the hint below the list one more
+    let rows = ((height - 2.0 * HELP_MARGIN) / line_height - 3.0).floor();
+    let columns = ((width - HELP_MARGIN) / HELP_COLUMN_WIDTH).floor();
+
+    (rows.max(1.0) as usize, columns.max(1.0) as usize)
+}
+
+/// Gets the number of pages of the help overlay in a window of a given size,
+/// see `help_layout()`
+pub fn help_page_count(width: f32, height: f32) -> usize {
+    let (rows, columns) = help_layout(width, height);
+    keybind_help().len().div_ceil(rows * columns).max(1)
+}
+
+/// Draws a page of the help overlay as a translucent panel over the whole
+/// window
+///
+/// # Arguments
+///
+/// * `page` - The page to draw, counting from 0, the last page is drawn if it
+///   is past the end (e.g. after the window was enlarged)
+pub fn draw_help_overlay(page: usize) {
+    let (width, height) = (screen_width(), screen_height());
+    draw_rectangle(0.0, 0.0, width, height, HELP_BG_COLOR);
+
+    let (rows, columns) = help_layout(width, height);
+    let pages = help_page_count(width, height);
+    let page = page.min(pages - 1);
+    let line_height = HELP_FONT_SIZE * 1.2;
+    let top = HELP_MARGIN + HELP_FONT_SIZE;
+
+    draw_text(
+        &format!("Keybinds ({} of {})", page + 1, pages),
+        HELP_MARGIN,
+        top,
+        HELP_FONT_SIZE * 1.5,
+        HELP_TEXT_COLOR,
+    );
+
+    let per_page = rows * columns;
+    for (i, (action, keys)) in keybind_help()
+        .into_iter()
+        .skip(page * per_page)
+        .take(per_page)
+        .enumerate()
+    {
+        let x = HELP_MARGIN + (i / rows) as f32 * HELP_COLUMN_WIDTH;
+        let y = top + line_height * (1.5 + (i % rows) as f32);
+        draw_text(action, x, y, HELP_FONT_SIZE, HELP_TEXT_COLOR);
+        draw_text(
+            &keys,
+            x + HELP_KEY_COLUMN,
+            y,
+            HELP_FONT_SIZE,
+            HELP_TEXT_COLOR,
+        );
+    }
+
+    draw_text(
+        &format!(
+            "Hold Shift to change values faster. {} / {} to turn the page, Esc to close.",
+            key_name(HELP_PREV_PAGE),
+            key_name(HELP_NEXT_PAGE)
+        ),
+        HELP_MARGIN,
+        top + line_height * (rows as f32 + 2.0),
+        HELP_FONT_SIZE * 0.75,
+        HELP_TEXT_COLOR,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_keybind_fits_on_a_page() {
+        let (width, height) = (WINDOW_WIDTH as f32, WINDOW_HEIGHT as f32);
+        let (rows, columns) = help_layout(width, height);
+        let line_height = HELP_FONT_SIZE * 1.2;
+
+        // the columns and the hint below them stay inside the window
+        assert!(HELP_MARGIN + columns as f32 * HELP_COLUMN_WIDTH <= width);
+        assert!(HELP_MARGIN + HELP_FONT_SIZE + line_height * (rows as f32 + 2.0) <= height);
+        assert!(help_page_count(width, height) * rows * columns >= keybind_help().len());
+
+        // a window too small for anything still shows one keybind per page
+        assert_eq!(help_layout(1.0, 1.0), (1, 1));
+        assert_eq!(help_page_count(1.0, 1.0), keybind_help().len());
+    }
+}
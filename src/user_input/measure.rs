@@ -0,0 +1,193 @@
+//! Angle measuring tool
+//!
+//! This module provides a tool for measuring the angle between two
+//! directions, such as an incoming and a reflected ray. While the tool is on,
+//! left clicks place a vertex and then a point on each of the two arms, and
+//! the angle between the arms is drawn at the vertex. Clicking again starts a
+//! new measurement.
+//!
+//! author:         Zhean Ganituen (zrygan)
+//! last updated:   April 18, 2025
+
+use macroquad::color::Color;
+use macroquad::shapes::{draw_circle, draw_line};
+use macroquad::text::draw_text;
+
+use crate::globals::{
+    HUD_FONT_SIZE, HUD_MEASURE_ARC_RADIUS, HUD_MEASURE_ARC_SEGMENTS, HUD_MEASURE_THICKNESS,
+};
+
+/// Gets the angle between two arms that meet at a vertex
+///
+/// # Arguments
+///
+/// * `vertex` - The point where the arms meet
+/// * `first` - A point on the first arm
+/// * `second` - A point on the second arm
+///
+/// # Returns
+///
+/// The angle in degrees, from 0 to 180, or `None` if a point is on the vertex
+/// so its arm has no direction
+pub fn angle_between(vertex: (f32, f32), first: (f32, f32), second: (f32, f32)) -> Option<f32> {
+    let (ax, ay) = (first.0 - vertex.0, first.1 - vertex.1);
+    let (bx, by) = (second.0 - vertex.0, second.1 - vertex.1);
+    if (ax == 0.0 && ay == 0.0) || (bx == 0.0 && by == 0.0) {
+        return None;
+    }
+
+    // atan2 of the cross and dot products is accurate for every angle,
+    // unlike acos near 0 and 180 degrees
+    let cross = ax * by - ay * bx;
+    let dot = ax * bx + ay * by;
+    Some(cross.abs().atan2(dot).to_degrees())
+}
+
+/// The state of the angle measuring tool
+#[derive(Clone, Debug, Default)]
+pub struct AngleMeasure {
+    /// The points placed so far: the vertex, then a point on each arm
+    points: Vec<(f32, f32)>,
+}
+
+impl AngleMeasure {
+    /// Starts a measurement with no points placed
+    pub fn new() -> AngleMeasure {
+        AngleMeasure::default()
+    }
+
+    /// Places the next point of the measurement
+    ///
+    /// Once both arms are placed, the next click starts a new measurement with
+    /// the clicked point as its vertex.
+    ///
+    /// # Returns
+    ///
+    /// The measured angle in degrees (see `angle_between()`) if this click
+    /// placed the second arm
+    pub fn click(&mut self, pos_x: f32, pos_y: f32) -> Option<f32> {
+        if self.points.len() == 3 {
+            self.points.clear();
+        }
+        self.points.push((pos_x, pos_y));
+
+        match self.points.as_slice() {
+            [vertex, first, second] => angle_between(*vertex, *first, *second),
+            _ => None,
+        }
+    }
+
+    /// Draws the measurement
+    ///
+    /// The arm that is being placed follows the cursor, so the angle is shown
+    /// live until the second arm is clicked.
+    ///
+    /// # Arguments
+    ///
+    /// * `mouse_x` - X coordinate of the cursor
+    /// * `mouse_y` - Y coordinate of the cursor
+    /// * `color` - The color of the arms, arc, and text
+    pub fn draw(&self, mouse_x: f32, mouse_y: f32, color: Color) {
+        let Some(&vertex) = self.points.first() else {
+            return;
+        };
+
+        let mut arms: Vec<(f32, f32)> = self.points[1..].to_vec();
+        if arms.len() < 2 {
+            arms.push((mouse_x, mouse_y));
+        }
+
+        draw_circle(vertex.0, vertex.1, HUD_MEASURE_THICKNESS * 2.0, color);
+        for arm in &arms {
+            draw_line(
+                vertex.0,
+                vertex.1,
+                arm.0,
+                arm.1,
+                HUD_MEASURE_THICKNESS,
+                color,
+            );
+        }
+
+        if let [first, second] = arms[..]
+            && let Some(angle) = angle_between(vertex, first, second)
+        {
+            draw_angle_arc(vertex, first, second, angle, color);
+            draw_text(
+                &format!("{:.1} deg", angle),
+                vertex.0 + HUD_MEASURE_ARC_RADIUS,
+                vertex.1 - HUD_MEASURE_ARC_RADIUS,
+                HUD_FONT_SIZE,
+                color,
+            );
+        }
+    }
+}
+
+/// Draws the arc between two arms at `HUD_MEASURE_ARC_RADIUS` from the vertex
+///
+/// The arc goes from the first arm towards the second, the short way around.
+fn draw_angle_arc(
+    vertex: (f32, f32),
+    first: (f32, f32),
+    second: (f32, f32),
+    angle: f32,
+    color: Color,
+) {
+    let start = (first.1 - vertex.1).atan2(first.0 - vertex.0);
+    let (ax, ay) = (first.0 - vertex.0, first.1 - vertex.1);
+    let (bx, by) = (second.0 - vertex.0, second.1 - vertex.1);
+    let sweep = angle.to_radians() * (ax * by - ay * bx).signum();
+
+    let point = |step: usize| {
+        let theta = start + sweep * step as f32 / HUD_MEASURE_ARC_SEGMENTS as f32;
+        (
+            vertex.0 + HUD_MEASURE_ARC_RADIUS * theta.cos(),
+            vertex.1 + HUD_MEASURE_ARC_RADIUS * theta.sin(),
+        )
+    };
+    for step in 0..HUD_MEASURE_ARC_SEGMENTS {
+        let (from, to) = (point(step), point(step + 1));
+        draw_line(from.0, from.1, to.0, to.1, HUD_MEASURE_THICKNESS, color);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: Option<f32>, expected: f32) {
+        let actual = actual.unwrap();
+        assert!(
+            (actual - expected).abs() < 1e-3,
+            "expected {}, got {}",
+            expected,
+            actual
+        );
+    }
+
+    #[test]
+    fn angles_are_measured_between_arms() {
+        let vertex = (10.0, 10.0);
+        assert_close(angle_between(vertex, (20.0, 10.0), (10.0, 30.0)), 90.0);
+        assert_close(angle_between(vertex, (20.0, 10.0), (20.0, 20.0)), 45.0);
+        // the order of the arms and the side they are on don't matter
+        assert_close(angle_between(vertex, (20.0, 20.0), (20.0, 10.0)), 45.0);
+        assert_close(angle_between(vertex, (20.0, 10.0), (20.0, 0.0)), 45.0);
+        assert_close(angle_between(vertex, (20.0, 10.0), (0.0, 10.0)), 180.0);
+        assert_close(angle_between(vertex, (20.0, 10.0), (40.0, 10.0)), 0.0);
+        assert_eq!(angle_between(vertex, vertex, (20.0, 10.0)), None);
+    }
+
+    #[test]
+    fn every_third_click_completes_a_measurement() {
+        let mut measure = AngleMeasure::new();
+        assert_eq!(measure.click(0.0, 0.0), None);
+        assert_eq!(measure.click(10.0, 0.0), None);
+        assert_close(measure.click(0.0, 10.0), 90.0);
+
+        // the next click is the vertex of a new measurement
+        assert_eq!(measure.click(5.0, 5.0), None);
+        assert_eq!(measure.points, vec![(5.0, 5.0)]);
+    }
+}
@@ -4,10 +4,20 @@
 //! in the raytracer application. It includes:
 //!
 //! - `actions`: Functions that respond to user interactions by creating and
-//!              manipulating objects in the scene
+//!   manipulating objects in the scene
+//! - `align`: Alignment and distribution of the selected objects
+//! - `console`: Text console for typing scripted commands
+//! - `help_overlay`: Overlay that lists every keybind
+//! - `measure`: Tool for measuring the angle between two directions
+//! - `numeric_entry`: Text-input overlay for typing exact object parameters
 //!
 //! author:         Zhean Ganituen
 //! last updated:   April 16, 2025
 
 pub mod add_to_scene_actions;
+pub mod align;
+pub mod console;
 pub mod emitter_actions;
+pub mod help_overlay;
+pub mod measure;
+pub mod numeric_entry;
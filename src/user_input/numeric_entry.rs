@@ -0,0 +1,333 @@
+//! Numeric entry overlay for exact object parameters
+//!
+//! This module provides a small text-input overlay that lets the user type an
+//! exact value for one of the hovered object's parameters instead of nudging
+//! it with repeated key presses. Input is captured with Macroquad's
+//! `get_char_pressed()` and only numeric characters are accepted.
+//!
+//! author:         Zhean Ganituen (zrygan)
+//! last updated:   April 18, 2025
+
+use crate::globals::{
+    NUMENTRY_BG_COLOR, NUMENTRY_FONT_SIZE, NUMENTRY_TEXT_COLOR, OBJ_COLLECTION, OBJC_MAX_RAY_COUNT,
+    OBJC_MIN_RAY_COUNT, collection::mark_absorbers_changed,
+};
+use crate::helpers::object_utils::total_ray_count;
+use crate::objects::behavior::{Drawable, ObjectParam, RaytracerObjects};
+use crate::objects::emitters::{Emitters, VariableRays};
+use macroquad::input::{KeyCode, clear_input_queue, get_char_pressed, is_key_pressed};
+use macroquad::shapes::draw_rectangle;
+use macroquad::text::draw_text;
+
+/// The parameters of an object that can be set through the numeric entry
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NumericField {
+    /// The radius of any object (in pixels)
+    Radius,
+    /// The orientation of a collimated, spotlight, or convergent emitter (in degrees)
+    Orientation,
+    /// The beam diameter of a collimated emitter (in pixels)
+    BeamDiameter,
+    /// The beam angle of a spotlight emitter (in degrees)
+    BeamAngle,
+    /// The number of rays of an emitter
+    RayCount,
+    /// The thickness of the rays of an emitter (in pixels)
+    RayWidth,
+    /// The range of an emitter's rays (in pixels), 0 for no limit
+    Range,
+}
+
+impl NumericField {
+    /// Gets the label displayed in the overlay for this field
+    pub fn label(&self) -> &'static str {
+        match self {
+            NumericField::Radius => "Radius (px)",
+            NumericField::Orientation => "Orientation (deg)",
+            NumericField::BeamDiameter => "Beam diameter (px)",
+            NumericField::BeamAngle => "Beam angle (deg)",
+            NumericField::RayCount => "Ray count",
+            NumericField::RayWidth => "Ray width (px)",
+            NumericField::Range => "Range (px, 0 = none)",
+        }
+    }
+
+    /// Gets the `ObjectParam` behind this field, with a typed value converted
+    /// to the units `RaytracerObjects::set_param()` takes
+    ///
+    /// # Returns
+    ///
+    /// The parameter and the value in its units, or `None` for the emitter
+    /// fields that are not an `ObjectParam`
+    pub fn param_value(&self, value: f32) -> Option<(ObjectParam, f32)> {
+        match self {
+            NumericField::Radius => Some((ObjectParam::Radius, value)),
+            NumericField::Orientation => Some((ObjectParam::Orientation, value.to_radians())),
+            NumericField::BeamDiameter => Some((ObjectParam::BeamDiameter, value)),
+            NumericField::BeamAngle => Some((ObjectParam::BeamAngle, value.to_radians())),
+            NumericField::RayCount | NumericField::RayWidth | NumericField::Range => None,
+        }
+    }
+
+    /// Gets all the fields that can be set for the given object
+    ///
+    /// Every object has a radius, every emitter has a ray count, and only the
+    /// oriented emitters have an orientation and their secondary trait.
+    pub fn fields_for(object: &RaytracerObjects) -> Vec<NumericField> {
+        match object {
+            RaytracerObjects::ObjectCircle(_) | RaytracerObjects::Absorbers(_) => {
+                vec![NumericField::Radius]
+            }
+            RaytracerObjects::Emitters(Emitters::EmitterIsotropic(_)) => {
+                vec![
+                    NumericField::Radius,
+                    NumericField::RayCount,
+                    NumericField::RayWidth,
+                    NumericField::Range,
+                ]
+            }
+            RaytracerObjects::Emitters(Emitters::EmitterCollimated(_)) => vec![
+                NumericField::Radius,
+                NumericField::Orientation,
+                NumericField::BeamDiameter,
+                NumericField::RayCount,
+                NumericField::RayWidth,
+                NumericField::Range,
+            ],
+            RaytracerObjects::Emitters(Emitters::EmitterTargeted(_)) => vec![
+                NumericField::Radius,
+                NumericField::RayWidth,
+                NumericField::Range,
+            ],
+            RaytracerObjects::Emitters(Emitters::EmitterConvergent(_)) => vec![
+                NumericField::Radius,
+                NumericField::Orientation,
+                NumericField::RayCount,
+                NumericField::RayWidth,
+                NumericField::Range,
+            ],
+            RaytracerObjects::Emitters(Emitters::EmitterSpotlight(_)) => vec![
+                NumericField::Radius,
+                NumericField::Orientation,
+                NumericField::BeamAngle,
+                NumericField::RayCount,
+                NumericField::RayWidth,
+                NumericField::Range,
+            ],
+        }
+    }
+}
+
+/// The result of capturing a frame of user input for the numeric entry
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NumericEntryState {
+    /// The user is still typing
+    Editing,
+    /// The user pressed Enter to apply the value
+    Submitted,
+    /// The user pressed Escape to close the overlay
+    Cancelled,
+}
+
+/// A text-input overlay for typing an exact value for an object parameter
+#[derive(Clone, Debug)]
+pub struct NumericEntry {
+    /// The index of the target object in `OBJ_COLLECTION`
+    pub target_index: usize,
+    /// The fields that can be set for the target object
+    pub fields: Vec<NumericField>,
+    /// The index of the currently selected field in `fields`
+    pub field_index: usize,
+    /// The text typed so far
+    pub buffer: String,
+}
+
+impl NumericEntry {
+    /// Opens a numeric entry for the object at the given index
+    ///
+    /// Any characters typed before the overlay was opened (such as the key
+    /// used to open it) are discarded.
+    ///
+    /// # Returns
+    ///
+    /// * `Some(NumericEntry)` - If there is an object at the given index
+    /// * `None` - If the index is out of bounds
+    pub fn open(target_index: usize) -> Option<NumericEntry> {
+        let collection = OBJ_COLLECTION.read().unwrap();
+        let fields = NumericField::fields_for(collection.get(target_index)?);
+
+        clear_input_queue();
+
+        Some(NumericEntry {
+            target_index,
+            fields,
+            field_index: 0,
+            buffer: String::new(),
+        })
+    }
+
+    /// Gets the currently selected field
+    pub fn field(&self) -> NumericField {
+        self.fields[self.field_index]
+    }
+
+    /// Processes the user's input for this frame
+    ///
+    /// Digits, a single decimal point, and a leading minus sign are appended to
+    /// the buffer, every other character is ignored. Backspace removes the last
+    /// character, Tab cycles to the next field, Enter submits, and Escape cancels.
+    pub fn capture_input(&mut self) -> NumericEntryState {
+        // the char queue is a stack, so reverse it to get the typed order
+        let mut typed = Vec::new();
+        while let Some(c) = get_char_pressed() {
+            typed.push(c);
+        }
+
+        for c in typed.into_iter().rev() {
+            let accept = c.is_ascii_digit()
+                || (c == '.' && !self.buffer.contains('.'))
+                || (c == '-' && self.buffer.is_empty());
+
+            if accept {
+                self.buffer.push(c);
+            }
+        }
+
+        if is_key_pressed(KeyCode::Backspace) {
+            self.buffer.pop();
+        }
+
+        if is_key_pressed(KeyCode::Tab) {
+            self.field_index = (self.field_index + 1) % self.fields.len();
+            self.buffer.clear();
+        }
+
+        if is_key_pressed(KeyCode::Escape) {
+            NumericEntryState::Cancelled
+        } else if is_key_pressed(KeyCode::Enter) || is_key_pressed(KeyCode::KpEnter) {
+            NumericEntryState::Submitted
+        } else {
+            NumericEntryState::Editing
+        }
+    }
+
+    /// Applies the typed value to the target object
+    ///
+    /// The radius, orientation, and beam fields are set with
+    /// `RaytracerObjects::set_param()`, which checks their bounds.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the value was applied, the rays must then be reinitialized
+    /// * `Err(String)` - A message describing why the value was rejected
+    pub fn apply(&self) -> Result<(), String> {
+        let value: f32 = self
+            .buffer
+            .parse()
+            .map_err(|_| format!("'{}' is not a number", self.buffer))?;
+
+        let mut collection = OBJ_COLLECTION.write().unwrap();
+        let scene_total_rays = total_ray_count(&collection);
+        let object = collection
+            .get_mut(self.target_index)
+            .ok_or("the object no longer exists")?;
+        if object.is_locked() {
+            return Err("the object is locked".to_string());
+        }
+
+        if let Some((param, value)) = self.field().param_value(value) {
+            let is_emitter = object.set_param(param, value).map_err(|e| e.to_string())?;
+            if !is_emitter {
+                mark_absorbers_changed();
+            }
+            return Ok(());
+        }
+
+        match (self.field(), object) {
+            (NumericField::RayCount, RaytracerObjects::Emitters(o)) => {
+                if value.fract() != 0.0 {
+                    return Err("ray count must be a whole number".to_string());
+                }
+
+                let ray_count = value as i32;
+                if !(OBJC_MIN_RAY_COUNT..=OBJC_MAX_RAY_COUNT).contains(&ray_count) {
+                    return Err(format!(
+                        "ray count must be between {} and {}",
+                        OBJC_MIN_RAY_COUNT, OBJC_MAX_RAY_COUNT
+                    ));
+                }
+
+                o.change_rays_count(ray_count - o.get_rays_count(), scene_total_rays)
+                    .map_err(|e| e.to_string())?;
+            }
+            (NumericField::RayWidth, RaytracerObjects::Emitters(o)) => {
+                if value <= 0.0 {
+                    return Err("ray width must be greater than 0".to_string());
+                }
+                o.set_ray_width(value);
+            }
+            (NumericField::Range, RaytracerObjects::Emitters(o)) => {
+                if value < 0.0 {
+                    return Err("range cannot be negative".to_string());
+                }
+                o.set_max_length(if value == 0.0 { None } else { Some(value) });
+            }
+            _ => return Err("this object does not have that parameter".to_string()),
+        }
+
+        Ok(())
+    }
+}
+
+impl Drawable for NumericEntry {
+    /// Draws the overlay near the target object
+    fn draw_object(&self) {
+        let (x, y) = match OBJ_COLLECTION.read().unwrap().get(self.target_index) {
+            Some(object) => object.get_pos(),
+            None => return,
+        };
+
+        let text = format!("{}: {}_", self.field().label(), self.buffer);
+        let hint = "[Tab] next field  [Enter] apply  [Esc] cancel";
+
+        draw_rectangle(
+            x,
+            y,
+            NUMENTRY_FONT_SIZE * 16.0,
+            NUMENTRY_FONT_SIZE * 2.5,
+            NUMENTRY_BG_COLOR,
+        );
+        draw_text(
+            &text,
+            x + 4.0,
+            y + NUMENTRY_FONT_SIZE,
+            NUMENTRY_FONT_SIZE,
+            NUMENTRY_TEXT_COLOR,
+        );
+        draw_text(
+            hint,
+            x + 4.0,
+            y + NUMENTRY_FONT_SIZE * 2.0,
+            NUMENTRY_FONT_SIZE * 0.75,
+            NUMENTRY_TEXT_COLOR,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn angle_fields_are_converted_to_radians() {
+        assert_eq!(
+            NumericField::Radius.param_value(40.0),
+            Some((ObjectParam::Radius, 40.0))
+        );
+        assert_eq!(
+            NumericField::BeamAngle.param_value(180.0),
+            Some((ObjectParam::BeamAngle, std::f32::consts::PI))
+        );
+        assert_eq!(NumericField::RayCount.param_value(12.0), None);
+    }
+}